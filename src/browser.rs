@@ -0,0 +1,594 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `browser` contains the full-screen terminal UI used to browse data that is too large to read
+//! by scrolling back through the terminal.
+//!
+//! The data is shown as a tree of [Nodes](struct.Node.html), built by
+//! [BlockData::to_browser](../json_structs/json_blocks/struct.BlockData.html#method.to_browser) or
+//! [StateData::to_browser](../json_structs/json_state/struct.StateData.html#method.to_browser). The
+//! [Browser](struct.Browser.html) has three panes:
+//! - a list of the top level nodes, e.g. the blocks,
+//! - a tree of the nodes inside the selected one, e.g. its batches and their transactions,
+//! - the fields and decoded payload of the selected node.
+//!
+//! | Key                  | Action                                                  |
+//! |----------------------|---------------------------------------------------------|
+//! | `Up`/`k`, `Down`/`j` | Select the previous or next item                        |
+//! | `Home`/`g`, `End`/`G`| Select the first or last item                           |
+//! | `Enter`/`Right`/`l`  | Move into the tree, or expand the selected item         |
+//! | `Left`/`h`           | Collapse the selected item, or move back to its parent  |
+//! | `Tab`                | Switch between the list and the tree                    |
+//! | `PageUp`, `PageDown` | Scroll the details                                      |
+//! | `/`                  | Search the labels, fields and payloads                  |
+//! | `n`, `N`             | Select the next or previous match                       |
+//! | `q`/`Esc`            | Quit                                                    |
+
+extern crate ratatui;
+
+use error::{Error, Result};
+
+use self::ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use self::ratatui::layout::{Constraint, Layout};
+use self::ratatui::style::{Modifier, Style};
+use self::ratatui::text::{Line, Span, Text};
+use self::ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use self::ratatui::{DefaultTerminal, Frame};
+
+use std::collections::HashSet;
+use std::io;
+
+/// How many lines `PageUp` and `PageDown` scroll the details by.
+const PAGE_LINES: u16 = 10;
+
+/// One item shown in the browser, such as a block, batch, transaction or state address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    label: String,
+    fields: Vec<(&'static str, String)>,
+    body: Option<String>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    /// Returns a node shown as `label` in the list or tree. When selected, the details show each of the
+    /// `fields` as `name: value`, followed by the `body`, which is usually the decoded payload.
+    pub fn new(label: String, fields: Vec<(&'static str, String)>, body: Option<String>, children: Vec<Node>) -> Node {
+        Node { label, fields, body, children }
+    }
+
+    /// Returns the label shown in the list or tree.
+    pub fn get_label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the nodes inside this one.
+    pub fn get_children(&self) -> &[Node] {
+        &self.children
+    }
+
+    /// Returns true if the label, a field or the body contains `query`, which must be lowercase.
+    fn matches(&self, query: &str) -> bool {
+        self.label.to_lowercase().contains(query)
+            || self.fields.iter().any(|(_, val)| val.to_lowercase().contains(query))
+            || self.body.as_ref().is_some_and(|body| body.to_lowercase().contains(query))
+    }
+}
+
+/// Which of the panes has the keyboard focus.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Focus {
+    List,
+    Tree,
+}
+
+/// The state of the terminal UI: which node is selected, which are expanded, and the current search.
+#[derive(Debug)]
+pub struct Browser {
+    title: String,
+    nodes: Vec<Node>,
+    focus: Focus,
+    list_state: ListState,
+    tree_state: ListState,
+    /// Paths, relative to the selected top level node, of the tree items that are expanded.
+    expanded: HashSet<Vec<usize>>,
+    detail_scroll: u16,
+    /// The query being typed, while searching.
+    search: Option<String>,
+    last_query: String,
+    status: String,
+}
+
+impl Browser {
+    /// Returns a browser over `nodes`, with the list pane titled `title`.
+    pub fn new(title: &str, nodes: Vec<Node>) -> Browser {
+        let mut list_state = ListState::default();
+        if !nodes.is_empty() {
+            list_state.select(Some(0));
+        }
+        Browser {
+            title: String::from(title),
+            nodes,
+            focus: Focus::List,
+            list_state,
+            tree_state: ListState::default(),
+            expanded: HashSet::new(),
+            detail_scroll: 0,
+            search: None,
+            last_query: String::new(),
+            status: String::new(),
+        }
+    }
+
+    /// Returns the number of top level nodes.
+    pub fn get_num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Takes over the terminal and shows the browser until the user quits.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal can't be set up, drawn to, or read from.
+    pub fn run(mut self) -> Result<()> {
+        let mut terminal = ratatui::try_init().map_err(|error| Error::Terminal { error })?;
+        let result = self.event_loop(&mut terminal);
+        // Give the terminal back even if the browser failed
+        let restored = ratatui::try_restore();
+        result.and(restored).map_err(|error| Error::Terminal { error })
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Updates the browser for a key press. Returns false if the user asked to quit.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if let Some(mut query) = self.search.take() {
+            match key.code {
+                KeyCode::Enter => {
+                    self.last_query = query;
+                    self.find(true);
+                },
+                KeyCode::Esc => self.status.clear(),
+                KeyCode::Backspace => {
+                    query.pop();
+                    self.search = Some(query);
+                },
+                KeyCode::Char(c) => {
+                    query.push(c);
+                    self.search = Some(query);
+                },
+                _ => self.search = Some(query),
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Home | KeyCode::Char('g') => self.move_selection(isize::MIN),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => self.expand(),
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(),
+            KeyCode::Tab | KeyCode::BackTab => match self.focus {
+                Focus::List => self.enter_tree(),
+                Focus::Tree => self.focus = Focus::List,
+            },
+            KeyCode::PageDown => self.detail_scroll = self.detail_scroll.saturating_add(PAGE_LINES),
+            KeyCode::PageUp => self.detail_scroll = self.detail_scroll.saturating_sub(PAGE_LINES),
+            KeyCode::Char('/') => self.search = Some(String::new()),
+            KeyCode::Char('n') => self.find(true),
+            KeyCode::Char('N') => self.find(false),
+            _ => (),
+        }
+        true
+    }
+
+    /// Returns the node shown in the details pane, if there is one.
+    pub fn get_selected(&self) -> Option<&Node> {
+        let top = self.nodes.get(self.list_state.selected()?)?;
+        match self.focus {
+            Focus::List => Some(top),
+            Focus::Tree => {
+                let rows = self.tree_rows();
+                let (path, _) = rows.get(self.tree_state.selected()?)?;
+                node_at(&top.children, path)
+            },
+        }
+    }
+
+    /// Returns the path and depth of each item shown in the tree, in the order they are shown.
+    fn tree_rows(&self) -> Vec<(Vec<usize>, usize)> {
+        let mut rows = Vec::new();
+        if let Some(top) = self.list_state.selected().and_then(|selected| self.nodes.get(selected)) {
+            self.add_tree_rows(&top.children, &mut Vec::new(), &mut rows);
+        }
+        rows
+    }
+
+    fn add_tree_rows(&self, nodes: &[Node], path: &mut Vec<usize>, rows: &mut Vec<(Vec<usize>, usize)>) {
+        for (index, node) in nodes.iter().enumerate() {
+            path.push(index);
+            rows.push((path.clone(), path.len() - 1));
+            if self.expanded.contains(path) {
+                self.add_tree_rows(&node.children, path, rows);
+            }
+            path.pop();
+        }
+    }
+
+    /// Moves the selection of the focused pane by `offset` items, stopping at the first and last item.
+    fn move_selection(&mut self, offset: isize) {
+        let (state, len) = match self.focus {
+            Focus::List => (&mut self.list_state, self.nodes.len()),
+            Focus::Tree => {
+                let len = self.tree_rows().len();
+                (&mut self.tree_state, len)
+            },
+        };
+        if len == 0 {
+            return;
+        }
+        let current = state.selected().unwrap_or(0) as isize;
+        let next = current.saturating_add(offset).max(0).min(len as isize - 1) as usize;
+        state.select(Some(next));
+
+        if self.focus == Focus::List && next as isize != current {
+            self.expanded.clear();
+            self.tree_state.select(None);
+        }
+        self.detail_scroll = 0;
+    }
+
+    /// Moves the focus to the tree, if the selected top level node has anything inside it.
+    fn enter_tree(&mut self) {
+        if self.tree_rows().is_empty() {
+            return;
+        }
+        if self.tree_state.selected().is_none() {
+            self.tree_state.select(Some(0));
+        }
+        self.focus = Focus::Tree;
+        self.detail_scroll = 0;
+    }
+
+    fn expand(&mut self) {
+        match self.focus {
+            Focus::List => self.enter_tree(),
+            Focus::Tree => {
+                let rows = self.tree_rows();
+                if let Some((path, _)) = self.tree_state.selected().and_then(|selected| rows.get(selected)) {
+                    let has_children = self.get_selected().is_some_and(|node| !node.children.is_empty());
+                    if has_children {
+                        self.expanded.insert(path.clone());
+                    }
+                }
+            },
+        }
+    }
+
+    fn collapse(&mut self) {
+        if self.focus == Focus::List {
+            return;
+        }
+        let rows = self.tree_rows();
+        let path = match self.tree_state.selected().and_then(|selected| rows.get(selected)) {
+            Some((path, _)) => path.clone(),
+            None => return,
+        };
+
+        if self.expanded.remove(&path) {
+            // Hide everything that was expanded inside it as well
+            self.expanded.retain(|expanded| !expanded.starts_with(&path));
+        } else if path.len() > 1 {
+            let parent = &path[..path.len() - 1];
+            self.tree_state.select(rows.iter().position(|(row, _)| row.as_slice() == parent));
+        } else {
+            self.focus = Focus::List;
+        }
+        self.detail_scroll = 0;
+    }
+
+    /// Selects the next (or previous) node that matches the last query, expanding the tree to show it.
+    fn find(&mut self, forward: bool) {
+        if self.last_query.is_empty() {
+            return;
+        }
+        let query = self.last_query.to_lowercase();
+
+        let mut paths = Vec::new();
+        add_paths(&self.nodes, &mut Vec::new(), &mut paths);
+        let current = self.selected_path();
+        let start = paths.iter().position(|path| Some(path) == current.as_ref());
+
+        let count = paths.len();
+        let found = (1..=count)
+            .map(|step| match (start, forward) {
+                (Some(start), true) => (start + step) % count,
+                (Some(start), false) => (start + count - step) % count,
+                (None, _) => step - 1,
+            })
+            .find(|&index| node_at(&self.nodes, &paths[index]).is_some_and(|node| node.matches(&query)));
+
+        match found {
+            Some(index) => {
+                self.select_path(&paths[index]);
+                self.status = format!("/{}", self.last_query);
+            },
+            None => self.status = format!("No matches for \"{}\"", self.last_query),
+        }
+    }
+
+    /// Returns the path of the selected node, starting from the top level.
+    fn selected_path(&self) -> Option<Vec<usize>> {
+        let mut path = vec![self.list_state.selected()?];
+        if self.focus == Focus::Tree {
+            let rows = self.tree_rows();
+            let (row, _) = rows.get(self.tree_state.selected()?)?;
+            path.extend(row);
+        }
+        Some(path)
+    }
+
+    /// Selects the node at `path`, starting from the top level.
+    fn select_path(&mut self, path: &[usize]) {
+        if self.list_state.selected() != Some(path[0]) {
+            self.list_state.select(Some(path[0]));
+            self.expanded.clear();
+        }
+        self.detail_scroll = 0;
+
+        if path.len() == 1 {
+            self.focus = Focus::List;
+            return;
+        }
+        let tree_path = &path[1..];
+        for depth in 1..tree_path.len() {
+            self.expanded.insert(tree_path[..depth].to_vec());
+        }
+        self.tree_state.select(self.tree_rows().iter().position(|(row, _)| row.as_slice() == tree_path));
+        self.focus = Focus::Tree;
+    }
+
+    /// Draws the panes and the status line onto `frame`.
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, tree_area, detail_area] = Layout::horizontal([
+            Constraint::Percentage(25),
+            Constraint::Percentage(30),
+            Constraint::Percentage(45),
+        ]).areas(main);
+
+        let highlight = |focus: Focus| match self.focus == focus {
+            true => Style::default().add_modifier(Modifier::REVERSED),
+            false => Style::default().add_modifier(Modifier::BOLD),
+        };
+
+        let list = List::new(self.nodes.iter().map(|node| ListItem::new(node.label.as_str())))
+            .block(Block::bordered().title(self.title.as_str()))
+            .highlight_style(highlight(Focus::List));
+        let tree_highlight = highlight(Focus::Tree);
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        let items: Vec<ListItem> = match self.list_state.selected().and_then(|selected| self.nodes.get(selected)) {
+            Some(top) => self.tree_rows().into_iter().map(|(path, depth)| {
+                let node = node_at(&top.children, &path).expect("tree rows are paths to nodes");
+                let marker = match (node.children.is_empty(), self.expanded.contains(&path)) {
+                    (true, _) => "  ",
+                    (false, true) => "▾ ",
+                    (false, false) => "▸ ",
+                };
+                ListItem::new(format!("{}{}{}", "  ".repeat(depth), marker, node.label))
+            }).collect(),
+            None => Vec::new(),
+        };
+        let tree = List::new(items)
+            .block(Block::bordered().title("Contents"))
+            .highlight_style(tree_highlight);
+        frame.render_stateful_widget(tree, tree_area, &mut self.tree_state);
+
+        let details = Paragraph::new(self.get_selected().map(detail_text).unwrap_or_default())
+            .block(Block::bordered().title("Details"))
+            .wrap(Wrap { trim: false })
+            .scroll((self.detail_scroll, 0));
+        frame.render_widget(details, detail_area);
+
+        let status_line = match self.search {
+            Some(ref query) => format!("/{}", query),
+            None if !self.status.is_empty() => self.status.clone(),
+            None => String::from("q quit  tab switch pane  enter expand  / search  n/N next/previous match"),
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+    }
+}
+
+/// Returns the fields of `node`, one line per value, followed by its body.
+fn detail_text(node: &Node) -> Text<'_> {
+    let mut lines = vec![Line::from(Span::styled(node.label.as_str(), Style::default().add_modifier(Modifier::BOLD))), Line::default()];
+    for (name, val) in node.fields.iter() {
+        let mut values = val.lines();
+        let first = values.next().unwrap_or("");
+        lines.push(Line::from(vec![Span::styled(format!("{}: ", name), Style::default().add_modifier(Modifier::BOLD)), Span::raw(first)]));
+        lines.extend(values.map(|line| Line::from(format!("  {}", line))));
+    }
+    if let Some(ref body) = node.body {
+        lines.push(Line::default());
+        lines.extend(body.lines().map(Line::from));
+    }
+    Text::from(lines)
+}
+
+/// Returns the node at `path` inside `nodes`, where each element of the path is an index into the children.
+fn node_at<'a>(nodes: &'a [Node], path: &[usize]) -> Option<&'a Node> {
+    let (first, rest) = path.split_first()?;
+    let node = nodes.get(*first)?;
+    match rest.is_empty() {
+        true => Some(node),
+        false => node_at(&node.children, rest),
+    }
+}
+
+/// Adds the path of every node inside `nodes` to `paths`, with each parent before its children.
+fn add_paths(nodes: &[Node], path: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>) {
+    for (index, node) in nodes.iter().enumerate() {
+        path.push(index);
+        paths.push(path.clone());
+        add_paths(&node.children, path, paths);
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod test_browser {
+    use super::*;
+    use super::ratatui::Terminal;
+    use super::ratatui::backend::TestBackend;
+    use super::ratatui::crossterm::event::KeyModifiers;
+
+    fn leaf(label: &str, body: &str) -> Node {
+        Node::new(String::from(label), vec![("ID", String::from(label))], Some(String::from(body)), Vec::new())
+    }
+
+    fn example() -> Browser {
+        let batch = |label: &str, txns: Vec<Node>| Node::new(String::from(label), Vec::new(), None, txns);
+        Browser::new("Blocks", vec![
+            Node::new(String::from("Block 2"), vec![("Inputs", String::from("1cf126\n000000"))], None, vec![
+                batch("Batch 0", vec![leaf("txn a", "\"Name\" : \"num1\""), leaf("txn b", "\"Name\" : \"num2\"")]),
+                batch("Batch 1", vec![leaf("txn c", "\"Name\" : \"num3\"")]),
+            ]),
+            Node::new(String::from("Block 1"), Vec::new(), None, vec![batch("Batch 0", vec![leaf("txn d", "\"Name\" : \"target\"")])]),
+            Node::new(String::from("Block 0"), Vec::new(), None, Vec::new()),
+        ])
+    }
+
+    fn press(browser: &mut Browser, codes: &[KeyCode]) -> bool {
+        codes.iter().all(|&code| browser.handle_key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    fn type_text(browser: &mut Browser, text: &str) {
+        for c in text.chars() {
+            press(browser, &[KeyCode::Char(c)]);
+        }
+    }
+
+    fn selected_label(browser: &Browser) -> &str {
+        browser.get_selected().unwrap().get_label()
+    }
+
+    #[test]
+    fn moves_through_the_list() {
+        let mut browser = example();
+        assert_eq!("Block 2", selected_label(&browser));
+        press(&mut browser, &[KeyCode::Down, KeyCode::Char('j')]);
+        assert_eq!("Block 0", selected_label(&browser));
+        press(&mut browser, &[KeyCode::Down]);
+        assert_eq!("Block 0", selected_label(&browser));
+        press(&mut browser, &[KeyCode::Home]);
+        assert_eq!("Block 2", selected_label(&browser));
+    }
+
+    #[test]
+    fn drills_down_and_back_up() {
+        let mut browser = example();
+        press(&mut browser, &[KeyCode::Enter]);
+        assert_eq!("Batch 0", selected_label(&browser));
+        press(&mut browser, &[KeyCode::Right, KeyCode::Down, KeyCode::Down]);
+        assert_eq!("txn b", selected_label(&browser));
+        press(&mut browser, &[KeyCode::Left]);
+        assert_eq!("Batch 0", selected_label(&browser));
+        press(&mut browser, &[KeyCode::Left]);
+        assert_eq!("Batch 0", selected_label(&browser));
+        assert_eq!(2, browser.tree_rows().len());
+        press(&mut browser, &[KeyCode::Left]);
+        assert_eq!("Block 2", selected_label(&browser));
+    }
+
+    #[test]
+    fn changing_block_resets_the_tree() {
+        let mut browser = example();
+        press(&mut browser, &[KeyCode::Enter, KeyCode::Right, KeyCode::Tab, KeyCode::Down, KeyCode::Tab]);
+        assert_eq!("Batch 0", selected_label(&browser));
+        assert_eq!(1, browser.tree_rows().len());
+    }
+
+    #[test]
+    fn leaf_nodes_do_not_expand() {
+        let mut browser = example();
+        press(&mut browser, &[KeyCode::End]);
+        press(&mut browser, &[KeyCode::Enter, KeyCode::Tab]);
+        assert_eq!("Block 0", selected_label(&browser));
+    }
+
+    #[test]
+    fn searches_and_expands_to_the_match() {
+        let mut browser = example();
+        press(&mut browser, &[KeyCode::Char('/')]);
+        type_text(&mut browser, "TARGET");
+        press(&mut browser, &[KeyCode::Enter]);
+        assert_eq!("txn d", selected_label(&browser));
+        assert_eq!(Some(vec![1, 0, 0]), browser.selected_path());
+    }
+
+    #[test]
+    fn cycles_through_matches() {
+        let mut browser = example();
+        press(&mut browser, &[KeyCode::Char('/')]);
+        type_text(&mut browser, "txn");
+        press(&mut browser, &[KeyCode::Enter]);
+        assert_eq!("txn a", selected_label(&browser));
+        press(&mut browser, &[KeyCode::Char('n')]);
+        assert_eq!("txn b", selected_label(&browser));
+        press(&mut browser, &[KeyCode::Char('N'), KeyCode::Char('N')]);
+        assert_eq!("txn d", selected_label(&browser));
+    }
+
+    #[test]
+    fn reports_missing_matches() {
+        let mut browser = example();
+        press(&mut browser, &[KeyCode::Char('/')]);
+        type_text(&mut browser, "nothing");
+        press(&mut browser, &[KeyCode::Enter]);
+        assert_eq!("Block 2", selected_label(&browser));
+        assert_eq!("No matches for \"nothing\"", browser.status);
+    }
+
+    #[test]
+    fn search_keys_are_not_commands() {
+        let mut browser = example();
+        assert!(press(&mut browser, &[KeyCode::Char('/'), KeyCode::Char('q'), KeyCode::Esc]));
+        assert!(!press(&mut browser, &[KeyCode::Char('q')]));
+    }
+
+    #[test]
+    fn draws_the_panes() {
+        let mut browser = example();
+        press(&mut browser, &[KeyCode::Enter, KeyCode::Right, KeyCode::Down]);
+        let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("Blocks"));
+        assert!(screen.contains("▾ Batch 0"));
+        assert!(screen.contains("▸ Batch 1"));
+        assert!(screen.contains("ID: txn a"));
+        assert!(screen.contains("\"Name\" : \"num1\""));
+    }
+
+    #[test]
+    fn multi_line_fields() {
+        let browser = example();
+        let text = detail_text(&browser.nodes[0]);
+        let lines: Vec<String> = text.lines.iter().map(|line| line.to_string()).collect();
+        assert_eq!(vec!["Block 2", "", "Inputs: 1cf126", "  000000"], lines);
+    }
+}
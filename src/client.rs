@@ -0,0 +1,430 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `client` contains the [SawtoothClient](struct.SawtoothClient.html), which requests data from the REST API of a
+//! Hyperledger Sawtooth node. A client is made from the base URL of the REST API, such as `http://localhost:8008`,
+//! and has a method for each endpoint that returns the matching structure from [json_structs](../json_structs/index.html).
+//!
+//! Nodes deployed behind a proxy can be reached by giving the client a timeout, a number of retries, extra headers
+//! and basic authentication. The endpoint readers of [json_reader](../json_reader/index.html) make their requests
+//! through a client too.
+//!
+//! ```no_run
+//! use rusty_saw_view::client::{ListQuery, SawtoothClient};
+//!
+//! let mut client = SawtoothClient::new("http://localhost:8008");
+//! client.set_retries(2);
+//!
+//! let mut query = ListQuery::default();
+//! query.set_limit(10);
+//! let blocks = client.get_blocks(&query).unwrap();
+//! ```
+
+extern crate reqwest;
+extern crate serde;
+
+use self::reqwest::header::Headers;
+use self::serde::de::DeserializeOwned;
+
+use error::{Error, Result};
+use json_reader::{parse_json, with_ids, with_query};
+use json_structs::{Paged};
+use json_structs::json_batch_statuses::{BatchStatusList};
+use json_structs::json_batches::{BatchList, BatchResponse};
+use json_structs::json_blocks::{BlockData, BlockResponse};
+use json_structs::json_receipts::{ReceiptList};
+use json_structs::json_state::{StateData, StateResponse};
+use json_structs::json_transactions::{TransactionList, TransactionResponse};
+
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait before the first retry. Every following retry waits this much longer than the one before.
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// A connection to the REST API of a Sawtooth node.
+///
+/// Every method makes a `GET` request and returns an [Error](../error/enum.Error.html) if the request fails, if the
+/// status code in the response is anything outside of the 200 range, or if the JSON data is malformed. Requests that
+/// fail to be made, or that get a `429` or `5xx` status, are retried as many times as set with
+/// [set_retries](#method.set_retries).
+#[derive(Debug, Clone)]
+pub struct SawtoothClient {
+    base_url: String,
+    http: reqwest::Client,
+    retries: u32,
+    headers: Vec<(String, String)>,
+    basic_auth: Option<(String, Option<String>)>,
+}
+
+impl SawtoothClient {
+    /// Creates a client for the REST API at `base_url`, e.g. `http://localhost:8008`. The client doesn't retry,
+    /// and requests time out after 30 seconds.
+    pub fn new(base_url: &str) -> SawtoothClient {
+        SawtoothClient {
+            base_url: String::from(base_url.trim_end_matches('/')),
+            http: http_client(None),
+            retries: 0,
+            headers: Vec::new(),
+            basic_auth: None,
+        }
+    }
+
+    /// Returns the base URL of the REST API.
+    pub fn get_base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Gives up on a request that takes longer than `timeout`.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.http = http_client(Some(timeout));
+    }
+
+    /// Retries a failed request up to `retries` times.
+    pub fn set_retries(&mut self, retries: u32) {
+        self.retries = retries;
+    }
+
+    /// Sends the header `name: value` with every request, e.g. an API key expected by a proxy.
+    pub fn add_header(&mut self, name: &str, value: &str) {
+        self.headers.push((String::from(name), String::from(value)));
+    }
+
+    /// Authenticates every request with HTTP basic authentication.
+    pub fn set_basic_auth(&mut self, username: &str, password: Option<&str>) {
+        self.basic_auth = Some((String::from(username), password.map(String::from)));
+    }
+
+    /// Reads the blocks from the `/blocks` endpoint, following the pages selected by `query`.
+    pub fn get_blocks(&self, query: &ListQuery) -> Result<BlockData> {
+        self.get_pages("blocks", query)
+    }
+
+    /// Reads the block with the given `id` from the `/blocks/{block_id}` endpoint.
+    pub fn get_block(&self, id: &str) -> Result<BlockResponse> {
+        self.get(&format!("blocks/{}", id))
+    }
+
+    /// Reads the batches from the `/batches` endpoint, following the pages selected by `query`.
+    pub fn get_batches(&self, query: &ListQuery) -> Result<BatchList> {
+        self.get_pages("batches", query)
+    }
+
+    /// Reads the batch with the given `id` from the `/batches/{batch_id}` endpoint.
+    pub fn get_batch(&self, id: &str) -> Result<BatchResponse> {
+        self.get(&format!("batches/{}", id))
+    }
+
+    /// Reads the transactions from the `/transactions` endpoint, following the pages selected by `query`.
+    pub fn get_transactions(&self, query: &ListQuery) -> Result<TransactionList> {
+        self.get_pages("transactions", query)
+    }
+
+    /// Reads the transaction with the given `id` from the `/transactions/{transaction_id}` endpoint.
+    pub fn get_transaction(&self, id: &str) -> Result<TransactionResponse> {
+        self.get(&format!("transactions/{}", id))
+    }
+
+    /// Reads the state from the `/state` endpoint, following the pages selected by `query`.
+    pub fn get_state(&self, query: &ListQuery) -> Result<StateData> {
+        self.get_pages("state", query)
+    }
+
+    /// Reads the data stored at `address` from the `/state/{address}` endpoint.
+    pub fn get_state_address(&self, address: &str) -> Result<StateResponse> {
+        self.get(&format!("state/{}", address))
+    }
+
+    /// Reads the status of the batches with the given `ids` from the `/batch_statuses` endpoint.
+    pub fn get_batch_statuses(&self, ids: &[&str]) -> Result<BatchStatusList> {
+        self.get(&with_ids("batch_statuses", ids))
+    }
+
+    /// Reads the receipts of the transactions with the given `ids` from the `/receipts` endpoint.
+    pub fn get_receipts(&self, ids: &[&str]) -> Result<ReceiptList> {
+        self.get(&with_ids("receipts", ids))
+    }
+
+    /// Reads the JSON at `path`, relative to the base URL, into `T`. An empty `path` reads the base URL itself.
+    pub fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.get_url(&self.url_of(path))
+    }
+
+    /// Reads a list that is split into pages, such as `/blocks` or `/state`, from `path` relative to the base URL.
+    /// The link to the `next` page in the paging of each response is followed until the last page, or until
+    /// the most pages allowed by `query` were read, and the pages are merged into one structure. The paging of the
+    /// merged structure links to the page after the last one that was read, if there is one.
+    ///
+    /// Only the query of each `next` link is used, on the URL of the first page. Sawtooth only changes the query from
+    /// page to page, and a node behind a proxy links to its own host, where the headers and credentials of the client
+    /// must not be sent.
+    pub fn get_pages<T: Paged + DeserializeOwned>(&self, path: &str, query: &ListQuery) -> Result<T> {
+        let first_url = query.add_to(&self.url_of(path));
+        let mut list: T = self.get_url(&first_url)?;
+        let mut num_pages = 1;
+        let mut last_url = first_url.clone();
+
+        while query.max_pages.is_none_or(|max_pages| num_pages < max_pages) {
+            // Stop if a page links to itself, rather than requesting it forever
+            let next_url = match list.get_next_page() {
+                Some(next) => next_page_url(&first_url, next),
+                None => break,
+            };
+            if next_url == last_url {
+                break;
+            }
+            list.append_page(self.get_url(&next_url)?);
+            last_url = next_url;
+            num_pages += 1;
+        }
+        Ok(list)
+    }
+
+    /// Returns the URL of `path`, relative to the base URL.
+    fn url_of(&self, path: &str) -> String {
+        match path.is_empty() {
+            true => self.base_url.clone(),
+            false => format!("{}/{}", self.base_url, path),
+        }
+    }
+
+    /// Reads the JSON at `url` into `T`, retrying the request if it fails for a reason that may go away.
+    fn get_url<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let mut attempt = 0;
+        loop {
+            match self.request(url) {
+                Err(ref error) if attempt < self.retries && error.is_transient() => {
+                    attempt += 1;
+                    thread::sleep(RETRY_DELAY * attempt);
+                },
+                response => return parse_json(&response?, url),
+            }
+        }
+    }
+
+    /// Makes a `GET` request to `url` and returns the body of the response.
+    fn request(&self, url: &str) -> Result<String> {
+        let transport_error = |error| Error::Transport { endpoint: String::from(url), error: Box::new(error) };
+
+        let mut headers = Headers::new();
+        for (name, value) in self.headers.iter() {
+            headers.set_raw(name.clone(), value.clone());
+        }
+        let mut request = self.http.get(url);
+        request.headers(headers);
+        if let Some((ref username, ref password)) = self.basic_auth {
+            request.basic_auth(username.clone(), password.clone());
+        }
+
+        let mut response = request.send().map_err(transport_error)?;
+        if !response.status().is_success() {
+            return Err(Error::HttpStatus { code: response.status().as_u16(), endpoint: String::from(url) });
+        }
+        response.text().map_err(transport_error)
+    }
+}
+
+/// Builds the HTTP client that makes every request of a `SawtoothClient`. Requests time out after `timeout`, or after
+/// 30 seconds by default.
+fn http_client(timeout: Option<Duration>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder();
+    if let Some(timeout) = timeout {
+        builder.timeout(timeout);
+    }
+    // Only fails if the TLS backend can't be set up, which reqwest::Client::new panics on as well
+    builder.build().expect("Failed to set up the TLS backend of the HTTP client")
+}
+
+/// Returns the URL of the page linked to by `next`, which is `first_url` with the query of the link.
+fn next_page_url(first_url: &str, next: &str) -> String {
+    let endpoint = first_url.split('?').next().unwrap_or(first_url);
+    match next.find('?') {
+        Some(start) => format!("{}{}", endpoint, &next[start..]),
+        None => String::from(endpoint),
+    }
+}
+
+/// Selects which part of a list is read from an endpoint that splits its entries into pages, such as `/blocks`.
+/// Each setting is passed to Sawtooth as the query parameter of the same name, except for the number of pages
+/// to read. An empty query reads every entry as of the current chain head.
+#[derive(Debug, Default, Clone)]
+pub struct ListQuery {
+    params: Vec<(&'static str, String)>,
+    max_pages: Option<usize>,
+}
+
+impl ListQuery {
+    /// Asks for `limit` entries on each page.
+    pub fn set_limit(&mut self, limit: u64) {
+        self.set("limit", limit.to_string());
+    }
+
+    /// Starts the list at the entry with this ID or address.
+    pub fn set_start(&mut self, start: &str) {
+        self.set("start", String::from(start));
+    }
+
+    /// Reads the list as of the block with this ID, instead of the current chain head.
+    pub fn set_head(&mut self, head: &str) {
+        self.set("head", String::from(head));
+    }
+
+    /// Only reads the state under addresses starting with `prefix`. Only for the `/state` endpoint.
+    pub fn set_address(&mut self, prefix: &str) {
+        self.set("address", String::from(prefix));
+    }
+
+    /// Only reads `count` blocks. Only for the `/blocks` endpoint.
+    pub fn set_count(&mut self, count: u64) {
+        self.set("count", count.to_string());
+    }
+
+    /// Reads the list from the oldest to the newest entry.
+    pub fn set_reverse(&mut self) {
+        self.set("reverse", String::from("true"));
+    }
+
+    /// Reads at most `max_pages` pages.
+    pub fn set_max_pages(&mut self, max_pages: usize) {
+        self.max_pages = Some(max_pages);
+    }
+
+    /// Sets the query parameter `key`, replacing its previous value.
+    fn set(&mut self, key: &'static str, value: String) {
+        self.params.retain(|&(param, _)| param != key);
+        self.params.push((key, value));
+    }
+
+    /// Returns `url` with the query parameters added.
+    fn add_to(&self, url: &str) -> String {
+        self.params.iter().fold(String::from(url), |url, &(key, ref value)| with_query(&url, key, value))
+    }
+}
+
+#[cfg(test)]
+mod test_client {
+    use super::*;
+    extern crate mockito;
+    use self::mockito::mock;
+
+    const URL: &str = mockito::SERVER_URL;
+
+    #[test]
+    fn base_url_without_trailing_slash() {
+        assert_eq!("http://localhost:8008", SawtoothClient::new("http://localhost:8008/").get_base_url());
+    }
+
+    #[test]
+    fn query_parameters_in_order_set() {
+        let mut query = ListQuery::default();
+        query.set_head("abcd");
+        query.set_address("1cf126");
+        query.set_limit(10);
+        query.set_head("ef01");
+        assert_eq!("http://node/state?address=1cf126&limit=10&head=ef01", query.add_to("http://node/state"));
+    }
+
+    #[test]
+    fn typed_methods_use_endpoint_paths() {
+        let _block = mock("GET", "/blocks/abcd")
+            .with_status(200)
+            .with_body(&::std::fs::read_to_string("example-blockchain/block.json").unwrap())
+            .create();
+        let _statuses = mock("GET", "/batch_statuses?id=aaaa,bbbb")
+            .with_status(200)
+            .with_body("{\"data\": [], \"link\": \"\"}")
+            .create();
+
+        let client = SawtoothClient::new(URL);
+        assert!(client.get_block("abcd").is_ok());
+        assert!(client.get_batch_statuses(&["aaaa", "bbbb"]).is_ok());
+    }
+
+    #[test]
+    fn lists_use_the_query() {
+        let _m = mock("GET", "/blocks?count=2&reverse=true")
+            .with_status(200)
+            .with_body("{\"data\": [], \"head\": \"\", \"link\": \"\", \"paging\": {\"limit\": null, \"start\": null}}")
+            .create();
+
+        let mut query = ListQuery::default();
+        query.set_count(2);
+        query.set_reverse();
+        assert!(SawtoothClient::new(URL).get_blocks(&query).is_ok());
+    }
+
+    #[test]
+    fn sends_headers_and_basic_auth() {
+        let _m = mock("GET", "/receipts?id=cccc")
+            .match_header("x-api-key", "secret")
+            .match_header("authorization", "Basic dXNlcjpwYXNz")
+            .with_status(200)
+            .with_body("{\"data\": [], \"link\": \"\"}")
+            .create();
+
+        let mut client = SawtoothClient::new(URL);
+        client.add_header("X-Api-Key", "secret");
+        client.set_basic_auth("user", Some("pass"));
+        assert!(client.get_receipts(&["cccc"]).is_ok());
+    }
+
+    #[test]
+    fn next_pages_stay_on_the_base_url() {
+        let _first = mock("GET", "/proxy/blocks?limit=1")
+            .match_header("x-api-key", "secret")
+            .with_status(200)
+            .with_body("{\"data\": [], \"head\": \"\", \"link\": \"\", \"paging\": {\"limit\": 1, \"start\": null, \
+                        \"next\": \"http://node.invalid:8008/blocks?limit=1&start=2\"}}")
+            .create();
+        let second = mock("GET", "/proxy/blocks?limit=1&start=2")
+            .match_header("x-api-key", "secret")
+            .with_status(200)
+            .with_body("{\"data\": [], \"head\": \"\", \"link\": \"\", \"paging\": {\"limit\": 1, \"start\": null}}")
+            .create();
+
+        let mut client = SawtoothClient::new(&format!("{}/proxy", URL));
+        client.add_header("X-Api-Key", "secret");
+        let mut query = ListQuery::default();
+        query.set_limit(1);
+        assert!(client.get_blocks(&query).is_ok());
+        second.assert();
+    }
+
+    #[test]
+    fn next_page_url_keeps_only_the_query_of_the_link() {
+        assert_eq!("http://proxy/sawtooth/state?start=2",
+                   next_page_url("http://proxy/sawtooth/state?limit=1", "http://node:8008/state?start=2"));
+        assert_eq!("http://proxy/state", next_page_url("http://proxy/state?start=2", "http://node:8008/state"));
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let m = mock("GET", "/transactions/dddd")
+            .with_status(503)
+            .expect(3)
+            .create();
+
+        let mut client = SawtoothClient::new(URL);
+        client.set_retries(2);
+        match client.get_transaction("dddd") {
+            Err(Error::HttpStatus { code, .. }) => assert_eq!(503, code),
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
+        m.assert();
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        let m = mock("GET", "/batches/eeee")
+            .with_status(404)
+            .expect(1)
+            .create();
+
+        let mut client = SawtoothClient::new(URL);
+        client.set_retries(2);
+        assert!(client.get_batch("eeee").is_err());
+        m.assert();
+    }
+}
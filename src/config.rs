@@ -0,0 +1,134 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `config` contains the [Config](struct.Config.html) structure that is read from the optional JSON config file.
+//!
+//! The config file lets users keep the [decoder rules](../decoder_selection/index.html) for their chain in one place
+//! instead of passing them on the command line every time. An example config file looks like:
+//! ```json
+//! {
+//!     "families": [
+//!         { "family_name": "intkey", "decoder": "cbor" },
+//!         { "family_name": "my_family", "family_version": "2.0", "decoder": "json" }
+//!     ],
+//!     "namespaces": [
+//!         { "prefix": "1cf126", "decoder": "cbor" }
+//!     ]
+//! }
+//! ```
+
+use decoder_selection::{DecoderSelection, FamilyRule, NamespaceRule};
+use error::{Result};
+use json_reader::{read_from_file};
+
+/// A structure that represents the config file.
+#[derive(Deserialize, Debug, Default)]
+pub struct Config {
+    #[serde(default)]
+    families: Vec<FamilyRule>,
+    #[serde(default)]
+    namespaces: Vec<NamespaceRule>,
+}
+
+impl Config {
+    /// Returns the family rules in the order they appear in the file.
+    pub fn get_family_rules(&self) -> &[FamilyRule] {
+        &self.families
+    }
+
+    /// Returns the namespace rules in the order they appear in the file.
+    pub fn get_namespace_rules(&self) -> &[NamespaceRule] {
+        &self.namespaces
+    }
+
+    /// Adds the rules from the config file to `selection`.
+    pub fn apply_to(&self, selection: &mut DecoderSelection) {
+        for rule in self.families.iter() {
+            selection.add_family_rule(rule.clone());
+        }
+        for rule in self.namespaces.iter() {
+            selection.add_namespace_rule(rule.clone());
+        }
+    }
+}
+
+/// Reads a config file that is stored as JSON.
+///
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_config_from_file(filepath: &str) -> Result<Config> {
+    read_from_file(filepath)
+}
+
+#[cfg(test)]
+mod test_config {
+    use super::*;
+
+    extern crate serde_json;
+
+    #[test]
+    fn empty_object_has_no_rules() {
+        let config: Config = serde_json::from_str("{}").unwrap();
+        assert!(config.get_family_rules().is_empty());
+        assert!(config.get_namespace_rules().is_empty());
+    }
+
+    #[test]
+    fn namespace_rules_are_applied_to_selection() {
+        let config: Config = serde_json::from_str("{
+            \"namespaces\": [
+                { \"prefix\": \"abcdef\", \"decoder\": \"json\" }
+            ]
+        }").unwrap();
+        assert_eq!(1, config.get_namespace_rules().len());
+
+        let mut selection = DecoderSelection::new("cbor");
+        config.apply_to(&mut selection);
+        assert_eq!("json", selection.for_address("abcdef0123"));
+        assert_eq!("cbor", selection.for_address("1cf1260123"));
+    }
+
+    #[test]
+    fn rules_are_applied_to_selection() {
+        let config: Config = serde_json::from_str("{
+            \"families\": [
+                { \"family_name\": \"xo\", \"decoder\": \"json\" },
+                { \"family_name\": \"xo\", \"family_version\": \"2.0\", \"decoder\": \"text\" }
+            ]
+        }").unwrap();
+        assert_eq!(2, config.get_family_rules().len());
+
+        let mut selection = DecoderSelection::new("cbor");
+        config.apply_to(&mut selection);
+        assert_eq!("json", selection.for_transaction("xo", "1.0"));
+        assert_eq!("text", selection.for_transaction("xo", "2.0"));
+        assert_eq!("cbor", selection.for_transaction("intkey", "1.0"));
+    }
+
+    #[test]
+    fn rules_are_checked_like_command_line_rules() {
+        let config: Config = serde_json::from_str("{
+            \"namespaces\": [ { \"prefix\": \"1CF126\", \"decoder\": \"json\" } ]
+        }").unwrap();
+        let mut selection = DecoderSelection::new("cbor");
+        config.apply_to(&mut selection);
+        assert_eq!("json", selection.for_address("1cf1260123"));
+
+        let error = serde_json::from_str::<Config>("{ \"namespaces\": [ { \"prefix\": \"intkey\", \"decoder\": \"json\" } ] }")
+            .unwrap_err();
+        assert!(error.to_string().contains("Invalid namespace rule `intkey=json`"));
+        let error = serde_json::from_str::<Config>("{ \"families\": [ { \"family_name\": \"\", \"decoder\": \"json\" } ] }")
+            .unwrap_err();
+        assert!(error.to_string().contains("Invalid family rule `=json`"));
+        assert!(serde_json::from_str::<Config>("{ \"families\": [ { \"family_name\": \"xo\", \"family_version\": \"\", \"decoder\": \"json\" } ] }")
+            .is_err());
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(read_config_from_file("does_not_exist.json").is_err());
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `decoded_value` contains [DecodedValue](enum.DecodedValue.html), the format-neutral tree that every
+//! [decoder](../json_deserialize/trait.PayloadDecoder.html) turns a payload into.
+//!
+//! Keeping the decoded payload as a tree, rather than a string, means that every output format can
+//! present it in its own way. For the terminal, [render](enum.DecodedValue.html#method.render) prints it
+//! with one line per entry, indenting nested maps and arrays:
+//!
+//! ```text
+//! "Name" : "num1"
+//! "Tags" : ["a", "b"]
+//! "Owner" :
+//!     "Key" : 0x02ab
+//! ```
+//!
+//! A `DecodedValue` can also be serialized with serde, e.g. into JSON. Maps become objects, with keys that
+//! aren't text written the same way as `render` writes them, and bytes become `0x` prefixed hex strings.
+
+extern crate serde;
+
+use self::serde::ser::{Serialize, Serializer, SerializeMap, SerializeSeq};
+
+use std::fmt::Write;
+
+/// A decoded payload, or a part of one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    Array(Vec<DecodedValue>),
+    /// Entries in the order they were decoded. Keys are usually `Text`, but some formats
+    /// (e.g. CBOR) allow any value as a key.
+    Map(Vec<(DecodedValue, DecodedValue)>),
+}
+
+impl DecodedValue {
+    /// Returns the value stored under the text key `key`, if this is a map that has one.
+    pub fn get(&self, key: &str) -> Option<&DecodedValue> {
+        match *self {
+            DecodedValue::Map(ref entries) => entries.iter()
+                .find(|(entry_key, _)| entry_key.as_text() == Some(key))
+                .map(|(_, val)| val),
+            _ => None,
+        }
+    }
+
+    /// Returns the string if this is `Text`.
+    pub fn as_text(&self) -> Option<&str> {
+        match *self {
+            DecodedValue::Text(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the value is an array or map.
+    pub fn is_nested(&self) -> bool {
+        matches!(*self, DecodedValue::Array(_) | DecodedValue::Map(_))
+    }
+
+    /// Returns a string with the value pretty printed over one or more lines, each ending in a newline.
+    /// The `tab_padding` arg specifies how many tabs should pad each line of the string, and every level
+    /// of nesting is padded by one more tab.
+    ///
+    /// Maps have one `key : value` line per entry, and arrays have one `- value` line per item. Arrays of
+    /// scalars are kept on a single line, and bytes are shown as hex.
+    pub fn render(&self, tab_padding: u8) -> String {
+        let mut rendered = String::new();
+        self.render_into(&mut rendered, tab_padding as usize);
+        rendered
+    }
+
+    fn render_into(&self, out: &mut String, depth: usize) {
+        let padding: String = (0..depth).map(|_| '\t').collect();
+
+        match *self {
+            DecodedValue::Map(ref entries) if !entries.is_empty() => {
+                for (key, val) in entries {
+                    render_entry(out, &padding, &key.render_inline(), " :", val, depth);
+                }
+            },
+            DecodedValue::Array(ref items) if !self.is_inline() => {
+                for item in items {
+                    render_entry(out, &padding, "", "-", item, depth);
+                }
+            },
+            _ => {
+                let _ = writeln!(out, "{}{}", padding, self.render_inline());
+            },
+        }
+    }
+
+    /// Returns true if the value can be rendered on a single line.
+    fn is_inline(&self) -> bool {
+        match *self {
+            DecodedValue::Array(ref items) => items.iter().all(|item| !item.is_nested()),
+            DecodedValue::Map(ref entries) => entries.is_empty(),
+            _ => true,
+        }
+    }
+
+    /// Renders a value that [is_inline](#method.is_inline) on a single line.
+    pub(crate) fn render_inline(&self) -> String {
+        match *self {
+            DecodedValue::Null => String::from("null"),
+            DecodedValue::Bool(b) => b.to_string(),
+            DecodedValue::Int(n) => n.to_string(),
+            DecodedValue::UInt(n) => n.to_string(),
+            DecodedValue::Float(n) => n.to_string(),
+            DecodedValue::Text(ref s) => format!("{:?}", s),
+            DecodedValue::Bytes(ref bytes) => format!("0x{}", bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()),
+            DecodedValue::Array(ref items) =>
+                format!("[{}]", items.iter().map(|item| item.render_inline()).collect::<Vec<_>>().join(", ")),
+            DecodedValue::Map(ref entries) =>
+                format!("{{{}}}", entries.iter().map(|(key, val)| format!("{} : {}", key.render_inline(), val.render_inline())).collect::<Vec<_>>().join(", ")),
+        }
+    }
+}
+
+/// Renders one map entry or array item. Values that fit on one line follow the `label` and `separator`,
+/// and any other value is rendered on the following lines, one level deeper.
+fn render_entry(out: &mut String, padding: &str, label: &str, separator: &str, val: &DecodedValue, depth: usize) {
+    if val.is_inline() {
+        let _ = writeln!(out, "{}{}{} {}", padding, label, separator, val.render_inline());
+    } else {
+        let _ = writeln!(out, "{}{}{}", padding, label, separator);
+        val.render_into(out, depth + 1);
+    }
+}
+
+impl Serialize for DecodedValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            DecodedValue::Null => serializer.serialize_unit(),
+            DecodedValue::Bool(b) => serializer.serialize_bool(b),
+            DecodedValue::Int(n) => serializer.serialize_i64(n),
+            DecodedValue::UInt(n) => serializer.serialize_u64(n),
+            DecodedValue::Float(n) => serializer.serialize_f64(n),
+            DecodedValue::Text(ref s) => serializer.serialize_str(s),
+            DecodedValue::Bytes(_) => serializer.serialize_str(&self.render_inline()),
+            DecodedValue::Array(ref items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            },
+            DecodedValue::Map(ref entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, val) in entries {
+                    match *key {
+                        DecodedValue::Text(ref key) => map.serialize_entry(key, val)?,
+                        ref key => map.serialize_entry(&key.render_inline(), val)?,
+                    }
+                }
+                map.end()
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a str> for DecodedValue {
+    fn from(s: &'a str) -> DecodedValue {
+        DecodedValue::Text(String::from(s))
+    }
+}
+
+impl From<String> for DecodedValue {
+    fn from(s: String) -> DecodedValue {
+        DecodedValue::Text(s)
+    }
+}
+
+impl From<u64> for DecodedValue {
+    fn from(n: u64) -> DecodedValue {
+        DecodedValue::UInt(n)
+    }
+}
+
+impl From<i64> for DecodedValue {
+    fn from(n: i64) -> DecodedValue {
+        DecodedValue::Int(n)
+    }
+}
+
+impl From<bool> for DecodedValue {
+    fn from(b: bool) -> DecodedValue {
+        DecodedValue::Bool(b)
+    }
+}
+
+#[cfg(test)]
+mod test_render {
+    extern crate serde_json;
+
+    use super::*;
+
+    fn map(entries: Vec<(&str, DecodedValue)>) -> DecodedValue {
+        DecodedValue::Map(entries.into_iter().map(|(key, val)| (DecodedValue::from(key), val)).collect())
+    }
+
+    #[test]
+    fn flat_map() {
+        let val = map(vec![("Name", DecodedValue::from("num1")), ("Value", DecodedValue::from(1u64))]);
+        assert_eq!("\t\"Name\" : \"num1\"\n\t\"Value\" : 1\n", val.render(1));
+    }
+
+    #[test]
+    fn nested_map_is_indented() {
+        let val = map(vec![("Owner", map(vec![("Key", DecodedValue::Bytes(vec![0x02, 0xab]))])), ("Ok", DecodedValue::from(true))]);
+        assert_eq!("\"Owner\" :\n\t\"Key\" : 0x02ab\n\"Ok\" : true\n", val.render(0));
+    }
+
+    #[test]
+    fn array_of_scalars_is_inline() {
+        let val = map(vec![("Tags", DecodedValue::Array(vec![DecodedValue::from("a"), DecodedValue::Int(-1)]))]);
+        assert_eq!("\"Tags\" : [\"a\", -1]\n", val.render(0));
+    }
+
+    #[test]
+    fn array_of_maps_has_an_item_per_line() {
+        let val = DecodedValue::Array(vec![map(vec![("a", DecodedValue::from(1u64))]), DecodedValue::Array(Vec::new())]);
+        assert_eq!("-\n\t\"a\" : 1\n- []\n", val.render(0));
+    }
+
+    #[test]
+    fn scalar() {
+        assert_eq!("\t\"hi\"\n", DecodedValue::from("hi").render(1));
+        assert_eq!("null\n", DecodedValue::Null.render(0));
+    }
+
+    #[test]
+    fn empty_map() {
+        assert_eq!("{}\n", DecodedValue::Map(Vec::new()).render(0));
+        assert_eq!("\"a\" : {}\n", map(vec![("a", DecodedValue::Map(Vec::new()))]).render(0));
+    }
+
+    #[test]
+    fn non_text_keys() {
+        let val = DecodedValue::Map(vec![(DecodedValue::UInt(1), DecodedValue::from("one"))]);
+        assert_eq!("1 : \"one\"\n", val.render(0));
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let val = DecodedValue::Map(vec![
+            (DecodedValue::from("Key"), DecodedValue::Bytes(vec![0x02, 0xab])),
+            (DecodedValue::Int(-1), DecodedValue::Array(vec![DecodedValue::Null, DecodedValue::from(true)])),
+        ]);
+        assert_eq!("{\"Key\":\"0x02ab\",\"-1\":[null,true]}", serde_json::to_string(&val).unwrap());
+    }
+
+    #[test]
+    fn get_by_key() {
+        let val = map(vec![("Name", DecodedValue::from("num1"))]);
+        assert_eq!(Some("num1"), val.get("Name").and_then(DecodedValue::as_text));
+        assert_eq!(None, val.get("Value"));
+        assert_eq!(None, DecodedValue::Null.get("Name"));
+    }
+}
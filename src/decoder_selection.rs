@@ -0,0 +1,477 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `decoder_selection` decides which [decoder](../json_deserialize/trait.PayloadDecoder.html) is used for each payload.
+//!
+//! A Sawtooth node can run several transaction processors at once, and each transaction family can serialize
+//! its payloads differently. A [DecoderSelection](struct.DecoderSelection.html) maps the `family_name` (and optionally
+//! the `family_version`) found in a transaction header to the name of a decoder, and falls back to a default
+//! method for transactions that aren't mapped.
+//!
+//! State addresses don't carry a family name, so entries from the `/state` endpoint are matched by the
+//! namespace prefix of their address instead. A built-in list of [well-known namespaces](constant.WELL_KNOWN_NAMESPACES.html)
+//! covers the transaction families that ship with Sawtooth, and the payloads of the
+//! [well-known families](constant.WELL_KNOWN_FAMILIES.html) with a built-in decoder are decoded without any rules.
+//!
+//! Family rules can be given on the command line as `NAME[:VERSION]=DECODER`, namespace rules as `PREFIX=DECODER`,
+//! or both can be read from a [config file](../config/index.html).
+
+use json_deserialize::{DecoderRegistry};
+
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// Namespaces of well-known Sawtooth transaction families as `(prefix, family name, decoder)`.
+/// The decoder is `None` for families whose state format has no built-in decoder.
+pub const WELL_KNOWN_NAMESPACES: &[(&str, &str, Option<&str>)] = &[
+    ("000000", "sawtooth_settings", Some("sawtooth_settings_state")),
+    ("00001d", "sawtooth_identity", None),
+    ("00b10c", "block_info", None),
+    ("1cf126", "intkey", Some("cbor")),
+    ("332514", "smallbank", None),
+    ("5b7349", "xo", None),
+    ("6a4372", "sawtooth_validator_registry", None),
+    ("a68b06", "seth", None),
+];
+
+/// Well-known Sawtooth transaction families whose payloads have a built-in decoder, as `(family name, decoder)`.
+pub const WELL_KNOWN_FAMILIES: &[(&str, &str)] = &[
+    ("sawtooth_settings", "sawtooth_settings"),
+];
+
+/// Returns the name of the well-known transaction family that owns `address`, if there is one.
+pub fn get_well_known_family(address: &str) -> Option<&'static str> {
+    WELL_KNOWN_NAMESPACES.iter()
+        .find(|&&(prefix, _, _)| address.starts_with(prefix))
+        .map(|&(_, family, _)| family)
+}
+
+/// Maps a transaction family, and optionally one of its versions, to the name of a decoder.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "FamilyRuleFields")]
+pub struct FamilyRule {
+    family_name: String,
+    #[serde(default)]
+    family_version: Option<String>,
+    decoder: String,
+}
+
+impl FamilyRule {
+    /// Returns a rule that decodes transactions of `family_name` with `decoder`. If `family_version` is
+    /// `None` the rule applies to every version of the family.
+    pub fn new(family_name: &str, family_version: Option<&str>, decoder: &str) -> FamilyRule {
+        FamilyRule {
+            family_name: String::from(family_name),
+            family_version: family_version.map(String::from),
+            decoder: String::from(decoder),
+        }
+    }
+
+    /// Returns the name of the decoder this rule selects.
+    pub fn get_decoder(&self) -> &str {
+        &self.decoder
+    }
+
+    /// Returns the rule, or an error naming `rule` if the family name or the decoder is empty, or the version is
+    /// given but empty.
+    fn checked(rule: &str, family_name: &str, family_version: Option<&str>, decoder: &str) -> Result<FamilyRule, String> {
+        if family_name.is_empty() || decoder.is_empty() || family_version == Some("") {
+            return Err(format!("Invalid family rule `{}`, expected NAME[:VERSION]=DECODER", rule));
+        }
+        Ok(FamilyRule::new(family_name, family_version, decoder))
+    }
+}
+
+/// The fields of a [FamilyRule](struct.FamilyRule.html) as written in the config file, before they are checked.
+#[derive(Deserialize)]
+struct FamilyRuleFields {
+    family_name: String,
+    #[serde(default)]
+    family_version: Option<String>,
+    decoder: String,
+}
+
+impl TryFrom<FamilyRuleFields> for FamilyRule {
+    type Error = String;
+
+    fn try_from(fields: FamilyRuleFields) -> Result<FamilyRule, String> {
+        let rule = match fields.family_version {
+            Some(ref version) => format!("{}:{}={}", fields.family_name, version, fields.decoder),
+            None => format!("{}={}", fields.family_name, fields.decoder),
+        };
+        FamilyRule::checked(&rule, &fields.family_name, fields.family_version.as_deref(), &fields.decoder)
+    }
+}
+
+impl FromStr for FamilyRule {
+    type Err = String;
+
+    /// Parses a rule written as `NAME[:VERSION]=DECODER`, e.g. `intkey=cbor` or `xo:1.0=csv`.
+    fn from_str(rule: &str) -> Result<FamilyRule, String> {
+        let mut parts = rule.splitn(2, '=');
+        let family = parts.next().unwrap_or("");
+        let decoder = parts.next().unwrap_or("");
+
+        let mut family_parts = family.splitn(2, ':');
+        let family_name = family_parts.next().unwrap_or("");
+        let family_version = family_parts.next();
+        FamilyRule::checked(rule, family_name, family_version, decoder)
+    }
+}
+
+/// Maps the state addresses that start with a namespace prefix to the name of a decoder.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "NamespaceRuleFields")]
+pub struct NamespaceRule {
+    prefix: String,
+    decoder: String,
+}
+
+impl NamespaceRule {
+    /// Returns a rule that decodes the state at addresses starting with `prefix` with `decoder`.
+    pub fn new(prefix: &str, decoder: &str) -> NamespaceRule {
+        NamespaceRule { prefix: prefix.to_lowercase(), decoder: String::from(decoder) }
+    }
+
+    /// Returns the name of the decoder this rule selects.
+    pub fn get_decoder(&self) -> &str {
+        &self.decoder
+    }
+
+    /// Returns the rule, or an error naming `rule` if the prefix isn't hex of at most 70 characters, or the decoder
+    /// is empty.
+    fn checked(rule: &str, prefix: &str, decoder: &str) -> Result<NamespaceRule, String> {
+        if prefix.is_empty() || prefix.len() > 70 || !prefix.chars().all(|c| c.is_ascii_hexdigit()) || decoder.is_empty() {
+            return Err(format!("Invalid namespace rule `{}`, expected PREFIX=DECODER where PREFIX is hex", rule));
+        }
+        Ok(NamespaceRule::new(prefix, decoder))
+    }
+}
+
+/// The fields of a [NamespaceRule](struct.NamespaceRule.html) as written in the config file, before they are checked.
+#[derive(Deserialize)]
+struct NamespaceRuleFields {
+    prefix: String,
+    decoder: String,
+}
+
+impl TryFrom<NamespaceRuleFields> for NamespaceRule {
+    type Error = String;
+
+    fn try_from(fields: NamespaceRuleFields) -> Result<NamespaceRule, String> {
+        NamespaceRule::checked(&format!("{}={}", fields.prefix, fields.decoder), &fields.prefix, &fields.decoder)
+    }
+}
+
+impl FromStr for NamespaceRule {
+    type Err = String;
+
+    /// Parses a rule written as `PREFIX=DECODER`, e.g. `1cf126=cbor`. The prefix must be hex.
+    fn from_str(rule: &str) -> Result<NamespaceRule, String> {
+        let mut parts = rule.splitn(2, '=');
+        let prefix = parts.next().unwrap_or("");
+        let decoder = parts.next().unwrap_or("");
+        NamespaceRule::checked(rule, prefix, decoder)
+    }
+}
+
+/// Selects the decoder to use for each payload.
+///
+/// Rules are added in groups, e.g. the built-in rules, then the rules of the config file, then the rules passed on the
+/// command line. A rule from a later group always takes precedence, and the most specific rule is only preferred
+/// within a group.
+#[derive(Debug, Clone)]
+pub struct DecoderSelection {
+    default_method: String,
+    group: usize,
+    families: Vec<(usize, FamilyRule)>,
+    namespaces: Vec<(usize, NamespaceRule)>,
+}
+
+impl DecoderSelection {
+    /// Returns a selection that decodes every payload with `default_method`.
+    pub fn new(default_method: &str) -> DecoderSelection {
+        DecoderSelection { default_method: String::from(default_method), group: 0, families: Vec::new(), namespaces: Vec::new() }
+    }
+
+    /// Returns the name of the decoder used for payloads that no rule applies to.
+    pub fn get_default_method(&self) -> &str {
+        &self.default_method
+    }
+
+    /// Starts a new group of rules. Every rule added after this takes precedence over the rules added before it, even
+    /// a rule for the exact `family_version` or a longer prefix.
+    pub fn start_rule_group(&mut self) {
+        self.group += 1;
+    }
+
+    /// Adds a family rule to the current group. Rules added later take precedence over rules added earlier.
+    pub fn add_family_rule(&mut self, rule: FamilyRule) {
+        self.families.push((self.group, rule));
+    }
+
+    /// Adds a namespace rule to the current group. Rules added later take precedence over rules added earlier with
+    /// the same prefix.
+    pub fn add_namespace_rule(&mut self, rule: NamespaceRule) {
+        self.namespaces.push((self.group, rule));
+    }
+
+    /// Adds a family rule for each of the [well-known families](constant.WELL_KNOWN_FAMILIES.html)
+    /// whose decoder is in `registry`. Call this before adding user supplied rules so they take precedence.
+    pub fn add_well_known_family_rules(&mut self, registry: &DecoderRegistry) {
+        for &(family_name, decoder) in WELL_KNOWN_FAMILIES.iter() {
+            if registry.get(decoder).is_some() {
+                self.add_family_rule(FamilyRule::new(family_name, None, decoder));
+            }
+        }
+    }
+
+    /// Adds a namespace rule for each of the [well-known namespaces](constant.WELL_KNOWN_NAMESPACES.html)
+    /// whose decoder is in `registry`. Call this before adding user supplied rules so they take precedence.
+    pub fn add_well_known_namespace_rules(&mut self, registry: &DecoderRegistry) {
+        for &(prefix, _, decoder) in WELL_KNOWN_NAMESPACES.iter() {
+            if let Some(decoder) = decoder.filter(|decoder| registry.get(decoder).is_some()) {
+                self.add_namespace_rule(NamespaceRule::new(prefix, decoder));
+            }
+        }
+    }
+
+    /// Returns the names of every decoder this selection can pick.
+    pub fn decoder_names(&self) -> Vec<&str> {
+        let mut names = vec![self.default_method.as_str()];
+        names.extend(self.families.iter().map(|(_, rule)| rule.decoder.as_str()));
+        names.extend(self.namespaces.iter().map(|(_, rule)| rule.decoder.as_str()));
+        names
+    }
+
+    /// Returns the decoder explicitly mapped to the family, if any. Within the latest group that maps the family,
+    /// a rule for the exact `family_version` is preferred over a rule that applies to every version of the family.
+    pub fn get_family_decoder(&self, family_name: &str, family_version: &str) -> Option<&str> {
+        let applies = |rule: &FamilyRule| rule.family_name == family_name
+            && rule.family_version.as_deref().is_none_or(|version| version == family_version);
+        let group = self.families.iter().filter(|(_, rule)| applies(rule)).map(|&(group, _)| group).max()?;
+        let matching = || self.families.iter().rev()
+            .filter(|&(rule_group, rule)| *rule_group == group && applies(rule))
+            .map(|(_, rule)| rule);
+
+        matching().find(|rule| rule.family_version.is_some())
+            .or_else(|| matching().next())
+            .map(|rule| rule.decoder.as_str())
+    }
+
+    /// Returns the decoder to use for a transaction of the given family. This is the
+    /// [mapped decoder](#method.get_family_decoder) if there is one, otherwise the default method.
+    pub fn for_transaction(&self, family_name: &str, family_version: &str) -> &str {
+        self.get_family_decoder(family_name, family_version).unwrap_or(&self.default_method)
+    }
+
+    /// Returns the decoder explicitly mapped to the namespace of `address`, if any. When several
+    /// prefixes of the latest group that maps the address match, the longest one is used.
+    pub fn get_namespace_decoder(&self, address: &str) -> Option<&str> {
+        let address = address.to_lowercase();
+        let applies = |rule: &NamespaceRule| address.starts_with(rule.prefix.as_str());
+        let group = self.namespaces.iter().filter(|(_, rule)| applies(rule)).map(|&(group, _)| group).max()?;
+        self.namespaces.iter().rev()
+            .filter(|&(rule_group, rule)| *rule_group == group && applies(rule))
+            .map(|(_, rule)| rule)
+            .fold(None, |longest: Option<&NamespaceRule>, rule| match longest {
+                Some(longest) if longest.prefix.len() >= rule.prefix.len() => Some(longest),
+                _ => Some(rule),
+            })
+            .map(|rule| rule.decoder.as_str())
+    }
+
+    /// Returns the decoder to use for the state stored at `address`. This is the
+    /// [mapped decoder](#method.get_namespace_decoder) if there is one, otherwise the default method.
+    pub fn for_address(&self, address: &str) -> &str {
+        self.get_namespace_decoder(address).unwrap_or(&self.default_method)
+    }
+}
+
+#[cfg(test)]
+mod test_family_rule {
+    use super::*;
+
+    #[test]
+    fn parse_name_only() {
+        assert_eq!(FamilyRule::new("intkey", None, "cbor"), "intkey=cbor".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_name_and_version() {
+        assert_eq!(FamilyRule::new("xo", Some("1.0"), "json"), "xo:1.0=json".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_missing_decoder() {
+        assert!("intkey".parse::<FamilyRule>().is_err());
+        assert!("intkey=".parse::<FamilyRule>().is_err());
+    }
+
+    #[test]
+    fn parse_missing_name() {
+        assert!("=cbor".parse::<FamilyRule>().is_err());
+        assert!(":1.0=cbor".parse::<FamilyRule>().is_err());
+    }
+
+    #[test]
+    fn parse_empty_version() {
+        assert!("intkey:=cbor".parse::<FamilyRule>().is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_namespace_rule {
+    use super::*;
+
+    #[test]
+    fn parse_prefix() {
+        assert_eq!(NamespaceRule::new("1cf126", "cbor"), "1cf126=cbor".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_is_case_insensitive() {
+        assert_eq!(NamespaceRule::new("1cf126", "cbor"), "1CF126=cbor".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_non_hex_prefix() {
+        assert!("intkey=cbor".parse::<NamespaceRule>().is_err());
+    }
+
+    #[test]
+    fn parse_missing_parts() {
+        assert!("1cf126".parse::<NamespaceRule>().is_err());
+        assert!("=cbor".parse::<NamespaceRule>().is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_well_known_namespaces {
+    use super::*;
+
+    #[test]
+    fn intkey_address_is_known() {
+        let address = "1cf1266258bf86bf61f911799bbfee3c2796a89c255d59a7f3371f91876980d5da0e0c";
+        assert_eq!(Some("intkey"), get_well_known_family(address));
+    }
+
+    #[test]
+    fn unknown_address() {
+        assert_eq!(None, get_well_known_family("abcdef"));
+    }
+
+    #[test]
+    fn only_registered_decoders_are_added() {
+        let mut selection = DecoderSelection::new("json");
+        selection.add_well_known_namespace_rules(&DecoderRegistry::default());
+        assert_eq!(vec!["json", "sawtooth_settings_state", "cbor"], selection.decoder_names());
+        assert_eq!("cbor", selection.for_address("1cf1266258bf86bf61f911799bbfee3c2796a89c255d59a7f3371f91876980d5da0e0c"));
+
+        let mut selection = DecoderSelection::new("json");
+        selection.add_well_known_namespace_rules(&DecoderRegistry::new());
+        assert_eq!(vec!["json"], selection.decoder_names());
+    }
+
+    #[test]
+    fn settings_family_is_known() {
+        let mut selection = DecoderSelection::new("cbor");
+        selection.add_well_known_family_rules(&DecoderRegistry::default());
+        assert_eq!(Some("sawtooth_settings"), selection.get_family_decoder("sawtooth_settings", "1.0"));
+        assert_eq!("cbor", selection.for_transaction("intkey", "1.0"));
+    }
+}
+
+#[cfg(test)]
+mod test_decoder_selection {
+    use super::*;
+
+    #[test]
+    fn unmapped_family_uses_default() {
+        let selection = DecoderSelection::new("cbor");
+        assert_eq!("cbor", selection.for_transaction("intkey", "1.0"));
+        assert_eq!(None, selection.get_family_decoder("intkey", "1.0"));
+    }
+
+    #[test]
+    fn mapped_family_uses_rule() {
+        let mut selection = DecoderSelection::new("cbor");
+        selection.add_family_rule(FamilyRule::new("xo", None, "json"));
+        assert_eq!("json", selection.for_transaction("xo", "1.0"));
+        assert_eq!("cbor", selection.for_transaction("intkey", "1.0"));
+    }
+
+    #[test]
+    fn exact_version_is_preferred() {
+        let mut selection = DecoderSelection::new("cbor");
+        selection.add_family_rule(FamilyRule::new("xo", Some("2.0"), "protobuf"));
+        selection.add_family_rule(FamilyRule::new("xo", None, "json"));
+        assert_eq!("protobuf", selection.for_transaction("xo", "2.0"));
+        assert_eq!("json", selection.for_transaction("xo", "1.0"));
+    }
+
+    #[test]
+    fn later_group_takes_precedence_over_exact_version() {
+        let mut selection = DecoderSelection::new("cbor");
+        selection.add_family_rule(FamilyRule::new("intkey", Some("1.0"), "json"));
+        selection.start_rule_group();
+        selection.add_family_rule(FamilyRule::new("intkey", None, "text"));
+        assert_eq!("text", selection.for_transaction("intkey", "1.0"));
+
+        // Within a group the exact version is still preferred
+        selection.add_family_rule(FamilyRule::new("intkey", Some("1.0"), "protobuf"));
+        selection.add_family_rule(FamilyRule::new("intkey", None, "json"));
+        assert_eq!("protobuf", selection.for_transaction("intkey", "1.0"));
+        assert_eq!("json", selection.for_transaction("intkey", "2.0"));
+    }
+
+    #[test]
+    fn later_group_takes_precedence_over_longer_prefix() {
+        let mut selection = DecoderSelection::new("cbor");
+        selection.add_namespace_rule(NamespaceRule::new("1cf12601", "json"));
+        selection.start_rule_group();
+        selection.add_namespace_rule(NamespaceRule::new("1cf126", "text"));
+        assert_eq!("text", selection.for_address("1cf1260123"));
+    }
+
+    #[test]
+    fn later_rules_take_precedence() {
+        let mut selection = DecoderSelection::new("cbor");
+        selection.add_family_rule(FamilyRule::new("xo", None, "json"));
+        selection.add_family_rule(FamilyRule::new("xo", None, "text"));
+        assert_eq!("text", selection.for_transaction("xo", "1.0"));
+    }
+
+    #[test]
+    fn unmapped_namespace_uses_default() {
+        let selection = DecoderSelection::new("cbor");
+        assert_eq!("cbor", selection.for_address("000000a87cb5"));
+        assert_eq!(None, selection.get_namespace_decoder("000000a87cb5"));
+    }
+
+    #[test]
+    fn longest_prefix_is_preferred() {
+        let mut selection = DecoderSelection::new("cbor");
+        selection.add_namespace_rule(NamespaceRule::new("abcdef01", "text"));
+        selection.add_namespace_rule(NamespaceRule::new("abcdef", "json"));
+        assert_eq!("text", selection.for_address("abcdef0123"));
+        assert_eq!("json", selection.for_address("abcdef9999"));
+    }
+
+    #[test]
+    fn later_namespace_rules_take_precedence() {
+        let mut selection = DecoderSelection::new("cbor");
+        selection.add_namespace_rule(NamespaceRule::new("abcdef", "json"));
+        selection.add_namespace_rule(NamespaceRule::new("ABCDEF", "text"));
+        assert_eq!("text", selection.for_address("abcdef0123"));
+    }
+
+    #[test]
+    fn decoder_names_include_default_and_rules() {
+        let mut selection = DecoderSelection::new("cbor");
+        selection.add_family_rule(FamilyRule::new("xo", None, "json"));
+        assert_eq!(vec!["cbor", "json"], selection.decoder_names());
+    }
+}
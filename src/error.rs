@@ -0,0 +1,196 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `error` contains the [Error](enum.Error.html) type that is returned by the fallible methods of this crate,
+//! such as the readers found in [json_reader](../json_reader/index.html).
+//!
+//! Each variant describes one way that reading blockchain data can fail, and carries enough
+//! information (file path, endpoint, status code, JSON path) to tell the user what went wrong
+//! without needing a backtrace.
+//!
+//! Failing to decode a single payload is reported separately with a [DecodeError](enum.DecodeError.html),
+//! since one bad transaction shouldn't stop the rest of the chain from being displayed.
+
+extern crate base64;
+extern crate reqwest;
+extern crate serde_json;
+
+use std::error;
+use std::fmt;
+use std::io;
+
+/// A `Result` alias where the `Err` case is an [Error](enum.Error.html).
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+/// The different errors that can occur when reading blockchain data.
+#[derive(Debug)]
+pub enum Error {
+    /// The file at `path` does not exist.
+    FileNotFound { path: String },
+    /// The file at `path` exists, but couldn't be opened or read.
+    Io { path: String, error: io::Error },
+    /// The endpoint responded with a status code outside of the 200 range.
+    HttpStatus { code: u16, endpoint: String },
+    /// The request to the endpoint couldn't be made, or its response couldn't be received.
+    Transport { endpoint: String, error: Box<reqwest::Error> },
+    /// The JSON read from `location` doesn't match the structure that was expected.
+    /// `path` is the serde path to the element that failed to parse (e.g. `data[0].header.block_num`).
+    JsonSchema { location: String, path: String, error: serde_json::Error },
+    /// The configuration given on the command line or in the config file is invalid.
+    Config { reason: String },
+    /// The protobuf schema at `path` isn't a valid `FileDescriptorSet` or `.proto` file.
+    ProtoSchema { path: String, reason: String },
+    /// The terminal couldn't be set up, drawn to, or read from by the interactive browser.
+    Terminal { error: io::Error },
+    /// The blocks failed verification with this many `problems`.
+    Verification { problems: usize },
+    /// The state replayed from the blocks differs from the state it was compared with in this many places.
+    Divergence { differences: usize },
+}
+
+impl Error {
+    /// Returns the exit code that the application should exit with when this error occurs.
+    /// The codes follow the conventions of `sysexits.h`.
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            Error::FileNotFound { .. } => 66,   // EX_NOINPUT
+            Error::Io { .. } => 74,             // EX_IOERR
+            Error::HttpStatus { .. } => 69,     // EX_UNAVAILABLE
+            Error::Transport { .. } => 68,      // EX_NOHOST
+            Error::JsonSchema { .. } => 65,     // EX_DATAERR
+            Error::Config { .. } => 78,         // EX_CONFIG
+            Error::ProtoSchema { .. } => 65,    // EX_DATAERR
+            Error::Terminal { .. } => 74,       // EX_IOERR
+            Error::Verification { .. } => 65,   // EX_DATAERR
+            Error::Divergence { .. } => 65,     // EX_DATAERR
+        }
+    }
+
+    /// Returns true if the request that failed with this error may succeed if it is made again, because it failed
+    /// to be made, or got a `429` or `5xx` status.
+    pub fn is_transient(&self) -> bool {
+        match *self {
+            Error::Transport { .. } => true,
+            Error::HttpStatus { code, .. } => code == 429 || code >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::FileNotFound { ref path } =>
+                write!(f, "File not found: {}", path),
+            Error::Io { ref path, ref error } =>
+                write!(f, "Unable to read file {}: {}", path, error),
+            Error::HttpStatus { code, ref endpoint } if code >= 400 =>
+                write!(f, "Error code {} when trying to get {}", code, endpoint),
+            Error::HttpStatus { code, ref endpoint } =>
+                write!(f, "Unexpected code {} when trying to get {}", code, endpoint),
+            Error::Transport { ref endpoint, ref error } =>
+                write!(f, "Unable to make GET request to {}: {}", endpoint, error),
+            Error::JsonSchema { ref location, ref path, ref error } =>
+                write!(f, "Unexpected JSON structure in {} at `{}`: {}", location, path, error),
+            Error::Config { ref reason } =>
+                write!(f, "Invalid configuration: {}", reason),
+            Error::ProtoSchema { ref path, ref reason } =>
+                write!(f, "Unable to load protobuf schema from {}: {}", path, reason),
+            Error::Terminal { ref error } =>
+                write!(f, "Unable to use the terminal: {}", error),
+            Error::Verification { problems: 1 } =>
+                write!(f, "The blocks failed verification with 1 problem"),
+            Error::Verification { problems } =>
+                write!(f, "The blocks failed verification with {} problems", problems),
+            Error::Divergence { differences: 1 } =>
+                write!(f, "The replayed state differs from the state in 1 place"),
+            Error::Divergence { differences } =>
+                write!(f, "The replayed state differs from the state in {} places", differences),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io { ref error, .. } | Error::Terminal { ref error } => Some(error),
+            Error::Transport { ref error, .. } => Some(error.as_ref()),
+            Error::JsonSchema { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The different errors that can occur when decoding the payload of a transaction or state address.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The payload isn't valid base64.
+    Base64(base64::DecodeError),
+    /// The decoded bytes couldn't be deserialized with `method`.
+    Deserialize { method: String, reason: String },
+    /// There is no decoder registered under the name `method`.
+    UnknownMethod { method: String },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::Base64(ref error) =>
+                write!(f, "invalid base64: {}", error),
+            DecodeError::Deserialize { ref method, ref reason } =>
+                write!(f, "invalid {}: {}", method, reason),
+            DecodeError::UnknownMethod { ref method } =>
+                write!(f, "no decoder is registered for `{}`", method),
+        }
+    }
+}
+
+impl error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            DecodeError::Base64(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<base64::DecodeError> for DecodeError {
+    fn from(error: base64::DecodeError) -> DecodeError {
+        DecodeError::Base64(error)
+    }
+}
+
+#[cfg(test)]
+mod test_error {
+    use super::*;
+
+    #[test]
+    fn status_400_and_up_is_an_error_code() {
+        let err = Error::HttpStatus { code: 404, endpoint: String::from("/blocks") };
+        assert_eq!("Error code 404 when trying to get /blocks", err.to_string());
+    }
+
+    #[test]
+    fn status_below_400_is_unexpected() {
+        let err = Error::HttpStatus { code: 301, endpoint: String::from("/state") };
+        assert_eq!("Unexpected code 301 when trying to get /state", err.to_string());
+    }
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let not_found = Error::FileNotFound { path: String::from("a.json") };
+        let status = Error::HttpStatus { code: 500, endpoint: String::from("/state") };
+        assert_ne!(not_found.exit_code(), status.exit_code());
+        assert!(not_found.exit_code() != 0 && status.exit_code() != 0);
+    }
+
+    #[test]
+    fn server_errors_are_transient() {
+        assert!(Error::HttpStatus { code: 503, endpoint: String::from("/blocks") }.is_transient());
+        assert!(Error::HttpStatus { code: 429, endpoint: String::from("/blocks") }.is_transient());
+        assert!(!Error::HttpStatus { code: 404, endpoint: String::from("/blocks") }.is_transient());
+        assert!(!Error::Config { reason: String::new() }.is_transient());
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `follower` contains the [BlockFollower](struct.BlockFollower.html), which watches a Sawtooth node for blocks
+//! as they are committed.
+//!
+//! The follower polls the `/blocks` endpoint for the head of the chain, and when the head moves it reads the
+//! blocks that were committed since the last poll. The blocks are returned as a
+//! [BlockData](../json_structs/json_blocks/struct.BlockData.html), so they can be printed with the same methods as
+//! any other blocks.
+//!
+//! ```no_run
+//! use rusty_saw_view::client::{SawtoothClient};
+//! use rusty_saw_view::decoder_selection::{DecoderSelection};
+//! use rusty_saw_view::follower::{BlockFollower};
+//! use rusty_saw_view::json_deserialize::{DecoderRegistry};
+//!
+//! use std::thread;
+//! use std::time::Duration;
+//!
+//! let registry = DecoderRegistry::default();
+//! let decoders = DecoderSelection::new("cbor");
+//! let mut follower = BlockFollower::new(SawtoothClient::new("http://localhost:8008"));
+//! loop {
+//!     follower.poll().unwrap().display_full_data((false, false, &decoders), &registry);
+//!     thread::sleep(Duration::from_secs(2));
+//! }
+//! ```
+
+use client::{ListQuery, SawtoothClient};
+use error::{Result};
+use json_structs::json_blocks::{BlockData};
+
+/// The most blocks Sawtooth returns on each page of the `/blocks` endpoint.
+const PAGE_LIMIT: u64 = 100;
+
+/// Reads the blocks committed to the chain of a node, one poll at a time.
+#[derive(Debug, Clone)]
+pub struct BlockFollower {
+    client: SawtoothClient,
+    /// The number and ID of the block at the head of the chain when it was last polled.
+    last_head: Option<(u64, String)>,
+}
+
+impl BlockFollower {
+    /// Creates a follower that reads the blocks through `client`.
+    pub fn new(client: SawtoothClient) -> BlockFollower {
+        BlockFollower { client, last_head: None }
+    }
+
+    /// Returns the blocks that were committed since the last poll, from the oldest to the newest. The first poll
+    /// returns the block at the head of the chain, and a poll returns no blocks if the head hasn't moved.
+    ///
+    /// If the node switched to a fork without the chain growing, only the new head block is returned. If the poll fails,
+    /// the next poll returns the blocks this one would have.
+    pub fn poll(&mut self) -> Result<BlockData> {
+        let mut query = ListQuery::default();
+        query.set_limit(1);
+        query.set_max_pages(1);
+        let newest = self.client.get_blocks(&query)?;
+
+        let (head_num, head_id) = match newest.get_blocks().first() {
+            Some(block) => (block.get_header().get_block_num().parse().unwrap_or(0), String::from(block.get_header_signature())),
+            None => return Ok(BlockData::default()),
+        };
+        let num_new = match self.last_head {
+            Some((_, ref last_id)) if *last_id == head_id => 0,
+            Some((last_num, _)) if head_num > last_num => head_num - last_num,
+            _ => 1,
+        };
+        let mut blocks = match num_new {
+            0 | 1 => newest.into_blocks(),
+            _ => {
+                // Read from the head that was just found, in case more blocks were committed in the meantime
+                let mut query = ListQuery::default();
+                query.set_head(&head_id);
+                query.set_limit(num_new.min(PAGE_LIMIT));
+                query.set_max_pages(num_new.div_ceil(PAGE_LIMIT) as usize);
+                self.client.get_blocks(&query)?.into_blocks()
+            },
+        };
+        // Only move on once the new blocks were read, so the next poll reads them again if this one failed
+        self.last_head = Some((head_num, head_id));
+        blocks.truncate(num_new as usize);
+        blocks.reverse();
+        Ok(BlockData::from(blocks))
+    }
+}
+
+#[cfg(test)]
+mod test_follower {
+    use super::*;
+    extern crate mockito;
+    use self::mockito::mock;
+
+    const URL: &str = mockito::SERVER_URL;
+
+    /// Returns the JSON of the `/blocks` endpoint, listing blocks with the given numbers and IDs.
+    fn blocks(blocks: &[(u64, &str)]) -> String {
+        let data: Vec<String> = blocks.iter()
+            .map(|&(num, id)| format!("{{\"batches\": [], \"header\": {{\"batch_ids\": [], \"block_num\": \"{}\", \
+                \"consensus\": \"\", \"previous_block_id\": \"\", \"signer_public_key\": \"\", \"state_root_hash\": \"\"}}, \
+                \"header_signature\": \"{}\"}}", num, id))
+            .collect();
+        format!("{{\"data\": [{}], \"head\": \"\", \"link\": \"\", \"paging\": {{\"limit\": null, \"start\": null}}}}", data.join(", "))
+    }
+
+    /// Returns the numbers of the blocks in `data`, in order.
+    fn block_nums(data: &BlockData) -> Vec<&str> {
+        data.get_blocks().iter().map(|block| block.get_header().get_block_num()).collect()
+    }
+
+    #[test]
+    fn follows_new_blocks_from_oldest_to_newest() {
+        let mut follower = BlockFollower::new(SawtoothClient::new(URL));
+
+        let head = mock("GET", "/blocks?limit=1").with_status(200).with_body(&blocks(&[(3, "c3")])).create();
+        assert_eq!(vec!["3"], block_nums(&follower.poll().unwrap()));
+        // The head hasn't moved
+        assert_eq!(0, follower.poll().unwrap().get_num_blocks());
+        drop(head);
+
+        let _head = mock("GET", "/blocks?limit=1").with_status(200).with_body(&blocks(&[(5, "e5")])).create();
+        let _new = mock("GET", "/blocks?head=e5&limit=2")
+            .with_status(200)
+            .with_body(&blocks(&[(5, "e5"), (4, "d4")]))
+            .create();
+        assert_eq!(vec!["4", "5"], block_nums(&follower.poll().unwrap()));
+    }
+
+    #[test]
+    fn empty_chain_has_no_blocks() {
+        let _m = mock("GET", "/empty/blocks?limit=1").with_status(200).with_body(&blocks(&[])).create();
+        let mut follower = BlockFollower::new(SawtoothClient::new(&format!("{}/empty", URL)));
+        assert_eq!(0, follower.poll().unwrap().get_num_blocks());
+    }
+
+    #[test]
+    fn failed_poll_is_read_again() {
+        let mut follower = BlockFollower::new(SawtoothClient::new(&format!("{}/retry", URL)));
+
+        let head = mock("GET", "/retry/blocks?limit=1").with_status(200).with_body(&blocks(&[(1, "a1")])).create();
+        follower.poll().unwrap();
+        drop(head);
+
+        let _head = mock("GET", "/retry/blocks?limit=1").with_status(200).with_body(&blocks(&[(3, "c3")])).create();
+        let failed = mock("GET", "/retry/blocks?head=c3&limit=2").with_status(503).create();
+        assert!(follower.poll().is_err());
+        drop(failed);
+
+        let _new = mock("GET", "/retry/blocks?head=c3&limit=2")
+            .with_status(200)
+            .with_body(&blocks(&[(3, "c3"), (2, "b2")]))
+            .create();
+        assert_eq!(vec!["2", "3"], block_nums(&follower.poll().unwrap()));
+    }
+
+    #[test]
+    fn new_head_of_a_fork() {
+        let mut follower = BlockFollower::new(SawtoothClient::new(&format!("{}/fork", URL)));
+
+        let head = mock("GET", "/fork/blocks?limit=1").with_status(200).with_body(&blocks(&[(7, "a7")])).create();
+        follower.poll().unwrap();
+        drop(head);
+
+        let _head = mock("GET", "/fork/blocks?limit=1").with_status(200).with_body(&blocks(&[(7, "b7")])).create();
+        let data = follower.poll().unwrap();
+        assert_eq!(1, data.get_num_blocks());
+        assert_eq!("b7", data.get_blocks()[0].get_header_signature());
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `history` lists every transaction of a [BlockData](../json_structs/json_blocks/struct.BlockData.html) that
+//! touched a key, from the oldest block to the newest.
+//!
+//! The key is either a 70 character state address, or the name of an `intkey` value, which is looked up at the
+//! [address](../replay/fn.intkey_address.html) the intkey family stores it at. A transaction touched the key if one
+//! of the `inputs` or `outputs` of its header is the address, or a prefix of it. Every transaction is listed with its
+//! block number and its operation. The operation of an intkey transaction is its verb, name and value, and the
+//! intkey transactions are [replayed](../replay/index.html) to give the value each one left. The payload of a
+//! transaction of any other family is decoded with the decoder selected for it.
+//!
+//! ```
+//! use rusty_saw_view::decoder_selection::{DecoderSelection};
+//! use rusty_saw_view::history::{key_history};
+//! use rusty_saw_view::json_deserialize::{DecoderRegistry};
+//! use rusty_saw_view::json_reader::{read_block_data_from_file};
+//!
+//! let blocks = read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+//! let decoders = DecoderSelection::new("cbor");
+//! let history = key_history(&blocks, "num1", (false, &decoders), &DecoderRegistry::default());
+//! assert_eq!(3, history.get_entries().len());
+//! assert_eq!(Some(1), history.get_entries()[2].get_value());
+//! ```
+
+extern crate serde_json;
+
+use colored::*;
+use decoder_selection::{DecoderSelection};
+use json_deserialize::{DecoderRegistry};
+use json_structs::{label_id, paint};
+use json_structs::json_blocks::{BlockData, Transaction, TransactionHeader};
+use json_structs::json_state::{is_address};
+use replay::{IntkeyState, intkey_address, oldest_first};
+
+/// How a transaction used the address of a key, according to the `inputs` and `outputs` of its header.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Access {
+    /// The address is only listed in the inputs, so the transaction could read it but not change it.
+    Read,
+    /// The address is only listed in the outputs.
+    Write,
+    /// The address is listed in both the inputs and the outputs.
+    ReadWrite,
+}
+
+impl Access {
+    /// Returns how `header` used `address`, or `None` if it didn't list the address as an input or output.
+    fn of(header: &TransactionHeader, address: &str) -> Option<Access> {
+        let lists = |entries: &[String]| entries.iter().any(|entry| address.starts_with(entry.as_str()));
+        match (lists(header.get_inputs()), lists(header.get_outputs())) {
+            (true, true) => Some(Access::ReadWrite),
+            (true, false) => Some(Access::Read),
+            (false, true) => Some(Access::Write),
+            (false, false) => None,
+        }
+    }
+
+    /// Returns a short description of the access, e.g. `read & write`.
+    fn describe(self) -> &'static str {
+        match self {
+            Access::Read => "read",
+            Access::Write => "write",
+            Access::ReadWrite => "read & write",
+        }
+    }
+}
+
+/// A transaction that touched the key.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    block_num: String,
+    transaction_id: String,
+    family: String,
+    access: Access,
+    operation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skipped: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Returns the number of the block the transaction is in.
+    pub fn get_block_num(&self) -> &str {
+        &self.block_num
+    }
+
+    /// Returns the ID of the transaction.
+    pub fn get_transaction_id(&self) -> &str {
+        &self.transaction_id
+    }
+
+    /// Returns how the transaction used the address of the key.
+    pub fn get_access(&self) -> Access {
+        self.access
+    }
+
+    /// Returns the operation of the transaction, e.g. `inc num1 1` for intkey, or its decoded payload on one line.
+    pub fn get_operation(&self) -> &str {
+        &self.operation
+    }
+
+    /// Returns the value the intkey transaction left, or `None` for other families and skipped transactions.
+    pub fn get_value(&self) -> Option<u64> {
+        self.value
+    }
+
+    /// Returns why the intkey transaction processor would have rejected the transaction, if it would have.
+    pub fn get_skipped(&self) -> Option<&str> {
+        self.skipped.as_deref()
+    }
+}
+
+/// Every transaction that touched a key, from the oldest block to the newest.
+#[derive(Serialize, Debug)]
+pub struct History {
+    key: String,
+    address: String,
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Returns the state address of the key.
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+
+    /// Returns the transactions that touched the key, from the oldest block to the newest.
+    pub fn get_entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Display every transaction that touched the key, followed by a summary.
+    /// Uses colored text so use this for terminal printing.
+    ///
+    /// Setting `full_id` to false will only print the first 6 and last 4 characters of addresses and IDs, otherwise
+    /// the full string is shown.
+    pub fn display(&self, full_id: bool) {
+        print!("{}", self.format(full_id, true));
+    }
+
+    /// Display every transaction that touched the key, followed by a summary.
+    /// No terminal color, so use this for piping to a file.
+    pub fn display_no_color(&self, full_id: bool) {
+        print!("{}", self.format(full_id, false));
+    }
+
+    /// Returns the history as pretty printed JSON, with the `key`, its `address` and one entry for each transaction.
+    /// An entry has a `value` if it's an intkey transaction that was applied, and the reason it was `skipped` if
+    /// the intkey transaction processor would have rejected it.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a history can always be written as JSON")
+    }
+
+    fn format(&self, full_id: bool, color: bool) -> String {
+        let mut out = match is_address(&self.key) {
+            true => format!("|History of {}\n", label_id(&self.address, full_id)),
+            false => format!("|History of {} ({})\n", self.key, label_id(&self.address, full_id)),
+        };
+        for entry in self.entries.iter() {
+            out.push_str(&format!("|Block {}, Transaction {}\n", entry.block_num, label_id(&entry.transaction_id, full_id)));
+            out.push_str(&format!("| Family: {}\n", entry.family));
+            out.push_str(&format!("| Access: {}\n", entry.access.describe()));
+            out.push_str(&format!("| Operation: {}\n", entry.operation));
+            if let Some(value) = entry.value {
+                out.push_str(&format!("| Value: {}\n", paint(&value.to_string(), Color::Green, color)));
+            }
+            if let Some(ref reason) = entry.skipped {
+                out.push_str(&format!("| {}\n", paint(&format!("Skipped: {}", reason), Color::Yellow, color)));
+            }
+        }
+        match self.entries.len() {
+            1 => out.push_str(&format!("Found 1 transaction that touched {}\n", self.key)),
+            count => out.push_str(&format!("Found {} transactions that touched {}\n", count, self.key)),
+        }
+        out
+    }
+}
+
+/// Returns every transaction of `data` that touched `key`, which is a 70 character state address or the name of an
+/// intkey value.
+///
+/// The function takes in a tuple (show_genesis, decoders) of settings:
+/// - `show_genesis` -> Setting this to false will omit the transactions of the genesis block.
+/// - `decoders` -> Which [decoder](../decoder_selection/struct.DecoderSelection.html) to use for the payload of each
+///   transaction that isn't intkey, based on its family name and version.
+pub fn key_history(data: &BlockData, key: &str, (show_genesis, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry) -> History {
+    let address = match is_address(key) {
+        true => key.to_ascii_lowercase(),
+        false => intkey_address(key),
+    };
+
+    // Every intkey transaction is replayed, since the value of a key depends on the ones before it
+    let mut state = IntkeyState::default();
+    let mut entries = Vec::new();
+    for block in oldest_first(data) {
+        let block_num = block.get_header().get_block_num();
+        let in_genesis = block_num == "0";
+        for txn in block.get_batches().iter().flat_map(|batch| batch.get_transactions()) {
+            let header = txn.get_header();
+            let change = match header.get_family_name() {
+                "intkey" => Some(state.apply(txn.get_payload())),
+                _ => None,
+            };
+            let access = match Access::of(header, &address) {
+                Some(access) if show_genesis || !in_genesis => access,
+                _ => continue,
+            };
+
+            let (operation, value, skipped) = match change {
+                Some(Ok(change)) => (format!("{} {} {}", change.verb, change.name, change.operand), Some(change.value), None),
+                Some(Err(reason)) => (describe_payload(txn, in_genesis, decoders, registry), None, Some(reason)),
+                None => (describe_payload(txn, in_genesis, decoders, registry), None, None),
+            };
+            entries.push(HistoryEntry {
+                block_num: String::from(block_num),
+                transaction_id: String::from(txn.get_header_signature()),
+                family: format!("{} {}", header.get_family_name(), header.get_family_version()),
+                access,
+                operation,
+                value,
+                skipped,
+            });
+        }
+    }
+    History { key: String::from(key), address, entries }
+}
+
+/// Returns the payload of `txn` decoded on a single line, or in base64 if no decoder is selected for it.
+fn describe_payload(txn: &Transaction, in_genesis: bool, decoders: &DecoderSelection, registry: &DecoderRegistry) -> String {
+    match txn.get_decoder(in_genesis, decoders) {
+        Some(method) => match registry.decode(method, txn.get_payload()) {
+            Ok(value) => value.render_inline(),
+            Err(error) => format!("payload can't be decoded: {}", error),
+        },
+        None => String::from(txn.get_payload()),
+    }
+}
+
+#[cfg(test)]
+mod test_history {
+    use super::*;
+    use super::serde_json::Value;
+
+    use json_reader::{read_block_data_from_file};
+
+    fn example() -> BlockData {
+        read_block_data_from_file("example-blockchain/blocks.json").unwrap()
+    }
+
+    fn history(data: &BlockData, key: &str) -> History {
+        key_history(data, key, (false, &DecoderSelection::new("cbor")), &DecoderRegistry::default())
+    }
+
+    #[test]
+    fn intkey_name() {
+        let history = history(&example(), "num1");
+        assert_eq!(intkey_address("num1"), history.get_address());
+        let steps: Vec<(&str, &str, Option<u64>)> = history.get_entries().iter()
+            .map(|entry| (entry.get_block_num(), entry.get_operation(), entry.get_value()))
+            .collect();
+        assert_eq!(vec![("1", "set num1 2", Some(2)), ("4", "inc num1 1", Some(3)), ("5", "dec num1 2", Some(1))], steps);
+        assert!(history.get_entries().iter().all(|entry| entry.get_access() == Access::ReadWrite));
+
+        let text = history.format(false, false);
+        assert!(text.starts_with("|History of num1 (1cf126...0e0c)\n|Block 1, Transaction "));
+        assert!(text.contains("| Family: intkey 1.0\n| Access: read & write\n| Operation: inc num1 1\n| Value: 3\n"));
+        assert!(text.ends_with("Found 3 transactions that touched num1\n"));
+    }
+
+    #[test]
+    fn state_address() {
+        let data = example();
+        let by_name = history(&data, "num2");
+        let by_address = history(&data, &intkey_address("num2").to_uppercase());
+        assert_eq!(by_name.get_entries(), by_address.get_entries());
+        assert_eq!(Some(12), by_address.get_entries().last().unwrap().get_value());
+        assert!(by_address.format(false, false).starts_with("|History of 1cf126...fcab\n"));
+    }
+
+    #[test]
+    fn untouched_key() {
+        let history = history(&example(), "num3");
+        assert!(history.get_entries().is_empty());
+        assert_eq!(format!("|History of num3 ({})\nFound 0 transactions that touched num3\n", label_id(&intkey_address("num3"), false)),
+            history.format(false, false));
+    }
+
+    #[test]
+    fn short_transaction_id() {
+        let mut json = serde_json::to_value(example()).unwrap();
+        json["data"][4]["batches"][0]["transactions"][0]["header_signature"] = Value::from("abc");
+        let history = history(&serde_json::from_value(json).unwrap(), "num1");
+        assert!(history.format(false, false).contains("|Block 1, Transaction abc\n"));
+    }
+
+    #[test]
+    fn namespace_prefix_and_other_families() {
+        let mut json = serde_json::to_value(example()).unwrap();
+        // Block 2 changes num2, and is turned into a transaction of another family that lists the whole namespace
+        let header = &mut json["data"][3]["batches"][0]["transactions"][0]["header"];
+        header["family_name"] = Value::from("other");
+        header["inputs"] = Value::from(Vec::<String>::new());
+        header["outputs"] = Value::from(vec!["1cf126"]);
+        let history = history(&serde_json::from_value(json).unwrap(), "num1");
+
+        let entry = &history.get_entries()[1];
+        assert_eq!("2", entry.get_block_num());
+        assert_eq!(Access::Write, entry.get_access());
+        assert_eq!("{\"Name\" : \"num2\", \"Value\" : 2, \"Verb\" : \"set\"}", entry.get_operation());
+        assert_eq!(None, entry.get_value());
+        assert_eq!(4, history.get_entries().len());
+    }
+
+    #[test]
+    fn skipped_transactions() {
+        let mut json = serde_json::to_value(example()).unwrap();
+        // Block 1 sets num1, so it's never set if block 1 sets num2 like block 2 does
+        let payload = json["data"][3]["batches"][0]["transactions"][0]["payload"].clone();
+        json["data"][4]["batches"][0]["transactions"][0]["payload"] = payload;
+        let history = history(&serde_json::from_value(json).unwrap(), &intkey_address("num1"));
+
+        let entry = &history.get_entries()[1];
+        assert_eq!(Some("inc of num1, which isn't set"), entry.get_skipped());
+        assert_eq!(None, entry.get_value());
+        let output: Value = serde_json::from_str(&history.to_json()).unwrap();
+        assert_eq!("read_write", output["entries"][1]["access"]);
+        assert_eq!("inc of num1, which isn't set", output["entries"][1]["skipped"]);
+        assert_eq!(Value::Null, output["entries"][1]["value"]);
+    }
+
+    #[test]
+    fn genesis_is_hidden() {
+        let data = example();
+        let settings = data.get_blocks().last().unwrap().get_batches()[0].get_transactions()[0].get_header().get_outputs()[0].clone();
+        assert!(history(&data, &settings).get_entries().is_empty());
+        let shown = key_history(&data, &settings, (true, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert_eq!("0", shown.get_entries()[0].get_block_num());
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `html` contains the building blocks of the HTML reports written by
+//! [BlockData::to_html](../json_structs/json_blocks/struct.BlockData.html#method.to_html) and
+//! [StateData::to_html](../json_structs/json_state/struct.StateData.html#method.to_html).
+//!
+//! A report is a single static page with the styling inlined, so it can be opened straight from disk or
+//! attached to an email without any other files.
+
+use json_deserialize::{DecoderRegistry, describe_decode_error};
+use json_structs::{label_id};
+use signatures::{SignatureStatus};
+
+/// The styling shared by every report.
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }
+details { margin: 0.4em 0 0.4em 1.5em; padding: 0.2em 0.6em; border-left: 3px solid #4a9; }
+details.block { margin-left: 0; border-left-color: #396; }
+summary { cursor: pointer; font-weight: bold; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 0.2em 1em; margin: 0.4em 0; }
+dt { color: #666; }
+dd { margin: 0; font-family: monospace; word-break: break-all; }
+.id { font-family: monospace; color: #838; }
+pre { background: #f4f4f4; padding: 0.6em; overflow-x: auto; }
+pre.payload { color: #24c; }
+pre.error { color: #c22; }
+.invalid { color: #c22; font-weight: bold; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+";
+
+/// Returns `s` with the characters that have a meaning in HTML replaced by entities.
+pub fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns an element showing `id`, with the full ID shown on hover. If `full_id` is false only
+/// the first 6 and last 4 characters are shown on the page.
+pub fn id_span(id: &str, full_id: bool) -> String {
+    format!("<span class=\"id\" title=\"{}\">{}</span>", escape(id), escape(&label_id(id, full_id)))
+}
+
+/// Returns a `<pre>` element with the base64 `payload` decoded by the decoder registered under `method`. If the
+/// payload can't be decoded the element holds the error instead, along with the raw payload.
+pub fn decoded_payload(registry: &DecoderRegistry, method: &str, payload: &str) -> String {
+    match registry.parse(method, payload, 0) {
+        Ok(decoded) => format!("<pre class=\"payload\">{}</pre>", escape(&decoded)),
+        Err(error) => format!("<pre class=\"error\">{}</pre>", escape(&describe_decode_error(payload, &error, 0))),
+    }
+}
+
+/// Returns the `Signature` entry of a definition list, showing the `signature` status, in red unless it's valid.
+pub fn signature_field(signature: SignatureStatus) -> String {
+    match signature.is_valid() {
+        true => format!("<dt>Signature</dt><dd>{}</dd>", signature.describe()),
+        false => format!("<dt>Signature</dt><dd class=\"invalid\">{}</dd>", signature.describe()),
+    }
+}
+
+/// Returns the flag added to the summary of a header whose `signature` isn't valid, or an empty string if it is.
+pub fn invalid_signature_flag(signature: SignatureStatus) -> &'static str {
+    match signature.is_valid() {
+        true => "",
+        false => " <span class=\"invalid\">invalid signature</span>",
+    }
+}
+
+/// Returns a complete HTML document with the given `title`, and `body` as its contents.
+pub fn page(title: &str, body: &str) -> String {
+    format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{style}</style>\n</head>\n<body>\n<h1>{title}</h1>\n{body}</body>\n</html>\n",
+        title = escape(title), style = STYLE, body = body)
+}
+
+#[cfg(test)]
+mod test_html {
+    use super::*;
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!("&lt;a href=&quot;x&quot;&gt;&amp;&#39;", escape("<a href=\"x\">&'"));
+    }
+
+    #[test]
+    fn shortens_ids() {
+        assert_eq!("<span class=\"id\" title=\"0123456789abcdef\">012345...cdef</span>", id_span("0123456789abcdef", false));
+        assert_eq!("<span class=\"id\" title=\"0123456789abcdef\">0123456789abcdef</span>", id_span("0123456789abcdef", true));
+        assert_eq!("<span class=\"id\" title=\"abc\">abc</span>", id_span("abc", false));
+    }
+
+    #[test]
+    fn decodes_payloads() {
+        let registry = DecoderRegistry::default();
+        // {"Name": "a"} in CBOR
+        assert_eq!("<pre class=\"payload\">&quot;Name&quot; : &quot;a&quot;\n</pre>", decoded_payload(&registry, "cbor", "oWROYW1lYWE="));
+        assert!(decoded_payload(&registry, "cbor", "not base64!").starts_with("<pre class=\"error\">"));
+    }
+
+    #[test]
+    fn flags_signatures() {
+        assert_eq!("<dt>Signature</dt><dd>valid</dd>", signature_field(SignatureStatus::Valid));
+        assert_eq!("<dt>Signature</dt><dd class=\"invalid\">malformed public key</dd>", signature_field(SignatureStatus::MalformedKey));
+        assert_eq!("", invalid_signature_flag(SignatureStatus::Valid));
+        assert_eq!(" <span class=\"invalid\">invalid signature</span>", invalid_signature_flag(SignatureStatus::Invalid));
+    }
+
+    #[test]
+    fn page_has_title_and_body() {
+        let html = page("Blocks <1>", "<p>hi</p>\n");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Blocks &lt;1&gt;</title>"));
+        assert!(html.contains("<p>hi</p>\n</body>"));
+    }
+}
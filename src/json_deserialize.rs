@@ -3,98 +3,200 @@
 // Please see the file LICENSE.md in the
 // source distribution of this software for license terms.
 
-//! `json_deserialize` is a collection of methods that help parse payload data stored in [Transaction](../json_structs/json_blocks/struct.Transaction.html)
+//! `json_deserialize` is a collection of decoders that help parse payload data stored in [Transaction](../json_structs/json_blocks/struct.Transaction.html)
 //! and [State](/json_structs/json_state/struct.State.html) structures.
 //! The payload is originally serialized by a user chosen method, and then base64 encoded before its stored.
+//!
+//! Every deserialization method is a [PayloadDecoder](trait.PayloadDecoder.html) that turns the payload bytes
+//! into a [DecodedValue](../decoded_value/enum.DecodedValue.html). Decoders are looked up by name in a [DecoderRegistry](struct.DecoderRegistry.html),
+//! which comes with the built-in `cbor` and `json` decoders, and the [sawtooth_settings](../sawtooth_settings/index.html)
+//! protobuf decoders. Library users can register their own decoders
+//! into it to support any other serialization scheme.
+//!
+//! None of the methods panic on bad input. If a payload can't be decoded a [DecodeError](../error/enum.DecodeError.html)
+//! is returned, which can be turned into a printable marker with [describe_decode_error](fn.describe_decode_error.html).
 
 extern crate base64;
 extern crate serde_cbor;
 extern crate serde_json;
 
-/// Parse data that was serialized with CBOR.
-/// 
-/// Returns a string that represents the deserialized object. The `tab_padding`
-/// arg specifies how many tabs should pad each line of the string.
-/// 
-/// # Panics
-/// This function will panic if there are any errors in trying to Base64 decode,
-/// deserialize the payload with CBOR, or if the deserialization results in anything other
-/// than an object.
-pub fn parse_cbor(payload_in_base64: String, tab_padding: u8) -> String {
-    let bytes = base64::decode(payload_in_base64.as_str())
-        .expect("Error in trying to base64 decode payload:");
-
-    let val: serde_cbor::Value = serde_cbor::from_slice(&bytes)
-        .expect("Error in trying to deserialize payload with CBOR:");
-
-    let val_object = val.as_object()
-        .expect("Error in trying to convert deserialized payload to object:");
-
-    let mut decoded_payload = String::with_capacity(tab_padding as usize);
-    for (key, val) in val_object.iter() {
-        for _i in 0..tab_padding {decoded_payload.push('\t')};
-
-        // Concatenate the current string with a new string composed of the current key/value pair
-        decoded_payload = format!("{}{:?} : {:?}\n", decoded_payload, key, val);
-    }
-    decoded_payload
+use decoded_value::{DecodedValue};
+use error::{DecodeError};
+use sawtooth_settings::{SettingsPayloadDecoder, SettingsStateDecoder};
+
+use self::serde_json::{Value};
+
+use std::collections::BTreeMap;
+
+/// A deserialization method for payloads.
+///
+/// Implement this trait and [register](struct.DecoderRegistry.html#method.register) it into a
+/// [DecoderRegistry](struct.DecoderRegistry.html) to support a serialization scheme that isn't built in.
+pub trait PayloadDecoder {
+    /// Returns the name the decoder is registered under, e.g. `cbor`.
+    fn name(&self) -> &str;
+
+    /// Deserializes the payload into a [DecodedValue](../decoded_value/enum.DecodedValue.html). The `bytes`
+    /// have already been base64 decoded.
+    fn decode(&self, bytes: &[u8]) -> Result<DecodedValue, DecodeError>;
+}
+
+/// Decodes payloads that were serialized with CBOR.
+#[derive(Debug, Default)]
+pub struct CborDecoder;
+
+impl PayloadDecoder for CborDecoder {
+    fn name(&self) -> &str {
+        "cbor"
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<DecodedValue, DecodeError> {
+        let val: serde_cbor::Value = serde_cbor::from_slice(bytes)
+            .map_err(|error| DecodeError::Deserialize { method: String::from(self.name()), reason: error.to_string() })?;
+        Ok(cbor_to_value(val))
+    }
+}
+
+/// Decodes payloads that were serialized with JSON.
+#[derive(Debug, Default)]
+pub struct JsonDecoder;
+
+impl PayloadDecoder for JsonDecoder {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<DecodedValue, DecodeError> {
+        let val: Value = serde_json::from_slice(bytes)
+            .map_err(|error| DecodeError::Deserialize { method: String::from(self.name()), reason: error.to_string() })?;
+        Ok(json_to_value(val))
+    }
+}
+
+/// A collection of [PayloadDecoders](trait.PayloadDecoder.html) keyed by their name.
+///
+/// The default registry contains the built-in `cbor`, `json`, `sawtooth_settings` and `sawtooth_settings_state` decoders.
+pub struct DecoderRegistry {
+    decoders: BTreeMap<String, Box<dyn PayloadDecoder>>,
+}
+
+impl DecoderRegistry {
+    /// Returns a registry with no decoders in it.
+    pub fn new() -> DecoderRegistry {
+        DecoderRegistry { decoders: BTreeMap::new() }
+    }
+
+    /// Adds `decoder` to the registry under its [name](trait.PayloadDecoder.html#tymethod.name).
+    /// If a decoder with the same name was already registered it is replaced and returned.
+    pub fn register<D: PayloadDecoder + 'static>(&mut self, decoder: D) -> Option<Box<dyn PayloadDecoder>> {
+        self.decoders.insert(String::from(decoder.name()), Box::new(decoder))
+    }
+
+    /// Returns the decoder registered under `name`, if there is one.
+    pub fn get(&self, name: &str) -> Option<&dyn PayloadDecoder> {
+        self.decoders.get(name).map(|decoder| decoder.as_ref())
+    }
+
+    /// Returns the names of all registered decoders in alphabetical order.
+    pub fn names(&self) -> Vec<&str> {
+        self.decoders.keys().map(|name| name.as_str()).collect()
+    }
+
+    /// Base64 decodes the payload and deserializes it with the decoder registered under `method`.
+    ///
+    /// # Errors
+    /// Returns an error if no decoder is registered under `method`, if the payload isn't
+    /// valid base64, or if the decoder fails.
+    pub fn decode(&self, method: &str, payload_in_base64: &str) -> Result<DecodedValue, DecodeError> {
+        let decoder = self.get(method)
+            .ok_or_else(|| DecodeError::UnknownMethod { method: String::from(method) })?;
+        let bytes = base64::decode(payload_in_base64)?;
+        decoder.decode(&bytes)
+    }
+
+    /// Decodes the payload with the decoder registered under `method`, and returns a string that
+    /// represents the deserialized value, as [rendered](../decoded_value/enum.DecodedValue.html#method.render) for the terminal.
+    /// The `tab_padding` arg specifies how many tabs should pad each line of the string.
+    ///
+    /// # Errors
+    /// Returns the same errors as [decode](#method.decode).
+    pub fn parse(&self, method: &str, payload_in_base64: &str, tab_padding: u8) -> Result<String, DecodeError> {
+        let val = self.decode(method, payload_in_base64)?;
+        Ok(val.render(tab_padding))
+    }
+}
+
+impl Default for DecoderRegistry {
+    fn default() -> DecoderRegistry {
+        let mut registry = DecoderRegistry::new();
+        registry.register(CborDecoder);
+        registry.register(JsonDecoder);
+        registry.register(SettingsPayloadDecoder);
+        registry.register(SettingsStateDecoder);
+        registry
+    }
 }
 
-/// Parse data that was serialized with JSON.
-/// 
-/// Returns a string that represents the deserialized object. The `tab_padding`
-/// arg specifies how many tabs should pad each line of the string.
-/// 
-/// # Panics
-/// This function will panic if there are any errors in trying to Base64 decode,
-/// deserialize the payload with CBOR, or if the deserialization results in anything other
-/// than an object.
-pub fn parse_json(payload_in_base64: String, tab_padding: u8) -> String {
-    let bytes = base64::decode(payload_in_base64.as_str())
-        .expect("Error in trying to base64 decode payload:");
-
-    let val: serde_json::Value = serde_json::from_slice(&bytes)
-        .expect("Error in trying to deserialize payload with JSON:");
-
-    let val_object = val.as_object()
-        .expect("Error in trying to convert deserialized payload to object:");
-
-    let mut decoded_payload = String::with_capacity(tab_padding as usize);
-    for (key, val) in val_object.iter() {
-        for _i in 0..tab_padding {decoded_payload.push('\t')};
-
-        // Concatenate the current string with a new string composed of the current key/value pair
-        decoded_payload = format!("{}{:?} : {:?}\n", decoded_payload, key, val);
-    }
-    decoded_payload
+/// Returns a string describing why a payload couldn't be decoded, to be printed in place of the decoded payload.
+///
+/// The string contains a `could not decode: <reason>` marker followed by the raw base64 payload, and
+/// the payload as hex if it was valid base64. The `tab_padding` arg specifies how many tabs should pad each line of the string.
+pub fn describe_decode_error(payload_in_base64: &str, error: &DecodeError, tab_padding: u8) -> String {
+    let padding: String = (0..tab_padding).map(|_| '\t').collect();
+
+    let mut description = format!("{}could not decode: {}\n", padding, error);
+    description.push_str(&format!("{}Base64: {}\n", padding, payload_in_base64));
+    if let Ok(bytes) = base64::decode(payload_in_base64) {
+        description.push_str(&format!("{}Hex: {}\n", padding, to_hex(&bytes)));
+    }
+    description
 }
 
-/// Parse data that was serialized with a method not originally supported by this application.
-/// 
-/// In the case that the data you want to parse is serialized with a custom or unsupported method,
-/// such as google protocol buffers, then you will have to impliment this method to handle it.
-/// Some of the code is already written for you, but commented out.
-/// 
-/// Returns a string that represents the deserialized object. The `tab_padding`
-/// arg specifies how many tabs should pad each line of the string.
-/// 
-/// # Panics
-/// This function panics if it's used and isn't implemented. 
-/// This function should panic if there are any decoding or deserialization errors.
-pub fn parse_custom(_payload_in_base64: String, _tab_padding: u8) -> String {
-
-    // Remove this once you've finished. Also remove the '_' infront of the args
-    unimplemented!();
-
-    // First the payload is Base64 Decoded.
-    // let bytes = base64::decode(payload_in_base64.as_str())
-    //     .expect("Error in trying to base64 decode payload:");
-
-    // Here you should parse the payload using your derserialization method
-
-    // Here you should work on generating a string that represents the deserialized data
-    // let mut decoded_payload = String::with_capacity(tab_padding as usize);
-    // decoded_payload
+/// Returns the bytes as a lowercase hex string.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Converts a value deserialized with CBOR into a [DecodedValue](../decoded_value/enum.DecodedValue.html).
+fn cbor_to_value(val: serde_cbor::Value) -> DecodedValue {
+    match val {
+        serde_cbor::Value::U64(n) => DecodedValue::UInt(n),
+        serde_cbor::Value::I64(n) => DecodedValue::Int(n),
+        serde_cbor::Value::Bytes(bytes) => DecodedValue::Bytes(bytes),
+        serde_cbor::Value::String(s) => DecodedValue::Text(s),
+        serde_cbor::Value::Array(vals) => DecodedValue::Array(vals.into_iter().map(cbor_to_value).collect()),
+        serde_cbor::Value::Object(object) => DecodedValue::Map(object.into_iter()
+            .map(|(key, val)| {
+                let key = match key {
+                    serde_cbor::ObjectKey::Integer(n) => DecodedValue::Int(n),
+                    serde_cbor::ObjectKey::Bytes(bytes) => DecodedValue::Bytes(bytes),
+                    serde_cbor::ObjectKey::String(s) => DecodedValue::Text(s),
+                    serde_cbor::ObjectKey::Bool(b) => DecodedValue::Bool(b),
+                    serde_cbor::ObjectKey::Null => DecodedValue::Null,
+                };
+                (key, cbor_to_value(val))
+            })
+            .collect()),
+        serde_cbor::Value::F64(n) => DecodedValue::Float(n),
+        serde_cbor::Value::Bool(b) => DecodedValue::Bool(b),
+        serde_cbor::Value::Null => DecodedValue::Null,
+    }
+}
+
+/// Converts a value deserialized with JSON into a [DecodedValue](../decoded_value/enum.DecodedValue.html).
+fn json_to_value(val: Value) -> DecodedValue {
+    match val {
+        Value::Null => DecodedValue::Null,
+        Value::Bool(b) => DecodedValue::Bool(b),
+        Value::Number(n) => n.as_u64().map(DecodedValue::UInt)
+            .or_else(|| n.as_i64().map(DecodedValue::Int))
+            .unwrap_or_else(|| DecodedValue::Float(n.as_f64().unwrap_or(0.0))),
+        Value::String(s) => DecodedValue::Text(s),
+        Value::Array(vals) => DecodedValue::Array(vals.into_iter().map(json_to_value).collect()),
+        Value::Object(object) => DecodedValue::Map(object.into_iter()
+            .map(|(key, val)| (DecodedValue::Text(key), json_to_value(val)))
+            .collect()),
+    }
 }
 
 
@@ -104,29 +206,56 @@ mod test_cbor_decode {
 
     #[test]
     fn valid_payload() {
-        let payload = String::from("o2VWYWx1ZQFkVmVyYmNpbmNkTmFtZWRudW0x");
-        let decoded_string = String::from("String(\"Name\") : String(\"num1\")\nString(\"Value\") : U64(1)\nString(\"Verb\") : String(\"inc\")\n");
-        assert_eq!(decoded_string, parse_cbor(payload, 0));
+        let payload = "o2VWYWx1ZQFkVmVyYmNpbmNkTmFtZWRudW0x";
+        let decoded_string = String::from("\"Name\" : \"num1\"\n\"Value\" : 1\n\"Verb\" : \"inc\"\n");
+        assert_eq!(decoded_string, DecoderRegistry::default().parse("cbor", payload, 0).unwrap());
     }
 
     #[test]
-    #[should_panic(expected = "Error in trying to base64 decode payload:")]
     fn invalid_base64() {
-        let payload = String::from("????>>><<<YmNpbmNkTmFtZWRudW0x");
-        parse_cbor(payload, 0);
+        let payload = "????>>><<<YmNpbmNkTmFtZWRudW0x";
+        match DecoderRegistry::default().parse("cbor", payload, 0) {
+            Err(DecodeError::Base64(_)) => (),
+            other => panic!("Expected Base64 error, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in trying to deserialize payload with CBOR:")]
     fn invalid_cbor() {
-        let payload = String::from("ZVZhbHVlAWRWZXJiY2luY2ROYW1lZG51bTFAQEA/Pz88Pjw+");
-        parse_cbor(payload, 0);
+        let payload = "ZVZhbHVlAWRWZXJiY2luY2ROYW1lZG51bTFAQEA/Pz88Pjw+";
+        match DecoderRegistry::default().parse("cbor", payload, 0) {
+            Err(DecodeError::Deserialize { method, .. }) => assert_eq!("cbor", method),
+            other => panic!("Expected Deserialize error, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in trying to deserialize payload with CBOR:")]
     fn empty_string() {
-        parse_cbor("".to_string(), 0);
+        match DecoderRegistry::default().parse("cbor", "", 0) {
+            Err(DecodeError::Deserialize { method, .. }) => assert_eq!("cbor", method),
+            other => panic!("Expected Deserialize error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn not_an_object() {
+        // CBOR encoding of the array [1, 2]
+        let payload = "ggEC";
+        assert_eq!("[1, 2]\n", DecoderRegistry::default().parse("cbor", payload, 0).unwrap());
+    }
+
+    #[test]
+    fn decodes_to_structured_value() {
+        let val = CborDecoder.decode(&base64::decode("ggEC").unwrap()).unwrap();
+        assert_eq!(DecodedValue::Array(vec![DecodedValue::UInt(1), DecodedValue::UInt(2)]), val);
+    }
+
+    #[test]
+    fn decodes_nested_values_and_bytes() {
+        // CBOR encoding of {"a": {"b": h'0102'}, "c": [-1]}
+        let val = CborDecoder.decode(&[0xa2, 0x61, b'a', 0xa1, 0x61, b'b', 0x42, 0x01, 0x02, 0x61, b'c', 0x81, 0x20]).unwrap();
+        assert_eq!(Some(&DecodedValue::Bytes(vec![0x01, 0x02])), val.get("a").and_then(|a| a.get("b")));
+        assert_eq!("\"a\" :\n\t\"b\" : 0x0102\n\"c\" : [-1]\n", val.render(0));
     }
 }
 
@@ -136,28 +265,117 @@ mod test_json_decode {
 
     #[test]
     fn valid_payload() {
-        let payload = String::from("eyJWZXJiIjogInZlcmIiLCJOYW1lIjogIm5hbWUiLCJWYWx1ZSI6IDEyMzR9");
-        let decoded_string = String::from("\"Name\" : String(\"name\")\n\"Value\" : Number(1234)\n\"Verb\" : String(\"verb\")\n");
-        assert_eq!(decoded_string, parse_json(payload, 0));
+        let payload = "eyJWZXJiIjogInZlcmIiLCJOYW1lIjogIm5hbWUiLCJWYWx1ZSI6IDEyMzR9";
+        let decoded_string = String::from("\"Name\" : \"name\"\n\"Value\" : 1234\n\"Verb\" : \"verb\"\n");
+        assert_eq!(decoded_string, DecoderRegistry::default().parse("json", payload, 0).unwrap());
     }
 
     #[test]
-    #[should_panic(expected = "Error in trying to base64 decode payload:")]
     fn invalid_base64() {
-        let payload = String::from("????>>><<<YmNpbmNkTmFtZWRudW0x");
-        parse_json(payload, 0);
+        let payload = "????>>><<<YmNpbmNkTmFtZWRudW0x";
+        match DecoderRegistry::default().parse("json", payload, 0) {
+            Err(DecodeError::Base64(_)) => (),
+            other => panic!("Expected Base64 error, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in trying to deserialize payload with JSON:")]
     fn invalid_json() {
-        let payload = String::from("eyJWZXJiIjogJ2EnJycsIk5hbWUiOiAibmFtZSIsIlZhbHVlIjogMTIzNH0=");
-        parse_json(payload, 0);
+        let payload = "eyJWZXJiIjogJ2EnJycsIk5hbWUiOiAibmFtZSIsIlZhbHVlIjogMTIzNH0=";
+        match DecoderRegistry::default().parse("json", payload, 0) {
+            Err(DecodeError::Deserialize { method, .. }) => assert_eq!("json", method),
+            other => panic!("Expected Deserialize error, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in trying to deserialize payload with JSON:")]
     fn empty_string() {
-        parse_json("".to_string(), 0);
+        match DecoderRegistry::default().parse("json", "", 0) {
+            Err(DecodeError::Deserialize { method, .. }) => assert_eq!("json", method),
+            other => panic!("Expected Deserialize error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn not_an_object() {
+        // JSON encoding of the array [1,2]
+        let payload = "WzEsMl0=";
+        assert_eq!("[1, 2]\n", DecoderRegistry::default().parse("json", payload, 0).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod test_decoder_registry {
+    use super::*;
+
+    /// A decoder that treats the payload as UTF-8 text.
+    struct TextDecoder;
+
+    impl PayloadDecoder for TextDecoder {
+        fn name(&self) -> &str {
+            "text"
+        }
+
+        fn decode(&self, bytes: &[u8]) -> Result<DecodedValue, DecodeError> {
+            let text = String::from_utf8(bytes.to_vec())
+                .map_err(|error| DecodeError::Deserialize { method: String::from("text"), reason: error.to_string() })?;
+            Ok(DecodedValue::Map(vec![(DecodedValue::from("text"), DecodedValue::Text(text))]))
+        }
+    }
+
+    #[test]
+    fn default_has_builtin_decoders() {
+        assert_eq!(vec!["cbor", "json", "sawtooth_settings", "sawtooth_settings_state"], DecoderRegistry::default().names());
+    }
+
+    #[test]
+    fn new_is_empty() {
+        assert!(DecoderRegistry::new().names().is_empty());
+    }
+
+    #[test]
+    fn registered_decoder_is_used() {
+        let mut registry = DecoderRegistry::default();
+        assert!(registry.register(TextDecoder).is_none());
+        assert!(registry.names().contains(&"text"));
+        // base64 of "hi"
+        assert_eq!("\"text\" : \"hi\"\n", registry.parse("text", "aGk=", 0).unwrap());
+    }
+
+    #[test]
+    fn registering_same_name_replaces() {
+        let mut registry = DecoderRegistry::default();
+        registry.register(TextDecoder);
+        assert!(registry.register(TextDecoder).is_some());
+        assert_eq!(5, registry.names().len());
+    }
+
+    #[test]
+    fn unknown_method() {
+        match DecoderRegistry::default().parse("yaml", "ggEC", 0) {
+            Err(DecodeError::UnknownMethod { method }) => assert_eq!("yaml", method),
+            other => panic!("Expected UnknownMethod error, got {:?}", other),
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_decode_error_description {
+    use super::*;
+
+    #[test]
+    fn includes_reason_base64_and_hex() {
+        let error = DecodeError::UnknownMethod { method: String::from("yaml") };
+        let description = describe_decode_error("ggEC", &error, 1);
+        assert_eq!("\tcould not decode: no decoder is registered for `yaml`\n\tBase64: ggEC\n\tHex: 820102\n", description);
+    }
+
+    #[test]
+    fn omits_hex_for_invalid_base64() {
+        let payload = "????";
+        let error = DecoderRegistry::default().parse("cbor", payload, 0).unwrap_err();
+        let description = describe_decode_error(payload, &error, 0);
+        assert!(description.starts_with("could not decode: invalid base64"));
+        assert!(!description.contains("Hex:"));
+    }
+}
@@ -6,82 +6,317 @@
 //! `json_reader` contains methods for parsing blockchain JSON into structures found in [json_structs](../json_structs/index.html).
 //! The JSON can come from either files or be located at HTTP endpoints.
 //! 
-//! Currently the module has methods that allow one to parse data from the `/state` or `/blocks`
-//! endpoints of Hyperledger Sawtooth. 
-
+//! Currently the module has methods that allow one to parse data from the `/state`, `/blocks`, `/batches`,
+//! `/transactions`, `/batch_statuses` or `/receipts` endpoints of Hyperledger Sawtooth, and from the endpoints
+//! that return a single block, batch, transaction or state address, such as `/blocks/{block_id}`. 
+//!
+//! Lists that Sawtooth splits into pages are read in full by following the link to each next page, see
+//! [read_pages_from_endpoint](fn.read_pages_from_endpoint.html). The requests are made by a
+//! [SawtoothClient](../client/struct.SawtoothClient.html) for the given URL. Use a client directly to read from
+//! the base URL of a node, or to set a timeout, retries, headers or authentication.
+//!
+//! None of the methods panic. Instead they return an [Error](../error/enum.Error.html) describing
+//! why the data couldn't be read.
+
+extern crate serde;
 extern crate serde_json;
-extern crate reqwest;
+extern crate serde_path_to_error;
 extern crate mockito;
 
-use json_structs::json_blocks::{BlockData};
-use json_structs::json_state::{StateData};
+use json_structs::json_batch_statuses::{BatchStatusList};
+use json_structs::json_batches::{BatchList, BatchResponse};
+use json_structs::json_blocks::{BlockData, BlockResponse};
+use json_structs::json_receipts::{ReceiptList};
+use json_structs::json_state::{StateData, StateResponse};
+use json_structs::json_transactions::{TransactionList, TransactionResponse};
+use client::{ListQuery, SawtoothClient};
+use json_structs::{Paged};
+use error::{Error, Result};
+
+use self::serde::de::DeserializeOwned;
 
 use std::fs;
+use std::io;
 
 /// Reads JSON data from the /blocks endpoint, but stored in a file.
 /// Returns the JSON as a [BlockData](../json_structs/json_blocks/struct.BlockData.html) structure.
 /// 
-/// # Panics
-/// This function will panic if the file cant be opened, read, or if it doesn't exist.
-/// It will also panic if the structure of the JSON data is malformatted.
-pub fn read_block_data_from_file(filepath: &str) -> BlockData {
-    let file = fs::read_to_string(filepath)
-        .expect("Unable to open file for reading block data: ");
- 
-    serde_json::from_str(file.as_str())
-        .expect("Error in parsing block data file: ")
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_block_data_from_file(filepath: &str) -> Result<BlockData> {
+    read_from_file(filepath)
 }
 
 /// Reads JSON data from the /state endpoint, but stored in a file.
 /// Returns the JSON as a [StateData](../json_structs/json_state/struct.StateData.html) structure.
 /// 
-/// # Panics
-/// This function will panic if the file cant be opened, read, or if it doesn't exist.
-/// It will also panic if the structure of the JSON data is malformatted.
-pub fn read_state_data_from_file(filepath: &str) -> StateData {
-    let file = fs::read_to_string(filepath)
-        .expect("Unable to open file for reading state data: ");
- 
-    serde_json::from_str(file.as_str())
-        .expect("Error in parsing state data file: ")
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_state_data_from_file(filepath: &str) -> Result<StateData> {
+    read_from_file(filepath)
+}
+
+/// Reads JSON data from the /batches endpoint, but stored in a file.
+/// Returns the JSON as a [BatchList](../json_structs/json_batches/struct.BatchList.html) structure.
+/// 
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_batch_list_from_file(filepath: &str) -> Result<BatchList> {
+    read_from_file(filepath)
+}
+
+/// Reads JSON data from the /transactions endpoint, but stored in a file.
+/// Returns the JSON as a [TransactionList](../json_structs/json_transactions/struct.TransactionList.html) structure.
+/// 
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_transaction_list_from_file(filepath: &str) -> Result<TransactionList> {
+    read_from_file(filepath)
+}
+
+/// Reads JSON data from the /batch_statuses endpoint, but stored in a file.
+/// Returns the JSON as a [BatchStatusList](../json_structs/json_batch_statuses/struct.BatchStatusList.html) structure.
+/// 
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_batch_statuses_from_file(filepath: &str) -> Result<BatchStatusList> {
+    read_from_file(filepath)
+}
+
+/// Reads JSON data from the /receipts endpoint, but stored in a file.
+/// Returns the JSON as a [ReceiptList](../json_structs/json_receipts/struct.ReceiptList.html) structure.
+/// 
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_receipts_from_file(filepath: &str) -> Result<ReceiptList> {
+    read_from_file(filepath)
+}
+
+/// Reads JSON data from the /blocks/{block_id} endpoint, but stored in a file.
+/// Returns the JSON as a [BlockResponse](../json_structs/json_blocks/struct.BlockResponse.html) structure.
+/// 
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_block_from_file(filepath: &str) -> Result<BlockResponse> {
+    read_from_file(filepath)
+}
+
+/// Reads JSON data from the /batches/{batch_id} endpoint, but stored in a file.
+/// Returns the JSON as a [BatchResponse](../json_structs/json_batches/struct.BatchResponse.html) structure.
+/// 
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_batch_from_file(filepath: &str) -> Result<BatchResponse> {
+    read_from_file(filepath)
+}
+
+/// Reads JSON data from the /transactions/{transaction_id} endpoint, but stored in a file.
+/// Returns the JSON as a [TransactionResponse](../json_structs/json_transactions/struct.TransactionResponse.html) structure.
+/// 
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_transaction_from_file(filepath: &str) -> Result<TransactionResponse> {
+    read_from_file(filepath)
+}
+
+/// Reads JSON data from the /state/{address} endpoint, but stored in a file.
+/// Returns the JSON as a [StateResponse](../json_structs/json_state/struct.StateResponse.html) structure.
+/// 
+/// # Errors
+/// Returns an error if the file doesn't exist or can't be read, or if the
+/// structure of the JSON data is malformatted.
+pub fn read_state_address_from_file(filepath: &str) -> Result<StateResponse> {
+    read_from_file(filepath)
 }
 
 /// Reads JSON data from the /blocks endpoint using a `GET` request.
 /// Returns the JSON as a [BlockData](../json_structs/json_blocks/struct.BlockData.html) structure.
 /// 
-/// # Panics
-/// This function will panic if the request fails to be made, if the status code in the response 
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
 /// is anything outisde of the 200 range, or if the JSON data is malformed.
-pub fn read_block_data_from_endpoint(url: &str) -> BlockData {
-    let mut response = reqwest::get(url).expect("Error in trying to make GET Request to server: ");
-    if response.status().is_success() {
-        let data: BlockData = response.json().expect("Error in parsing block JSON: ");
-        data
-    } else if response.status().is_server_error() || response.status().is_client_error(){
-        panic!("Error code {} when trying to get /blocks endpoint: ", response.status().as_u16());
-    } else {
-        panic!("Unexpected code {} when trying to get /blocks endpoint: ", response.status().as_u16());
-    }
+///
+/// Every page of the list is read and merged, see [read_pages_from_endpoint](fn.read_pages_from_endpoint.html).
+pub fn read_block_data_from_endpoint(url: &str) -> Result<BlockData> {
+    read_pages_from_endpoint(url, None)
 }
 
 /// Reads JSON data from the `/state` endpoint using a `GET` request.
 /// Returns the JSON as a [StateData](../json_structs/json_state/struct.StateData.html) structure.
 ///
-/// # Panics
-/// This function will panic if the request fails to be made, if the status code in the response 
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
+/// is anything outisde of the 200 range, or if the JSON data is malformed.
+///
+/// Every page of the list is read and merged, see [read_pages_from_endpoint](fn.read_pages_from_endpoint.html).
+pub fn read_state_data_from_endpoint(url: &str) -> Result<StateData> {
+    read_pages_from_endpoint(url, None)
+}
+
+/// Reads JSON data from the `/batches` endpoint using a `GET` request.
+/// Returns the JSON as a [BatchList](../json_structs/json_batches/struct.BatchList.html) structure.
+///
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
+/// is anything outisde of the 200 range, or if the JSON data is malformed.
+///
+/// Every page of the list is read and merged, see [read_pages_from_endpoint](fn.read_pages_from_endpoint.html).
+pub fn read_batch_list_from_endpoint(url: &str) -> Result<BatchList> {
+    read_pages_from_endpoint(url, None)
+}
+
+/// Reads JSON data from the `/transactions` endpoint using a `GET` request.
+/// Returns the JSON as a [TransactionList](../json_structs/json_transactions/struct.TransactionList.html) structure.
+///
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
 /// is anything outisde of the 200 range, or if the JSON data is malformed.
-pub fn read_state_data_from_endpoint(url: &str) -> StateData {
-    let mut response = reqwest::get(url).expect("Error in trying to make GET Request to server: ");
-    if response.status().is_success() {
-        let data: StateData = response.json().expect("Error in parsing state JSON: ");
-        data
-    } else if response.status().is_server_error() || response.status().is_client_error(){
-        panic!("Error code {} when trying to get /state endpoint: ", response.status().as_u16());
-    } else {
-        panic!("Unexpected code {} when trying to get /state endpoint: ", response.status().as_u16());
+///
+/// Every page of the list is read and merged, see [read_pages_from_endpoint](fn.read_pages_from_endpoint.html).
+pub fn read_transaction_list_from_endpoint(url: &str) -> Result<TransactionList> {
+    read_pages_from_endpoint(url, None)
+}
+
+/// Reads the status of the batches with the given `ids` from the `/batch_statuses` endpoint at `url`, using a `GET` request.
+/// Returns the JSON as a [BatchStatusList](../json_structs/json_batch_statuses/struct.BatchStatusList.html) structure.
+///
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
+/// is anything outisde of the 200 range, or if the JSON data is malformed. Sawtooth answers with
+/// a `400` status if `ids` is empty.
+pub fn read_batch_statuses_from_endpoint(url: &str, ids: &[&str]) -> Result<BatchStatusList> {
+    SawtoothClient::new(&with_ids(url, ids)).get("")
+}
+
+/// Reads the receipts of the transactions with the given `ids` from the `/receipts` endpoint at `url`, using a `GET` request.
+/// Returns the JSON as a [ReceiptList](../json_structs/json_receipts/struct.ReceiptList.html) structure.
+///
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
+/// is anything outisde of the 200 range, or if the JSON data is malformed. Sawtooth answers with
+/// a `400` status if `ids` is empty, or a `404` status if one of the transactions has no receipt.
+pub fn read_receipts_from_endpoint(url: &str, ids: &[&str]) -> Result<ReceiptList> {
+    SawtoothClient::new(&with_ids(url, ids)).get("")
+}
+
+/// Reads the block with the given `id` from the `/blocks` endpoint at `url`, by making a `GET` request to `/blocks/{id}`.
+/// Returns the JSON as a [BlockResponse](../json_structs/json_blocks/struct.BlockResponse.html) structure.
+///
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
+/// is anything outisde of the 200 range, or if the JSON data is malformed. Sawtooth answers with
+/// a `404` status if there is no block with that ID.
+pub fn read_block_from_endpoint(url: &str, id: &str) -> Result<BlockResponse> {
+    SawtoothClient::new(url).get(id)
+}
+
+/// Reads the batch with the given `id` from the `/batches` endpoint at `url`, by making a `GET` request to `/batches/{id}`.
+/// Returns the JSON as a [BatchResponse](../json_structs/json_batches/struct.BatchResponse.html) structure.
+///
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
+/// is anything outisde of the 200 range, or if the JSON data is malformed. Sawtooth answers with
+/// a `404` status if there is no batch with that ID.
+pub fn read_batch_from_endpoint(url: &str, id: &str) -> Result<BatchResponse> {
+    SawtoothClient::new(url).get(id)
+}
+
+/// Reads the transaction with the given `id` from the `/transactions` endpoint at `url`, by making a `GET` request to
+/// `/transactions/{id}`.
+/// Returns the JSON as a [TransactionResponse](../json_structs/json_transactions/struct.TransactionResponse.html) structure.
+///
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
+/// is anything outisde of the 200 range, or if the JSON data is malformed. Sawtooth answers with
+/// a `404` status if there is no transaction with that ID.
+pub fn read_transaction_from_endpoint(url: &str, id: &str) -> Result<TransactionResponse> {
+    SawtoothClient::new(url).get(id)
+}
+
+/// Reads the data stored at `address` from the `/state` endpoint at `url`, by making a `GET` request to `/state/{address}`.
+/// Returns the JSON as a [StateResponse](../json_structs/json_state/struct.StateResponse.html) structure.
+///
+/// # Errors
+/// Returns an error if the request fails to be made, if the status code in the response 
+/// is anything outisde of the 200 range, or if the JSON data is malformed. Sawtooth answers with
+/// a `404` status if nothing is stored at the address.
+pub fn read_state_address_from_endpoint(url: &str, address: &str) -> Result<StateResponse> {
+    SawtoothClient::new(url).get(address)
+}
+
+/// Reads a list that is split into pages from the endpoint at `url`, such as `/blocks` or `/state`, by making a `GET`
+/// request for each page. The link to the `next` page in the paging of each response is followed until the last page,
+/// or until `max_pages` pages were read, and the pages are merged into one structure. The paging of the merged structure
+/// links to the page after the last one that was read, if there is one.
+///
+/// # Errors
+/// Returns an error if the request for any of the pages fails, for the same reasons as
+/// [read_block_data_from_endpoint](fn.read_block_data_from_endpoint.html).
+pub fn read_pages_from_endpoint<T: Paged + DeserializeOwned>(url: &str, max_pages: Option<usize>) -> Result<T> {
+    let mut query = ListQuery::default();
+    if let Some(max_pages) = max_pages {
+        query.set_max_pages(max_pages);
+    }
+    SawtoothClient::new(url).get_pages("", &query)
+}
+
+/// Returns `url` with the query parameter `key` set to `value`, e.g. to pass the `limit` and `start` of
+/// the paging to Sawtooth.
+pub fn with_query(url: &str, key: &str, value: &str) -> String {
+    match url.contains('?') {
+        true => format!("{}&{}={}", url, key, value),
+        false => format!("{}?{}={}", url, key, value),
+    }
+}
+
+/// Returns `url` with the comma separated `ids` added as its `id` query parameter.
+pub(crate) fn with_ids(url: &str, ids: &[&str]) -> String {
+    match ids.is_empty() {
+        true => String::from(url),
+        false => with_query(url, "id", &ids.join(",")),
+    }
+}
+
+/// Reads the file at `filepath` and parses its contents as JSON into `T`.
+pub(crate) fn read_from_file<T: DeserializeOwned>(filepath: &str) -> Result<T> {
+    let file = fs::read_to_string(filepath).map_err(|error| file_error(filepath, error))?;
+
+    parse_json(file.as_str(), filepath)
+}
+
+/// Reads the raw contents of the file at `filepath`.
+pub(crate) fn read_bytes_from_file(filepath: &str) -> Result<Vec<u8>> {
+    fs::read(filepath).map_err(|error| file_error(filepath, error))
+}
+
+/// Converts an error from reading the file at `filepath` into an [Error](../error/enum.Error.html).
+fn file_error(filepath: &str, error: io::Error) -> Error {
+    match error.kind() {
+        io::ErrorKind::NotFound => Error::FileNotFound { path: String::from(filepath) },
+        _ => Error::Io { path: String::from(filepath), error },
     }
 }
 
+/// Parses `json` into `T`, keeping track of the path to the element that failed to parse.
+/// `location` is the file or URL the JSON came from and is only used for error reporting.
+pub(crate) fn parse_json<T: DeserializeOwned>(json: &str, location: &str) -> Result<T> {
+    let deserializer = &mut serde_json::Deserializer::from_str(json);
+    serde_path_to_error::deserialize(deserializer).map_err(|error| Error::JsonSchema {
+        location: String::from(location),
+        path: error.path().to_string(),
+        error: error.into_inner(),
+    })
+}
+
 #[cfg(test)]
 mod test_read_from_file {
     use super::*;
@@ -89,55 +324,145 @@ mod test_read_from_file {
     #[test]
     fn blockdata_valid_path_and_format() {
         let path = "example-blockchain/blocks.json";
-        read_block_data_from_file(path);
+        assert!(read_block_data_from_file(path).is_ok());
     }
 
     #[test]
-    #[should_panic(expected = "Unable to open file for reading block data:")]
     fn blockdata_invalid_path() {
         let path = "example-blockchain/does_not_exist.json";
-        read_block_data_from_file(path);
+        match read_block_data_from_file(path) {
+            Err(Error::FileNotFound { path: p }) => assert_eq!(path, p),
+            other => panic!("Expected FileNotFound, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in parsing block data file:")]
     fn blockdata_valid_path_but_invalid_format() {
         let path = "example-blockchain/malformatted_block_data.json";
-        read_block_data_from_file(path);
+        match read_block_data_from_file(path) {
+            Err(Error::JsonSchema { location, path: json_path, .. }) => {
+                assert_eq!(path, location);
+                assert_eq!("data[0]", json_path);
+            },
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in parsing block data file:")]
     fn blockdata_valid_path_but_wrong_file() {
         let path = "example-blockchain/state.json";
-        read_block_data_from_file(path);
+        match read_block_data_from_file(path) {
+            Err(Error::JsonSchema { .. }) => (),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
     }
 
     #[test]
     fn statedata_valid_path_and_format() {
         let path = "example-blockchain/state.json";
-        read_state_data_from_file(path);
+        assert!(read_state_data_from_file(path).is_ok());
     }
 
     #[test]
-    #[should_panic(expected = "Unable to open file for reading state data:")]
     fn statedata_invalid_path() {
         let path = "example-blockchain/does_not_exist.json";
-        read_state_data_from_file(path);
+        match read_state_data_from_file(path) {
+            Err(Error::FileNotFound { path: p }) => assert_eq!(path, p),
+            other => panic!("Expected FileNotFound, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in parsing state data file:")]
     fn statedata_valid_path_but_invalid_format() {
         let path = "example-blockchain/malformatted_block_data.json";
-        read_state_data_from_file(path);
+        match read_state_data_from_file(path) {
+            Err(Error::JsonSchema { .. }) => (),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in parsing state data file:")]
     fn statedata_valid_path_but_wrong_file() {
         let path = "example-blockchain/blocks.json";
-        read_state_data_from_file(path);
+        match read_state_data_from_file(path) {
+            Err(Error::JsonSchema { .. }) => (),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn batchlist_valid_path_and_format() {
+        let path = "example-blockchain/batches.json";
+        assert!(read_batch_list_from_file(path).is_ok());
+    }
+
+    #[test]
+    fn batchlist_invalid_path() {
+        let path = "example-blockchain/does_not_exist.json";
+        match read_batch_list_from_file(path) {
+            Err(Error::FileNotFound { path: p }) => assert_eq!(path, p),
+            other => panic!("Expected FileNotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn batchlist_valid_path_but_wrong_file() {
+        let path = "example-blockchain/blocks.json";
+        match read_batch_list_from_file(path) {
+            Err(Error::JsonSchema { path: json_path, .. }) => assert_eq!("data[0].header", json_path),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn transactionlist_valid_path_and_format() {
+        let path = "example-blockchain/transactions.json";
+        assert!(read_transaction_list_from_file(path).is_ok());
+    }
+
+    #[test]
+    fn batch_statuses_valid_path_and_format() {
+        assert!(read_batch_statuses_from_file("example-blockchain/batch_statuses.json").is_ok());
+    }
+
+    #[test]
+    fn receipts_valid_path_and_format() {
+        assert!(read_receipts_from_file("example-blockchain/receipts.json").is_ok());
+    }
+
+    #[test]
+    fn receipts_valid_path_but_wrong_file() {
+        let path = "example-blockchain/batch_statuses.json";
+        match read_receipts_from_file(path) {
+            Err(Error::JsonSchema { path: json_path, .. }) => assert_eq!("data[0]", json_path),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn single_entities_valid_path_and_format() {
+        assert!(read_block_from_file("example-blockchain/block.json").is_ok());
+        assert!(read_batch_from_file("example-blockchain/batch.json").is_ok());
+        assert!(read_transaction_from_file("example-blockchain/transaction.json").is_ok());
+        assert!(read_state_address_from_file("example-blockchain/state_address.json").is_ok());
+    }
+
+    #[test]
+    fn single_block_from_list_file() {
+        let path = "example-blockchain/blocks.json";
+        match read_block_from_file(path) {
+            Err(Error::JsonSchema { path: json_path, .. }) => assert_eq!("data[0]", json_path),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn transactionlist_valid_path_but_wrong_file() {
+        let path = "example-blockchain/state.json";
+        match read_transaction_list_from_file(path) {
+            Err(Error::JsonSchema { .. }) => (),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
     }
 }
 
@@ -146,10 +471,9 @@ mod test_read_from_endpoint {
     use super::*;
     use self::mockito::mock;
 
-    const URL: &'static str = mockito::SERVER_URL;
+    const URL: &str = mockito::SERVER_URL;
 
     #[test]
-    #[should_panic(expected = "Error code 404")]
     fn blockdata_error_404() {
         let _m = mock("GET", "/")
             .with_status(404)
@@ -157,11 +481,16 @@ mod test_read_from_endpoint {
             .with_body("")
             .create();
 
-        read_block_data_from_endpoint(URL);
+        match read_block_data_from_endpoint(URL) {
+            Err(Error::HttpStatus { code, endpoint }) => {
+                assert_eq!(404, code);
+                assert_eq!(URL, endpoint);
+            },
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Unexpected code 301")]
     fn blockdata_unknown_301() {
         let _m = mock("GET", "/")
             .with_status(301)
@@ -169,11 +498,13 @@ mod test_read_from_endpoint {
             .with_body("")
             .create();
 
-        read_block_data_from_endpoint(URL);
+        match read_block_data_from_endpoint(URL) {
+            Err(Error::HttpStatus { code, .. }) => assert_eq!(301, code),
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error code 501")]
     fn blockdata_error_501() {
         let _m = mock("GET", "/")
             .with_status(501)
@@ -181,11 +512,13 @@ mod test_read_from_endpoint {
             .with_body("")
             .create();
 
-        read_block_data_from_endpoint(URL);
+        match read_block_data_from_endpoint(URL) {
+            Err(Error::HttpStatus { code, .. }) => assert_eq!(501, code),
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in parsing block JSON:")]
     fn blockdata_code_200_bad_json() {
         let _m = mock("GET", "/")
             .with_status(200)
@@ -193,7 +526,32 @@ mod test_read_from_endpoint {
             .with_body("{hello:world}")
             .create();
 
-        read_block_data_from_endpoint(URL);
+        match read_block_data_from_endpoint(URL) {
+            Err(Error::JsonSchema { location, .. }) => assert_eq!(URL, location),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn blockdata_code_200_wrong_field_type() {
+        let _m = mock("GET", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("{
+                \"data\": [],
+                \"head\": 12,
+                \"link\": \"\",
+                \"paging\": {
+                    \"limit\": null,
+                    \"start\": null
+                }
+            }")
+            .create();
+
+        match read_block_data_from_endpoint(URL) {
+            Err(Error::JsonSchema { path, .. }) => assert_eq!("head", path),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
     }
 
     #[test]
@@ -212,11 +570,10 @@ mod test_read_from_endpoint {
             }")
             .create();
 
-        read_block_data_from_endpoint(URL);
+        assert!(read_block_data_from_endpoint(URL).is_ok());
     }
 
     #[test]
-    #[should_panic(expected = "Error code 404")]
     fn statedata_error_404() {
         let _m = mock("GET", "/")
             .with_status(404)
@@ -224,11 +581,16 @@ mod test_read_from_endpoint {
             .with_body("")
             .create();
 
-        read_state_data_from_endpoint(URL);
+        match read_state_data_from_endpoint(URL) {
+            Err(Error::HttpStatus { code, endpoint }) => {
+                assert_eq!(404, code);
+                assert_eq!(URL, endpoint);
+            },
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Unexpected code 301")]
     fn statedata_unknown_301() {
         let _m = mock("GET", "/")
             .with_status(301)
@@ -236,11 +598,13 @@ mod test_read_from_endpoint {
             .with_body("")
             .create();
 
-        read_state_data_from_endpoint(URL);
+        match read_state_data_from_endpoint(URL) {
+            Err(Error::HttpStatus { code, .. }) => assert_eq!(301, code),
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error code 501")]
     fn statedata_error_501() {
         let _m = mock("GET", "/")
             .with_status(501)
@@ -248,11 +612,13 @@ mod test_read_from_endpoint {
             .with_body("")
             .create();
 
-        read_state_data_from_endpoint(URL);
+        match read_state_data_from_endpoint(URL) {
+            Err(Error::HttpStatus { code, .. }) => assert_eq!(501, code),
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error in parsing state JSON:")]
     fn statedata_code_200_bad_json() {
         let _m = mock("GET", "/")
             .with_status(200)
@@ -260,7 +626,10 @@ mod test_read_from_endpoint {
             .with_body("{hello:world}")
             .create();
 
-        read_state_data_from_endpoint(URL);
+        match read_state_data_from_endpoint(URL) {
+            Err(Error::JsonSchema { location, .. }) => assert_eq!(URL, location),
+            other => panic!("Expected JsonSchema, got {:?}", other),
+        }
     }
 
     #[test]
@@ -279,6 +648,221 @@ mod test_read_from_endpoint {
             }")
             .create();
 
-        read_state_data_from_endpoint(URL);
+        assert!(read_state_data_from_endpoint(URL).is_ok());
+    }
+
+    #[test]
+    fn batchlist_error_404() {
+        let _m = mock("GET", "/")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body("")
+            .create();
+
+        match read_batch_list_from_endpoint(URL) {
+            Err(Error::HttpStatus { code, endpoint }) => {
+                assert_eq!(404, code);
+                assert_eq!(URL, endpoint);
+            },
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn batchlist_code_200_good_json() {
+        let _m = mock("GET", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("{
+                \"data\": [],
+                \"head\": \"\",
+                \"link\": \"\",
+                \"paging\": {
+                    \"limit\": null,
+                    \"start\": null
+                }
+            }")
+            .create();
+
+        assert!(read_batch_list_from_endpoint(URL).is_ok());
+    }
+
+    #[test]
+    fn transactionlist_error_501() {
+        let _m = mock("GET", "/")
+            .with_status(501)
+            .with_header("content-type", "application/json")
+            .with_body("")
+            .create();
+
+        match read_transaction_list_from_endpoint(URL) {
+            Err(Error::HttpStatus { code, .. }) => assert_eq!(501, code),
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn transactionlist_code_200_good_json() {
+        let _m = mock("GET", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("{
+                \"data\": [],
+                \"head\": \"\",
+                \"link\": \"\",
+                \"paging\": {
+                    \"limit\": null,
+                    \"start\": null
+                }
+            }")
+            .create();
+
+        assert!(read_transaction_list_from_endpoint(URL).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn unreachable_server_is_transport_error() {
+        match read_state_data_from_endpoint("http://127.0.0.1:1/state") {
+            Err(Error::Transport { endpoint, .. }) => assert_eq!("http://127.0.0.1:1/state", endpoint),
+            other => panic!("Expected Transport, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn batch_statuses_query_ids() {
+        let _m = mock("GET", "/batch_statuses?id=aaaa,bbbb")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("{
+                \"data\": [
+                    {\"id\": \"aaaa\", \"invalid_transactions\": [], \"status\": \"UNKNOWN\"},
+                    {\"id\": \"bbbb\", \"invalid_transactions\": [], \"status\": \"COMMITTED\"}
+                ],
+                \"link\": \"\"
+            }")
+            .create();
+
+        let url = format!("{}/batch_statuses", URL);
+        let statuses = read_batch_statuses_from_endpoint(&url, &["aaaa", "bbbb"]).unwrap();
+        assert_eq!(2, statuses.get_num_statuses());
+    }
+
+    #[test]
+    fn receipts_query_ids_after_other_parameters() {
+        let _m = mock("GET", "/receipts?wait=1&id=cccc")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("{\"data\": [], \"link\": \"\"}")
+            .create();
+
+        let url = format!("{}/receipts?wait=1", URL);
+        assert!(read_receipts_from_endpoint(&url, &["cccc"]).is_ok());
+    }
+
+    #[test]
+    fn receipts_error_404() {
+        let _m = mock("GET", "/receipts?id=dddd")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body("")
+            .create();
+
+        let url = format!("{}/receipts", URL);
+        match read_receipts_from_endpoint(&url, &["dddd"]) {
+            Err(Error::HttpStatus { code, endpoint }) => {
+                assert_eq!(404, code);
+                assert_eq!(format!("{}?id=dddd", url), endpoint);
+            },
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn single_block_by_id() {
+        let _m = mock("GET", "/blocks/abcd")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(&fs::read_to_string("example-blockchain/block.json").unwrap())
+            .create();
+
+        let url = format!("{}/blocks/", URL);
+        assert!(read_block_from_endpoint(&url, "abcd").is_ok());
+    }
+
+    #[test]
+    fn single_state_address_error_404() {
+        let _m = mock("GET", "/state/1cf126")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body("")
+            .create();
+
+        let url = format!("{}/state", URL);
+        match read_state_address_from_endpoint(&url, "1cf126") {
+            Err(Error::HttpStatus { code, endpoint }) => {
+                assert_eq!(404, code);
+                assert_eq!(format!("{}/1cf126", url), endpoint);
+            },
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
+    }
+
+    fn page(data: &str, next: Option<&str>) -> String {
+        let next = next.map(|next| format!(", \"next\": \"{}\"", next)).unwrap_or_default();
+        format!("{{\"data\": [{}], \"head\": \"\", \"link\": \"\", \"paging\": {{\"limit\": 1, \"start\": null{}}}}}", data, next)
+    }
+
+    #[test]
+    fn follows_next_pages() {
+        let state = "{\"address\": \"1cf126\", \"data\": \"\"}";
+        let _first = mock("GET", "/state?limit=1")
+            .with_status(200)
+            .with_body(&page(state, Some(&format!("{}/state?limit=1&start=2", URL))))
+            .create();
+        let _second = mock("GET", "/state?limit=1&start=2")
+            .with_status(200)
+            .with_body(&page(state, Some(&format!("{}/state?limit=1&start=3", URL))))
+            .create();
+        let _third = mock("GET", "/state?limit=1&start=3")
+            .with_status(200)
+            .with_body(&page(state, None))
+            .create();
+
+        let url = with_query(&format!("{}/state", URL), "limit", "1");
+        let all: StateData = read_pages_from_endpoint(&url, None).unwrap();
+        assert_eq!(3, all.get_num_states());
+        assert_eq!(None, all.get_next_page());
+
+        let two: StateData = read_pages_from_endpoint(&url, Some(2)).unwrap();
+        assert_eq!(2, two.get_num_states());
+        assert_eq!(Some(format!("{}/state?limit=1&start=3", URL).as_str()), two.get_next_page());
+    }
+
+    #[test]
+    fn stops_at_page_linking_to_itself() {
+        let url = format!("{}/blocks?start=5", URL);
+        let _m = mock("GET", "/blocks?start=5")
+            .with_status(200)
+            .with_body(&page("", Some(&url)))
+            .create();
+
+        let blocks = read_block_data_from_endpoint(&url).unwrap();
+        assert_eq!(0, blocks.get_num_blocks());
+    }
+
+    #[test]
+    fn error_on_later_page() {
+        let _first = mock("GET", "/transactions")
+            .with_status(200)
+            .with_body(&page("", Some(&format!("{}/transactions?start=2", URL))))
+            .create();
+        let _second = mock("GET", "/transactions?start=2")
+            .with_status(503)
+            .create();
+
+        match read_transaction_list_from_endpoint(&format!("{}/transactions", URL)) {
+            Err(Error::HttpStatus { code, .. }) => assert_eq!(503, code),
+            other => panic!("Expected HttpStatus, got {:?}", other),
+        }
+    }
+}
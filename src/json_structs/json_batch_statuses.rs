@@ -0,0 +1,221 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `json_batch_statuses` contains the structures that represent the JSON structured data from the
+//! `/batch_statuses` endpoint of Hyperledger Sawtooth, which tells whether submitted batches made it into the chain.
+//!
+//! The root [BatchStatusList](struct.BatchStatusList.html) structure holds a [BatchStatus](struct.BatchStatus.html)
+//! for each batch ID that was asked for. A batch that was rejected lists the
+//! [InvalidTransactions](struct.InvalidTransaction.html) that caused it, with the message given by the transaction
+//! processor.
+
+extern crate base64;
+extern crate serde_json;
+
+use colored::*;
+use json_deserialize::{to_hex};
+use super::{label_id, paint};
+
+use std::fmt;
+
+/// A structure that represents the root data item found at the `/batch_statuses` endpoint.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BatchStatusList {
+    data: Vec<BatchStatus>,
+    link: String,
+}
+
+impl BatchStatusList {
+
+    /// Returns the number of statuses contained.
+    pub fn get_num_statuses(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the statuses contained.
+    pub fn get_statuses(&self) -> &[BatchStatus] {
+        &self.data
+    }
+
+    /// Removes the status of every batch whose ID isn't in `ids`. Does nothing if `ids` is empty.
+    pub fn retain_ids(&mut self, ids: &[&str]) {
+        if !ids.is_empty() {
+            self.data.retain(|status| ids.contains(&status.id.as_str()));
+        }
+    }
+
+    /// Display the status of each batch, and the reason that any invalid transactions were rejected.
+    /// Uses colored text so use this for terminal printing. IDs are shortened to their first 6 and last 4
+    /// characters unless `full_id` is set.
+    pub fn display_full_data(&self, full_id: bool) {
+        print!("{}", self.format(full_id, true));
+    }
+
+    /// Display the status of each batch, and the reason that any invalid transactions were rejected.
+    /// No terminal color, so use this for piping to a file.
+    pub fn display_full_data_no_color(&self, full_id: bool) {
+        print!("{}", self.format(full_id, false));
+    }
+
+    /// Returns the statuses as pretty printed JSON, in the same structure as the `/batch_statuses` endpoint.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("batch statuses can always be written as JSON")
+    }
+
+    fn format(&self, full_id: bool, color: bool) -> String {
+        let mut out = String::new();
+        for status in self.data.iter() {
+            let status_color = match status.status {
+                Status::Committed => Color::Green,
+                Status::Invalid => Color::Red,
+                Status::Pending => Color::Yellow,
+                Status::Unknown => Color::White,
+            };
+            out.push_str(&format!("|Batch {}\n", label_id(&status.id, full_id)));
+            out.push_str(&format!("| Status: {}\n", paint(&status.status.to_string(), status_color, color)));
+            for txn in status.invalid_transactions.iter() {
+                out.push_str(&format!("\t|Invalid Transaction {}\n", label_id(&txn.id, full_id)));
+                out.push_str(&format!("\t| Message: {}\n", paint(&txn.message, Color::Red, color)));
+                // The extended data is raw bytes in base64, so it is shown in hex unless it isn't valid base64
+                match txn.extended_data.as_ref().map(|data| (data, base64::decode(data))) {
+                    Some((_, Ok(bytes))) => out.push_str(&format!("\t| Extended Data (hex): {}\n", to_hex(&bytes))),
+                    Some((data, Err(_))) => out.push_str(&format!("\t| Extended Data (base64): {}\n", data)),
+                    None => (),
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A structure that represents the status of one batch.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BatchStatus {
+    id: String,
+    invalid_transactions: Vec<InvalidTransaction>,
+    status: Status,
+}
+
+impl BatchStatus {
+    /// Returns the ID of the batch.
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the status of the batch.
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    /// Returns the transactions that caused the batch to be rejected.
+    pub fn get_invalid_transactions(&self) -> &[InvalidTransaction] {
+        &self.invalid_transactions
+    }
+}
+
+/// Where a batch is in its way into the chain.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Status {
+    /// The batch is part of a block in the chain.
+    Committed,
+    /// The batch was rejected, see its invalid transactions.
+    Invalid,
+    /// The batch was received, but hasn't been committed yet.
+    Pending,
+    /// The validator doesn't know the batch, e.g. it was never submitted.
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Status::Committed => "COMMITTED",
+            Status::Invalid => "INVALID",
+            Status::Pending => "PENDING",
+            Status::Unknown => "UNKNOWN",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A structure that represents a transaction that was rejected by its transaction processor.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct InvalidTransaction {
+    id: String,
+    message: String,
+    /// Extra data about the error in base64, whose meaning depends on the transaction family.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extended_data: Option<String>,
+}
+
+impl InvalidTransaction {
+    /// Returns the ID of the transaction.
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the reason the transaction was rejected.
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+}
+
+#[cfg(test)]
+mod test_batch_statuses {
+    use super::*;
+
+    const INVALID: &str = "{
+        \"data\": [
+            {\"id\": \"aaaaaaaaaaaaaaaa\", \"invalid_transactions\": [], \"status\": \"PENDING\"},
+            {\"id\": \"bbbbbbbbbbbbbbbb\", \"invalid_transactions\": [
+                {\"id\": \"cccccccccccccccc\", \"message\": \"Verb is 'inc' but Name not in state\", \"extended_data\": \"AQI=\"}
+            ], \"status\": \"INVALID\"}
+        ],
+        \"link\": \"\"
+    }";
+
+    #[test]
+    fn reads_example_file() {
+        let data = ::json_reader::read_batch_statuses_from_file("example-blockchain/batch_statuses.json").unwrap();
+        assert_eq!(2, data.get_num_statuses());
+        assert!(data.get_statuses().iter().all(|status| status.get_status() == Status::Committed));
+    }
+
+    #[test]
+    fn reads_invalid_transactions() {
+        let data: BatchStatusList = serde_json::from_str(INVALID).unwrap();
+        assert_eq!(Status::Pending, data.get_statuses()[0].get_status());
+        let invalid = &data.get_statuses()[1].get_invalid_transactions()[0];
+        assert_eq!("cccccccccccccccc", invalid.get_id());
+        assert_eq!("Verb is 'inc' but Name not in state", invalid.get_message());
+    }
+
+    #[test]
+    fn formats_status_and_messages() {
+        let data: BatchStatusList = serde_json::from_str(INVALID).unwrap();
+        let text = data.format(false, false);
+        assert_eq!("|Batch aaaaaa...aaaa\n| Status: PENDING\n\
+            |Batch bbbbbb...bbbb\n| Status: INVALID\n\
+            \t|Invalid Transaction cccccc...cccc\n\t| Message: Verb is 'inc' but Name not in state\n\t| Extended Data (hex): 0102\n", text);
+    }
+
+    #[test]
+    fn retains_ids() {
+        let mut data: BatchStatusList = serde_json::from_str(INVALID).unwrap();
+        data.retain_ids(&[]);
+        assert_eq!(2, data.get_num_statuses());
+        data.retain_ids(&["bbbbbbbbbbbbbbbb"]);
+        assert_eq!(1, data.get_num_statuses());
+        assert_eq!("bbbbbbbbbbbbbbbb", data.get_statuses()[0].get_id());
+    }
+
+    #[test]
+    fn unknown_status_is_an_error() {
+        assert!(serde_json::from_str::<Status>("\"LOST\"").is_err());
+        assert_eq!("\"COMMITTED\"", serde_json::to_string(&Status::Committed).unwrap());
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `json_batches` contains the structure that represents the JSON structured data from the
+//! `/batches` endpoint of Hyperledger Sawtooth, along with methods for displaying it.
+//!
+//! The endpoint lists batches on their own, without the blocks that contain them, so the root
+//! [BatchList](struct.BatchList.html) structure reuses the [Batch](../json_blocks/struct.Batch.html)
+//! structure of the `/blocks` endpoint. Use [display_full_data](struct.BatchList.html#method.display_full_data) when
+//! printing to the terminal, and [display_full_data_no_color](struct.BatchList.html#method.display_full_data_no_color)
+//! if you want to pipe the output to a file. [to_json](struct.BatchList.html#method.to_json),
+//! [to_html](struct.BatchList.html#method.to_html) and [to_browser](struct.BatchList.html#method.to_browser) work the
+//! same way as they do for [BlockData](../json_blocks/struct.BlockData.html).
+
+extern crate serde_json;
+
+use super::json_blocks::{Batch, BatchOutput, Paging};
+use super::Paged;
+use browser::{Browser};
+use decoder_selection::{DecoderSelection};
+use html;
+use json_deserialize::*;
+
+/// A structure that represents the root data item found at the `/batches` endpoint.
+///
+/// Batches aren't part of a block here, so there is no genesis block to leave out. The `show_genesis` setting
+/// taken by the display methods is ignored, and every payload is decoded.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BatchList {
+    data: Vec<Batch>,
+    head: String,
+    link: String,
+    paging: Paging
+}
+
+impl BatchList {
+
+    /// Returns the number of batches contained.
+    pub fn get_num_batches(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Display the individual batches and the transactions contained within them.
+    /// Uses colored text so use this for terminal printing.
+    ///
+    /// If instead you want to pipe this data to a file, see [display_full_data_no_color](struct.BatchList.html#method.display_full_data_no_color)
+    /// since it prints with no coloring.
+    ///
+    /// The function takes in the same tuple (full_id, show_genesis, decoders) of settings as
+    /// [BlockData::display_full_data](../json_blocks/struct.BlockData.html#method.display_full_data), except that
+    /// `show_genesis` is ignored.
+    pub fn display_full_data(&self, (full_id, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) {
+        for (count, batch) in self.data.iter().enumerate() {
+            batch.display(count, 0, false, (full_id, decoders), registry);
+        }
+    }
+
+    /// Display the individual batches and the transactions contained within them.
+    /// No terminal color, so use this for piping to a file.
+    ///
+    /// If instead you want to print this to a terminal window, see [display_full_data](struct.BatchList.html#method.display_full_data)
+    /// since it prints with coloring.
+    ///
+    /// The function takes in the same tuple (full_id, show_genesis, decoders) of settings as
+    /// [BlockData::display_full_data](../json_blocks/struct.BlockData.html#method.display_full_data), except that
+    /// `show_genesis` is ignored.
+    pub fn display_full_data_no_color(&self, (full_id, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) {
+        for (count, batch) in self.data.iter().enumerate() {
+            batch.display_no_color(count, 0, false, (full_id, decoders), registry);
+        }
+    }
+
+    /// Returns the batches as pretty printed JSON, in the same structure as the `/batches` endpoint. Each
+    /// transaction has extra `decoded_payload` and `payload_sha512_matches` elements, as described in
+    /// [BlockData::to_json](../json_blocks/struct.BlockData.html#method.to_json).
+    pub fn to_json(&self, (_, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> String {
+        let output = BatchListOutput {
+            data: self.data.iter().map(|batch| BatchOutput::new(batch, false, decoders, registry)).collect(),
+            head: &self.head,
+            link: &self.link,
+            paging: &self.paging,
+        };
+        serde_json::to_string_pretty(&output).expect("batches can always be written as JSON")
+    }
+
+    /// Returns the batches as a single static HTML page, with a collapsible section for every batch and transaction.
+    /// See [BlockData::to_html](../json_blocks/struct.BlockData.html#method.to_html).
+    pub fn to_html(&self, (full_id, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> String {
+        let mut body = String::new();
+        for (count, batch) in self.data.iter().enumerate() {
+            batch.write_html(&mut body, count, false, (full_id, decoders), registry);
+        }
+        html::page("Batches", &body)
+    }
+
+    /// Returns an interactive [Browser](../../browser/struct.Browser.html) that lists the batches, and shows the
+    /// transactions of the selected batch. See [BlockData::to_browser](../json_blocks/struct.BlockData.html#method.to_browser).
+    pub fn to_browser(&self, (full_id, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> Browser {
+        let batches = self.data.iter().enumerate()
+            .map(|(count, batch)| batch.to_node(count, false, (full_id, decoders), registry))
+            .collect();
+        Browser::new("Batches", batches)
+    }
+}
+
+impl Paged for BatchList {
+    fn get_next_page(&self) -> Option<&str> {
+        self.paging.get_next()
+    }
+
+    fn append_page(&mut self, page: BatchList) {
+        self.data.extend(page.data);
+        self.paging.append(page.paging);
+    }
+}
+
+/// The JSON output of a [BatchList](struct.BatchList.html).
+#[derive(Serialize)]
+struct BatchListOutput<'a> {
+    data: Vec<BatchOutput<'a>>,
+    head: &'a str,
+    link: &'a str,
+    paging: &'a Paging,
+}
+
+/// A structure that represents the data found at the `/batches/{batch_id}` endpoint, which holds a single batch.
+///
+/// Convert it into a [BatchList](struct.BatchList.html) to display the batch. The response doesn't name the head
+/// of the chain, so the `head` of the converted list is empty.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BatchResponse {
+    data: Batch,
+    link: String,
+}
+
+impl BatchResponse {
+    /// Returns the batch held by the response.
+    pub fn get_batch(&self) -> &Batch {
+        &self.data
+    }
+}
+
+impl From<BatchResponse> for BatchList {
+    fn from(response: BatchResponse) -> BatchList {
+        BatchList { data: vec![response.data], head: String::new(), link: response.link, paging: Paging::default() }
+    }
+}
+
+#[cfg(test)]
+mod test_batchlist_struct {
+    use super::*;
+
+    #[test]
+    fn num_batches() {
+        let data = ::json_reader::read_batch_list_from_file("example-blockchain/batches.json").unwrap();
+        assert_eq!(6, data.get_num_batches());
+    }
+
+    #[test]
+    fn num_batches_empty() {
+        assert_eq!(0, BatchList::default().get_num_batches());
+    }
+
+    #[test]
+    fn from_single_batch() {
+        let response = ::json_reader::read_batch_from_file("example-blockchain/batch.json").unwrap();
+        assert_eq!(1, BatchList::from(response).get_num_batches());
+    }
+}
+
+#[cfg(test)]
+mod test_batchlist_output {
+    use super::*;
+    use super::serde_json::Value;
+
+    #[test]
+    fn json_includes_decoded_payloads() {
+        let data = ::json_reader::read_batch_list_from_file("example-blockchain/batches.json").unwrap();
+        let registry = DecoderRegistry::default();
+        let mut decoders = DecoderSelection::new("cbor");
+        decoders.add_well_known_family_rules(&registry);
+        let json: Value = serde_json::from_str(&data.to_json((false, false, &decoders), &registry)).unwrap();
+
+        let batches = json["data"].as_array().unwrap();
+        assert_eq!(6, batches.len());
+        assert_eq!("num1", batches[0]["transactions"][0]["decoded_payload"]["value"]["Name"]);
+        // The settings batch from the genesis block is decoded as well
+        assert_eq!("sawtooth_settings", batches[5]["transactions"][0]["decoded_payload"]["decoder"]);
+        assert_eq!("valid", batches[5]["signature"]);
+        assert_eq!(true, batches[5]["transactions"][0]["batcher_matches"]);
+        assert!(json["head"].is_string());
+    }
+
+    #[test]
+    fn html_has_a_section_per_batch() {
+        let data = ::json_reader::read_batch_list_from_file("example-blockchain/batches.json").unwrap();
+        let html = data.to_html((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert_eq!(6, html.matches("<details class=\"batch\">").count());
+        assert!(html.contains("<title>Batches</title>"));
+    }
+
+    #[test]
+    fn browser_lists_batches() {
+        let data = ::json_reader::read_batch_list_from_file("example-blockchain/batches.json").unwrap();
+        let browser = data.to_browser((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert_eq!(6, browser.get_num_nodes());
+        let batch = browser.get_selected().unwrap();
+        assert!(batch.get_label().starts_with("Batch 0 "));
+        assert_eq!(1, batch.get_children().len());
+    }
+}
@@ -11,13 +11,29 @@
 //! [BlockData](struct.BlockData.html) structure. That structure contains methods for printing out
 //! the contents of the blockchain. Use [display_full_data](struct.BlockData.html#method.display_full_data) when 
 //! printing to the terminal, and [display_full_data_no_color](struct.BlockData.html#method.display_full_data_no_color)
-//! if you want to pipe the output to a file.
+//! if you want to pipe the output to a file. Use [to_json](struct.BlockData.html#method.to_json) for output
+//! that other programs can read, [to_dot](struct.BlockData.html#method.to_dot) to draw the chain as a graph, and
+//! [to_html](struct.BlockData.html#method.to_html) for a report that can be browsed. To page through a large chain
+//! in the terminal, use [to_browser](struct.BlockData.html#method.to_browser).
 
+extern crate base64;
+extern crate serde_json;
+
+use browser::{Browser, Node};
 use colored::*;
+use decoder_selection::{DecoderSelection};
+use html;
 use json_deserialize::*;
+use signatures::{self, SignatureStatus};
+use super::{DecodedPayload, Paged, label_id, sha512_hex};
+
+use serde::{de, Deserialize, Deserializer};
+
+use std::collections::HashSet;
+use std::fmt::Write;
 
 /// A structure that represents the root data item found at the `/blocks` endpoint.
-#[derive(Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct BlockData {
     data: Vec<Block>,
     head: String,
@@ -32,20 +48,33 @@ impl BlockData {
         self.data.len()
     }
 
+    /// Returns the blocks contained, from the newest to the oldest as listed by the `/blocks` endpoint.
+    pub fn get_blocks(&self) -> &[Block] {
+        &self.data
+    }
+
+    /// Returns the blocks contained, giving up the rest of the data.
+    pub fn into_blocks(self) -> Vec<Block> {
+        self.data
+    }
+
     /// Display the individual blocks, their batches, and the transaction contained within them.
     /// Uses colored text so use this for terminal printing.
     /// 
     /// If instead you want to pipe this data to a file, see [display_full_data_no_color](struct.BlockData.html#method.display_full_data_no_color)
     /// since it prints with no coloring.
     /// 
-    /// The function takes in a tuple (full_id, show_genesis, method) of settings:
+    /// The function takes in a tuple (full_id, show_genesis, decoders) of settings:
     /// - `full_id` -> Setting this to false will only print the first 6 and last 4 characters of IDs and Pubkeys, 
-    ///     otherwise the full string is shown.
+    ///   otherwise the full string is shown.
     /// - `show_genesis` -> Setting this to false will omit the genesis block that sets blockchain setting data. It is
-    ///     recommended that this stay false as its serialization will often be different from the rest of the data.
-    /// - `method` -> What method to use when deserializing data. See
-    ///     [supported methods](../../index.html#supported-deserialization-methods) for a list of valid options.
-    pub fn display_full_data(&self, (full_id, show_genesis, method): (bool, bool, String)) {
+    ///   recommended that this stay false as its serialization will often be different from the rest of the data.
+    /// - `decoders` -> Which [decoder](../../decoder_selection/struct.DecoderSelection.html) to use for each transaction,
+    ///   based on its family name and version. See [supported methods](../../index.html#supported-deserialization-methods)
+    ///   for a list of valid options. The genesis block is only decoded if its family is mapped to a decoder.
+    ///
+    /// The decoder names are looked up in `registry`, which holds the available [decoders](../../json_deserialize/trait.PayloadDecoder.html).
+    pub fn display_full_data(&self, (full_id, show_genesis, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) {
         
         // Figure out when to stop displaying arrows.
         let last_block_num = match show_genesis { true => "0", false => "1"};
@@ -54,16 +83,12 @@ impl BlockData {
             // Only print out the first block if show_genesis is true
             if show_genesis || block.header.block_num != "0" {
                 println!("{}{}{}", "|Block ".green().bold().on_black(), block.header.block_num.green().bold().on_black(), " ".on_black());
-                if full_id {
-                    println!("| ID: {}", block.header_signature.magenta());
-                    println!("| Previous Block ID: {}", block.header.previous_block_id.magenta());
-                    println!("| Signer Pub Key: {}", block.header.signer_public_key);
-                } else {
-                    // If not full_id, only print out the first 6, and last 4 characters
-                    // Ok to do this since the following are always expected to be byte strings
-                    println!("| ID: {}...{}", &block.header_signature[0..6].magenta(), &block.header_signature[(block.header_signature.len() - 4)..].magenta());
-                    println!("| Previous Block ID: {}...{}", &block.header.previous_block_id[0..6].magenta(), &block.header.previous_block_id[(block.header.previous_block_id.len() - 4)..].magenta());
-                    println!("| Signer Pub Key: {}...{}", &block.header.signer_public_key[0..6], &block.header.signer_public_key[(block.header.signer_public_key.len() - 4)..]);
+                println!("| ID: {}", label_id(&block.header_signature, full_id).magenta());
+                println!("| Previous Block ID: {}", label_id(&block.header.previous_block_id, full_id).magenta());
+                println!("| Signer Pub Key: {}", label_id(&block.header.signer_public_key, full_id));
+                let signature = block.check_signature();
+                if !signature.is_valid() {
+                    println!("| {}", format!("Signature: {}", signature.describe()).red().bold());
                 }
 
                 match block.get_num_batches() {
@@ -72,45 +97,7 @@ impl BlockData {
                 }
 
                 for (count, batch) in block.batches.iter().enumerate() {
-                    println!("\t{}{}{}", "|Batch ".green().bold().on_black(), count.to_string().green().bold().on_black(), " ".on_black());
-                    if full_id {
-                        println!("\t| ID: {}", batch.header_signature);
-                        println!("\t| Signer Pub Key: {}", batch.header.signer_public_key);
-                    } else {
-                        println!("\t| ID: {}...{}", &batch.header_signature[0..6], &batch.header_signature[(batch.header_signature.len() - 4)..]);
-                        println!("\t| Signer Pub Key: {}...{}", &batch.header.signer_public_key[0..6], &batch.header.signer_public_key[(batch.header.signer_public_key.len() - 4)..]);
-                    }
-
-                    match batch.get_num_txns() {
-                    1 => println!("\t| There is 1 transaction in this batch"),
-                    count => println!("\t| There are {} transactions in this batch", count),
-                    }
-
-                    for (count, txn) in batch.transactions.iter().enumerate() {
-                        println!("\t\t{}{}{}", "|Transaction ".green().bold().on_black(), count.to_string().green().bold().on_black(), " ".on_black());
-                        if full_id {
-                            println!("\t\t| ID: {}", txn.header_signature);
-                            println!("\t\t| Signer Pub Key: {}", txn.header.signer_public_key);
-                        } else {
-                            println!("\t\t| ID: {}...{}", &txn.header_signature[0..6], &txn.header_signature[(txn.header_signature.len() - 4)..]);
-                            println!("\t\t| Signer Pub Key: {}...{}", &txn.header.signer_public_key[0..6], &txn.header.signer_public_key[(txn.header.signer_public_key.len() - 4)..]);
-                        }
-
-                        // Deserialize the payload according to the passed in method
-                        let payload_encoded = String::from(txn.payload.as_ref());
-
-                        // If we are printing out the genesis block, don't deserialize the payload
-                        if show_genesis && block.header.block_num == "0" {
-                            println!("\t\t| Payload:\n{}", payload_encoded.blue())
-                        } else {
-                            match method.as_str() {
-                                "cbor" => println!("\t\t| Payload:\n{}", parse_cbor(payload_encoded, 3).blue()),
-                                "json" => println!("\t\t| Payload:\n{}", parse_json(payload_encoded, 3).blue()),
-                                "custom" => println!("\t\t| Payload:\n{}", parse_custom(payload_encoded, 3).blue()),
-                                _ => panic!("Unsupported deserialization method: {}", method)
-                            }
-                        }
-                    }
+                    batch.display(count, 1, block.header.block_num == "0", (full_id, decoders), registry);
                 }
                 // Display an arrow until we get to the last block
                 if block.header.block_num != last_block_num {println!("{}", "\t\t| |\n\t\t| |\n\t\t\\ /\n\t\t V \n".green());}
@@ -124,14 +111,17 @@ impl BlockData {
     /// If instead you want to print this to a terminal window, see [display_full_data_no_color](struct.BlockData.html#method.display_full_data)
     /// since it prints with coloring.
     /// 
-    /// The function takes in a tuple (full_id, show_genesis, method) of settings:
+    /// The function takes in a tuple (full_id, show_genesis, decoders) of settings:
     /// - `full_id` -> Setting this to false will only print the first 6 and last 4 characters of IDs and Pubkeys, 
-    ///     otherwise the full string is shown.
+    ///   otherwise the full string is shown.
     /// - `show_genesis` -> Setting this to false will omit the genesis block that sets blockchain setting data. It is
-    ///     recommended that this stay false as its serialization will often be different from the rest of the data.
-    /// - `method` -> What method to use when deserializing data. See
-    ///     [supported methods](../../index.html#supported-deserialization-methods) for a list of valid options.
-    pub fn display_full_data_no_color(&self, (full_id, show_genesis, method): (bool, bool, String)) {
+    ///   recommended that this stay false as its serialization will often be different from the rest of the data.
+    /// - `decoders` -> Which [decoder](../../decoder_selection/struct.DecoderSelection.html) to use for each transaction,
+    ///   based on its family name and version. See [supported methods](../../index.html#supported-deserialization-methods)
+    ///   for a list of valid options. The genesis block is only decoded if its family is mapped to a decoder.
+    ///
+    /// The decoder names are looked up in `registry`, which holds the available [decoders](../../json_deserialize/trait.PayloadDecoder.html).
+    pub fn display_full_data_no_color(&self, (full_id, show_genesis, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) {
         
         // Figure out when to stop displaying arrows.
         let last_block_num = match show_genesis { true => "0", false => "1"};
@@ -139,17 +129,13 @@ impl BlockData {
         for block in self.data.iter() {
             // Only print out the first block if show_genesis is true
             if show_genesis || block.header.block_num != "0" {
-                println!("{}{}{}", "|Block ", block.header.block_num, " ");
-                if full_id {
-                    println!("| ID: {}", block.header_signature);
-                    println!("| Previous Block ID: {}", block.header.previous_block_id);
-                    println!("| Signer Pub Key: {}", block.header.signer_public_key);
-                } else {
-                    // If not full_id, only print out the first 6, and last 4 characters
-                    // Ok to do this since the following are always expected to be byte strings
-                    println!("| ID: {}...{}", &block.header_signature[0..6], &block.header_signature[(block.header_signature.len() - 4)..]);
-                    println!("| Previous Block ID: {}...{}", &block.header.previous_block_id[0..6], &block.header.previous_block_id[(block.header.previous_block_id.len() - 4)..]);
-                    println!("| Signer Pub Key: {}...{}", &block.header.signer_public_key[0..6], &block.header.signer_public_key[(block.header.signer_public_key.len() - 4)..]);
+                println!("|Block {} ", block.header.block_num);
+                println!("| ID: {}", label_id(&block.header_signature, full_id));
+                println!("| Previous Block ID: {}", label_id(&block.header.previous_block_id, full_id));
+                println!("| Signer Pub Key: {}", label_id(&block.header.signer_public_key, full_id));
+                let signature = block.check_signature();
+                if !signature.is_valid() {
+                    println!("| Signature: {}", signature.describe());
                 }
 
                 match block.get_num_batches() {
@@ -158,55 +144,292 @@ impl BlockData {
                 }
 
                 for (count, batch) in block.batches.iter().enumerate() {
-                    println!("\t{}{}{}", "|Batch ", count.to_string(), " ");
-                    if full_id {
-                        println!("\t| ID: {}", batch.header_signature);
-                        println!("\t| Signer Pub Key: {}", batch.header.signer_public_key);
-                    } else {
-                        println!("\t| ID: {}...{}", &batch.header_signature[0..6], &batch.header_signature[(batch.header_signature.len() - 4)..]);
-                        println!("\t| Signer Pub Key: {}...{}", &batch.header.signer_public_key[0..6], &batch.header.signer_public_key[(batch.header.signer_public_key.len() - 4)..]);
-                    }
+                    batch.display_no_color(count, 1, block.header.block_num == "0", (full_id, decoders), registry);
+                }
+                // Display an arrow until we get to the last block
+                if block.header.block_num != last_block_num {println!("\t\t| |\n\t\t| |\n\t\t\\ /\n\t\t V \n");}
+            }
+        }
+    }
 
-                    match batch.get_num_txns() {
-                    1 => println!("\t| There is 1 transaction in this batch"),
-                    count => println!("\t| There are {} transactions in this batch", count),
-                    }
+    /// Returns the blocks as pretty printed JSON, in the same structure as the `/blocks` endpoint. Each
+    /// transaction has an extra `decoded_payload` element with the name of the `decoder` used, and either
+    /// the decoded `value` or the `error` that stopped the payload from being decoded. `payload_sha512_matches` tells
+    /// whether the hash of the payload is the `payload_sha512` in the header. Every block, batch and transaction has a
+    /// `signature` element with the [status](../../signatures/enum.SignatureStatus.html) of its `header_signature`, and
+    /// `batcher_matches` tells whether each transaction names the signer of its batch as the batcher.
+    ///
+    /// The function takes in the same tuple (full_id, show_genesis, decoders) of settings as
+    /// [display_full_data](struct.BlockData.html#method.display_full_data). IDs are always written in full, so `full_id`
+    /// is ignored. Transactions of the genesis block have no `decoded_payload` unless their family is mapped to a decoder.
+    pub fn to_json(&self, (_, show_genesis, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> String {
+        let blocks = self.data.iter()
+            .filter(|block| show_genesis || block.header.block_num != "0")
+            .map(|block| BlockOutput {
+                header: &block.header,
+                header_signature: &block.header_signature,
+                signature: block.check_signature(),
+                batches: block.batches.iter()
+                    .map(|batch| BatchOutput::new(batch, block.header.block_num == "0", decoders, registry))
+                    .collect(),
+            })
+            .collect();
 
-                    for (count, txn) in batch.transactions.iter().enumerate() {
-                        println!("\t\t{}{}{}", "|Transaction ", count.to_string(), " ");
-                        if full_id {
-                            println!("\t\t| ID: {}", txn.header_signature);
-                            println!("\t\t| Signer Pub Key: {}", txn.header.signer_public_key);
-                        } else {
-                            println!("\t\t| ID: {}...{}", &txn.header_signature[0..6], &txn.header_signature[(txn.header_signature.len() - 4)..]);
-                            println!("\t\t| Signer Pub Key: {}...{}", &txn.header.signer_public_key[0..6], &txn.header.signer_public_key[(txn.header.signer_public_key.len() - 4)..]);
-                        }
+        let output = BlockDataOutput { data: blocks, head: &self.head, link: &self.link, paging: &self.paging };
+        serde_json::to_string_pretty(&output).expect("blocks can always be written as JSON")
+    }
 
-                        // Deserialize the payload according to the passed in method
-                        let payload_encoded = String::from(txn.payload.as_ref());
-
-                        // If we are printing out the genesis block, don't deserialize the payload
-                        if show_genesis && block.header.block_num == "0" {
-                            println!("\t\t| Payload:\n{}", payload_encoded)
-                        } else {
-                            match method.as_str() {
-                                "cbor" => println!("\t\t| Payload:\n{}", parse_cbor(payload_encoded, 3)),
-                                "json" => println!("\t\t| Payload:\n{}", parse_json(payload_encoded, 3)),
-                                "custom" => println!("\t\t| Payload:\n{}", parse_custom(payload_encoded, 3)),
-                                _ => panic!("Unsupported deserialization method: {}", method)
-                            }
-                        } 
+    /// Returns the blocks as a [Graphviz](https://graphviz.org) DOT graph, which can be rendered with e.g. `dot -Tsvg`.
+    ///
+    /// Each block points to the block in its `previous_block_id`, and contains its batches, which in turn contain
+    /// their transactions. Transactions point to the transactions in their `dependencies` with a dotted edge. Dependencies
+    /// that aren't part of the data are drawn as dashed nodes.
+    ///
+    /// The function takes in the same tuple (full_id, show_genesis, decoders) of settings as
+    /// [display_full_data](struct.BlockData.html#method.display_full_data). `full_id` decides whether the labels
+    /// show the full IDs, and `decoders` is ignored since payloads aren't part of the graph.
+    pub fn to_dot(&self, (full_id, show_genesis, _): (bool, bool, &DecoderSelection)) -> String {
+        let blocks: Vec<&Block> = self.data.iter()
+            .filter(|block| show_genesis || block.header.block_num != "0")
+            .collect();
+        let txn_ids: HashSet<&str> = blocks.iter()
+            .flat_map(|block| block.batches.iter())
+            .flat_map(|batch| batch.transactions.iter())
+            .map(|txn| txn.header_signature.as_str())
+            .collect();
+
+        let mut dot = String::from("digraph blockchain {\n\tnode [shape=box];\n");
+        let mut missing_dependencies = HashSet::new();
+
+        // Every ID is quoted in the graph, so it is escaped like the labels are
+        for block in blocks.iter() {
+            let block_id = dot_escape(&block.header_signature);
+            let (flags, color) = dot_flags(&[(!block.check_signature().is_valid(), "invalid signature")]);
+            let _ = writeln!(dot, "\t\"{}\" [label=\"Block {}\\n{}{}\", style=bold{}];", block_id,
+                dot_escape(&block.header.block_num), dot_escape(&label_id(&block.header_signature, full_id)), flags, color);
+            if blocks.iter().any(|other| other.header_signature == block.header.previous_block_id) {
+                let _ = writeln!(dot, "\t\"{}\" -> \"{}\" [label=\"previous\", style=bold];", block_id, dot_escape(&block.header.previous_block_id));
+            }
+
+            for (count, batch) in block.batches.iter().enumerate() {
+                let batch_id = dot_escape(&batch.header_signature);
+                let (flags, color) = dot_flags(&[(!batch.check_signature().is_valid(), "invalid signature")]);
+                let _ = writeln!(dot, "\t\"{}\" [label=\"Batch {}\\n{}{}\", shape=folder{}];", batch_id, count,
+                    dot_escape(&label_id(&batch.header_signature, full_id)), flags, color);
+                let _ = writeln!(dot, "\t\"{}\" -> \"{}\";", block_id, batch_id);
+
+                for txn in batch.transactions.iter() {
+                    let txn_id = dot_escape(&txn.header_signature);
+                    let (flags, color) = dot_flags(&[
+                        (!txn.payload_matches_hash(), "payload SHA-512 mismatch"),
+                        (!txn.check_signature().is_valid(), "invalid signature"),
+                        (!batch.is_batcher_of(txn), "batcher mismatch"),
+                    ]);
+                    let _ = writeln!(dot, "\t\"{}\" [label=\"{} {}\\n{}{}\", shape=note{}];", txn_id,
+                        dot_escape(&txn.header.family_name), dot_escape(&txn.header.family_version), dot_escape(&label_id(&txn.header_signature, full_id)), flags, color);
+                    let _ = writeln!(dot, "\t\"{}\" -> \"{}\";", batch_id, txn_id);
+
+                    for dependency in txn.header.dependencies.iter() {
+                        if !txn_ids.contains(dependency.as_str()) && missing_dependencies.insert(dependency.as_str()) {
+                            let _ = writeln!(dot, "\t\"{}\" [label=\"{}\", shape=note, style=dashed];", dot_escape(dependency), dot_escape(&label_id(dependency, full_id)));
+                        }
+                        let _ = writeln!(dot, "\t\"{}\" -> \"{}\" [label=\"depends on\", style=dotted];", txn_id, dot_escape(dependency));
                     }
                 }
-                // Display an arrow until we get to the last block
-                if block.header.block_num != last_block_num {println!("{}", "\t\t| |\n\t\t| |\n\t\t\\ /\n\t\t V \n");}
             }
         }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Returns the blocks as a single static HTML page, which can be opened in any browser without other files.
+    ///
+    /// Every block, batch and transaction is a collapsible section, with the decoded payload shown inside its
+    /// transaction. IDs and keys show their full value on hover, and each block links to the block in its
+    /// `previous_block_id` and to the block that follows it, when those are part of the page.
+    ///
+    /// The function takes in the same tuple (full_id, show_genesis, decoders) of settings as
+    /// [display_full_data](struct.BlockData.html#method.display_full_data). Payloads of the genesis block are shown
+    /// in base64 unless their family is mapped to a decoder.
+    pub fn to_html(&self, (full_id, show_genesis, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> String {
+        let blocks: Vec<&Block> = self.data.iter()
+            .filter(|block| show_genesis || block.header.block_num != "0")
+            .collect();
+        // Links to another block are only written if that block is on the page
+        let block_link = |id: &str| match blocks.iter().any(|block| block.header_signature == id) {
+            true => format!("<a href=\"#block-{}\">{}</a>", html::escape(id), html::id_span(id, full_id)),
+            false => html::id_span(id, full_id),
+        };
+
+        let mut body = String::new();
+        for block in blocks.iter() {
+            let _ = writeln!(body, "<details class=\"block\" id=\"block-{}\" open>", html::escape(&block.header_signature));
+            let signature = block.check_signature();
+            let _ = writeln!(body, "<summary>Block {} {}{}</summary>", html::escape(&block.header.block_num),
+                html::id_span(&block.header_signature, full_id), html::invalid_signature_flag(signature));
+            let _ = writeln!(body, "<dl>");
+            let _ = writeln!(body, "<dt>Previous Block</dt><dd>{}</dd>", block_link(&block.header.previous_block_id));
+            if let Some(next) = blocks.iter().find(|next| next.header.previous_block_id == block.header_signature) {
+                let _ = writeln!(body, "<dt>Next Block</dt><dd>{}</dd>", block_link(&next.header_signature));
+            }
+            let _ = writeln!(body, "<dt>Signer Pub Key</dt><dd>{}</dd>", html::id_span(&block.header.signer_public_key, full_id));
+            let _ = writeln!(body, "{}", html::signature_field(signature));
+            let _ = writeln!(body, "<dt>State Root Hash</dt><dd>{}</dd>", html::id_span(&block.header.state_root_hash, full_id));
+            let _ = writeln!(body, "</dl>");
+
+            for (count, batch) in block.batches.iter().enumerate() {
+                batch.write_html(&mut body, count, block.header.block_num == "0", (full_id, decoders), registry);
+            }
+            let _ = writeln!(body, "</details>");
+        }
+        html::page("Blocks", &body)
+    }
+
+    /// Returns an interactive [Browser](../../browser/struct.Browser.html) over the blocks, which lists the blocks
+    /// and shows the batches and transactions of the selected block as a tree. Selecting a transaction shows every
+    /// field of its header, e.g. its inputs, outputs and dependencies, followed by its decoded payload.
+    ///
+    /// The function takes in the same tuple (full_id, show_genesis, decoders) of settings as
+    /// [display_full_data](struct.BlockData.html#method.display_full_data). `full_id` only shortens the IDs in the
+    /// list and tree, since the details always show them in full. Payloads of the genesis block are shown in base64
+    /// unless their family is mapped to a decoder.
+    pub fn to_browser(&self, (full_id, show_genesis, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> Browser {
+        let blocks = self.data.iter()
+            .filter(|block| show_genesis || block.header.block_num != "0")
+            .map(|block| {
+                let batches = block.batches.iter().enumerate()
+                    .map(|(count, batch)| batch.to_node(count, block.header.block_num == "0", (full_id, decoders), registry))
+                    .collect();
+                let signature = block.check_signature();
+                Node::new(
+                    format!("Block {} {}{}", block.header.block_num, label_id(&block.header_signature, full_id), signature_label(signature)),
+                    vec![
+                        ("ID", block.header_signature.clone()),
+                        ("Previous Block ID", block.header.previous_block_id.clone()),
+                        ("Signer Pub Key", block.header.signer_public_key.clone()),
+                        ("Signature", String::from(signature.describe())),
+                        ("State Root Hash", block.header.state_root_hash.clone()),
+                        ("Consensus", block.header.consensus.clone()),
+                        ("Batch IDs", block.header.batch_ids.join("\n")),
+                    ],
+                    None,
+                    batches)
+            })
+            .collect();
+        Browser::new("Blocks", blocks)
+    }
+}
+
+/// Returns the suffix of a browser label for a header with the `signature` status, which is empty if it's valid.
+fn signature_label(signature: SignatureStatus) -> &'static str {
+    match signature {
+        SignatureStatus::Valid => "",
+        SignatureStatus::Invalid => " (invalid signature)",
+        _ => " (malformed signature)",
+    }
+}
+
+/// Returns the lines to add to a DOT label for each flag that is set, and the attributes that draw the node in red if
+/// any of them is.
+fn dot_flags(flags: &[(bool, &str)]) -> (String, &'static str) {
+    let lines: String = flags.iter()
+        .filter(|&&(set, _)| set)
+        .map(|&(_, flag)| format!("\\n{}", flag))
+        .collect();
+    match lines.is_empty() {
+        true => (lines, ""),
+        false => (lines, ", color=red, fontcolor=red"),
+    }
+}
+
+/// Escapes the characters of `s` that have a meaning inside a quoted DOT string.
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Paged for BlockData {
+    fn get_next_page(&self) -> Option<&str> {
+        self.paging.get_next()
+    }
+
+    fn append_page(&mut self, page: BlockData) {
+        self.data.extend(page.data);
+        self.paging.append(page.paging);
+    }
+}
+
+/// The JSON output of a [BlockData](struct.BlockData.html), with the blocks that are shown.
+#[derive(Serialize)]
+struct BlockDataOutput<'a> {
+    data: Vec<BlockOutput<'a>>,
+    head: &'a str,
+    link: &'a str,
+    paging: &'a Paging,
+}
+
+/// The JSON output of a [Block](struct.Block.html).
+#[derive(Serialize)]
+struct BlockOutput<'a> {
+    batches: Vec<BatchOutput<'a>>,
+    header: &'a BlockHeader,
+    header_signature: &'a str,
+    signature: SignatureStatus,
+}
+
+/// The JSON output of a [Batch](struct.Batch.html).
+#[derive(Serialize)]
+pub(super) struct BatchOutput<'a> {
+    header: &'a BatchHeader,
+    header_signature: &'a str,
+    signature: SignatureStatus,
+    trace: bool,
+    transactions: Vec<TransactionOutput<'a>>,
+}
+
+impl<'a> BatchOutput<'a> {
+    /// Returns the output of `batch`, decoding the payload of each transaction. Transactions of a batch `in_genesis`
+    /// have no decoded payload unless their family is mapped to a decoder.
+    pub(super) fn new(batch: &'a Batch, in_genesis: bool, decoders: &'a DecoderSelection, registry: &DecoderRegistry) -> BatchOutput<'a> {
+        BatchOutput {
+            header: &batch.header,
+            header_signature: &batch.header_signature,
+            signature: batch.check_signature(),
+            trace: batch.trace,
+            transactions: batch.transactions.iter().map(|txn| TransactionOutput::new(txn, in_genesis, Some(batch), decoders, registry)).collect(),
+        }
+    }
+}
+
+/// The JSON output of a [Transaction](struct.Transaction.html), with its decoded payload, whether the payload matches
+/// its hash and the status of its signature.
+#[derive(Serialize)]
+pub(super) struct TransactionOutput<'a> {
+    #[serde(flatten)]
+    transaction: &'a Transaction,
+    payload_sha512_matches: bool,
+    signature: SignatureStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    batcher_matches: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoded_payload: Option<DecodedPayload<'a>>,
+}
+
+impl<'a> TransactionOutput<'a> {
+    /// Returns the output of `txn` with its decoded payload. A transaction `in_genesis` has no decoded payload unless
+    /// its family is mapped to a decoder. `batcher_matches` is only written for a transaction read in its `batch`.
+    pub(super) fn new(txn: &'a Transaction, in_genesis: bool, batch: Option<&Batch>, decoders: &'a DecoderSelection, registry: &DecoderRegistry) -> TransactionOutput<'a> {
+        TransactionOutput {
+            transaction: txn,
+            payload_sha512_matches: txn.payload_matches_hash(),
+            signature: txn.check_signature(),
+            batcher_matches: batch.map(|batch| batch.is_batcher_of(txn)),
+            decoded_payload: txn.get_decoder(in_genesis, decoders).map(|method| DecodedPayload::new(registry, method, &txn.payload)),
+        }
     }
 }
 
 /// A structure that represents a Block. Blocks contain metadata and a list of [Batches](struct.Batch.html). 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Block {
     batches: Vec<Batch>,
     header: BlockHeader,
@@ -218,10 +441,33 @@ impl Block {
     pub fn get_num_batches(&self) -> usize {
         self.batches.len()
     }
+
+    /// Returns the metadata of the block.
+    pub fn get_header(&self) -> &BlockHeader {
+        &self.header
+    }
+
+    /// Returns the signature of the header, which is the ID of the block.
+    pub fn get_header_signature(&self) -> &str {
+        &self.header_signature
+    }
+
+    /// Returns the batches contained.
+    pub fn get_batches(&self) -> &[Batch] {
+        &self.batches
+    }
+
+    /// Checks that the header was signed by the private key of its `signer_public_key`.
+    pub fn check_signature(&self) -> SignatureStatus {
+        match signatures::encode_block_header(&self.header) {
+            Some(header) => signatures::verify_signature(&header, &self.header.signer_public_key, &self.header_signature),
+            None => SignatureStatus::MalformedHeader,
+        }
+    }
 }
 
 /// A structure that represents the metadata of a [Block](struct.Block.html). 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct BlockHeader {
     batch_ids: Vec<String>,
     block_num: String,
@@ -231,8 +477,40 @@ pub struct BlockHeader {
     state_root_hash: String
 }
 
+impl BlockHeader {
+    /// Returns the number of the block, counting up from the genesis block at `0`.
+    pub fn get_block_num(&self) -> &str {
+        &self.block_num
+    }
+
+    /// Returns the ID of the block that comes before this one in the chain.
+    pub fn get_previous_block_id(&self) -> &str {
+        &self.previous_block_id
+    }
+
+    /// Returns the IDs of the batches in the block, in the order they were committed.
+    pub fn get_batch_ids(&self) -> &[String] {
+        &self.batch_ids
+    }
+
+    /// Returns the public key of the validator that published the block.
+    pub fn get_signer_public_key(&self) -> &str {
+        &self.signer_public_key
+    }
+
+    /// Returns the data of the consensus engine, encoded in base64.
+    pub fn get_consensus(&self) -> &str {
+        &self.consensus
+    }
+
+    /// Returns the hash of the state after the block was applied.
+    pub fn get_state_root_hash(&self) -> &str {
+        &self.state_root_hash
+    }
+}
+
 /// A structure that represents a Batch. Batches contain metadata and a list of [Transactions](struct.Transaction.html). 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Batch {
     header: BatchHeader,
     header_signature: String,
@@ -245,25 +523,315 @@ impl Batch {
     pub fn get_num_txns(&self) -> usize {
         self.transactions.len()
     }
+
+    /// Returns the metadata of the batch.
+    pub fn get_header(&self) -> &BatchHeader {
+        &self.header
+    }
+
+    /// Returns the signature of the header, which is the ID of the batch.
+    pub fn get_header_signature(&self) -> &str {
+        &self.header_signature
+    }
+
+    /// Returns the transactions contained.
+    pub fn get_transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// Checks that the header was signed by the private key of its `signer_public_key`.
+    pub fn check_signature(&self) -> SignatureStatus {
+        signatures::verify_signature(&signatures::encode_batch_header(&self.header), &self.header.signer_public_key, &self.header_signature)
+    }
+
+    /// Returns true if `txn` names the signer of this batch as its batcher. A transaction can only be committed in a
+    /// batch signed by the key in its `batcher_public_key`.
+    pub fn is_batcher_of(&self, txn: &Transaction) -> bool {
+        txn.header.batcher_public_key == self.header.signer_public_key
+    }
+
+    /// Displays the batch as `Batch {count}`, followed by its transactions, with every line padded by `depth` tabs.
+    /// Uses colored text so use this for terminal printing. Payloads of a batch `in_genesis` are printed in base64
+    /// unless their family is mapped to a decoder.
+    pub(super) fn display(&self, count: usize, depth: usize, in_genesis: bool, (full_id, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry) {
+        let pad = "\t".repeat(depth);
+        println!("{}{}{}{}", pad, "|Batch ".green().bold().on_black(), count.to_string().green().bold().on_black(), " ".on_black());
+        println!("{}| ID: {}", pad, label_id(&self.header_signature, full_id));
+        println!("{}| Signer Pub Key: {}", pad, label_id(&self.header.signer_public_key, full_id));
+        let signature = self.check_signature();
+        if !signature.is_valid() {
+            println!("{}| {}", pad, format!("Signature: {}", signature.describe()).red().bold());
+        }
+
+        match self.get_num_txns() {
+            1 => println!("{}| There is 1 transaction in this batch", pad),
+            count => println!("{}| There are {} transactions in this batch", pad, count),
+        }
+
+        for (count, txn) in self.transactions.iter().enumerate() {
+            txn.display(count, depth + 1, in_genesis, Some(self), (full_id, decoders), registry);
+        }
+    }
+
+    /// Displays the batch like [display](#method.display), but with no terminal color, so use this for piping to a file.
+    pub(super) fn display_no_color(&self, count: usize, depth: usize, in_genesis: bool, (full_id, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry) {
+        let pad = "\t".repeat(depth);
+        println!("{}|Batch {} ", pad, count);
+        println!("{}| ID: {}", pad, label_id(&self.header_signature, full_id));
+        println!("{}| Signer Pub Key: {}", pad, label_id(&self.header.signer_public_key, full_id));
+        let signature = self.check_signature();
+        if !signature.is_valid() {
+            println!("{}| Signature: {}", pad, signature.describe());
+        }
+
+        match self.get_num_txns() {
+            1 => println!("{}| There is 1 transaction in this batch", pad),
+            count => println!("{}| There are {} transactions in this batch", pad, count),
+        }
+
+        for (count, txn) in self.transactions.iter().enumerate() {
+            txn.display_no_color(count, depth + 1, in_genesis, Some(self), (full_id, decoders), registry);
+        }
+    }
+
+    /// Writes the batch as a collapsible HTML section, titled `Batch {count}`, with a section for each transaction.
+    /// Payloads of a batch `in_genesis` are written in base64 unless their family is mapped to a decoder.
+    pub(super) fn write_html(&self, body: &mut String, count: usize, in_genesis: bool, (full_id, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry) {
+        let _ = writeln!(body, "<details class=\"batch\">");
+        let signature = self.check_signature();
+        let _ = writeln!(body, "<summary>Batch {} {}{}</summary>", count, html::id_span(&self.header_signature, full_id), html::invalid_signature_flag(signature));
+        let _ = writeln!(body, "<dl><dt>Signer Pub Key</dt><dd>{}</dd>{}</dl>", html::id_span(&self.header.signer_public_key, full_id), html::signature_field(signature));
+
+        for (count, txn) in self.transactions.iter().enumerate() {
+            txn.write_html(body, count, in_genesis, Some(self), (full_id, decoders), registry);
+        }
+        let _ = writeln!(body, "</details>");
+    }
+
+    /// Returns the batch as a [Node](../../browser/struct.Node.html) labelled `Batch {count}`, with a node for each
+    /// transaction. Payloads of a batch `in_genesis` are shown in base64 unless their family is mapped to a decoder.
+    pub(super) fn to_node(&self, count: usize, in_genesis: bool, (full_id, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry) -> Node {
+        let txns = self.transactions.iter()
+            .map(|txn| txn.to_node(in_genesis, Some(self), (full_id, decoders), registry))
+            .collect();
+
+        let signature = self.check_signature();
+        Node::new(
+            format!("Batch {} {}{}", count, label_id(&self.header_signature, full_id), signature_label(signature)),
+            vec![
+                ("ID", self.header_signature.clone()),
+                ("Signer Pub Key", self.header.signer_public_key.clone()),
+                ("Signature", String::from(signature.describe())),
+                ("Transaction IDs", self.header.transaction_ids.join("\n")),
+            ],
+            None,
+            txns)
+    }
 }
 
 /// A structure that represents the metadata of a [Batch](struct.Batch.html). 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct BatchHeader {
     signer_public_key: String,
     transaction_ids: Vec<String>
 }
 
+impl BatchHeader {
+    /// Returns the public key of the client that signed the batch.
+    pub fn get_signer_public_key(&self) -> &str {
+        &self.signer_public_key
+    }
+
+    /// Returns the IDs of the transactions in the batch, in the order they are applied.
+    pub fn get_transaction_ids(&self) -> &[String] {
+        &self.transaction_ids
+    }
+}
+
 /// A structure that represents a Transaction. Transactions contain metadata and a serialized payload encoded in Base64. 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Transaction {
     header: TransactionHeader,
     header_signature: String,
     payload: String
 }
 
+impl Transaction {
+    /// Returns the metadata of the transaction.
+    pub fn get_header(&self) -> &TransactionHeader {
+        &self.header
+    }
+
+    /// Returns the signature of the header, which is the ID of the transaction.
+    pub fn get_header_signature(&self) -> &str {
+        &self.header_signature
+    }
+
+    /// Returns the payload of the transaction, encoded in base64.
+    pub fn get_payload(&self) -> &str {
+        &self.payload
+    }
+
+    /// Returns the SHA-512 hash of the payload in hex, or `None` if the payload isn't valid base64.
+    pub fn hash_payload(&self) -> Option<String> {
+        base64::decode(&self.payload).ok().map(|payload| sha512_hex(&payload))
+    }
+
+    /// Returns true if the hash of the payload is the `payload_sha512` in the header. A payload that was changed
+    /// after the transaction was signed, or that was corrupted, doesn't match.
+    pub fn payload_matches_hash(&self) -> bool {
+        self.hash_payload().is_some_and(|hash| hash.eq_ignore_ascii_case(&self.header.payload_sha512))
+    }
+
+    /// Checks that the header was signed by the private key of its `signer_public_key`.
+    pub fn check_signature(&self) -> SignatureStatus {
+        signatures::verify_signature(&signatures::encode_transaction_header(&self.header), &self.header.signer_public_key, &self.header_signature)
+    }
+
+    /// Displays the transaction as `Transaction {count}`, followed by its payload, with every line padded by `depth` tabs.
+    /// Uses colored text so use this for terminal printing. The payload of a transaction `in_genesis` is printed in
+    /// base64 unless its family is mapped to a decoder. The batcher is only checked for a transaction shown in its `batch`.
+    pub(super) fn display(&self, count: usize, depth: usize, in_genesis: bool, batch: Option<&Batch>, (full_id, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry) {
+        let pad = "\t".repeat(depth);
+        println!("{}{}{}{}", pad, "|Transaction ".green().bold().on_black(), count.to_string().green().bold().on_black(), " ".on_black());
+        println!("{}| ID: {}", pad, label_id(&self.header_signature, full_id));
+        println!("{}| Signer Pub Key: {}", pad, label_id(&self.header.signer_public_key, full_id));
+        println!("{}| Family: {} {}", pad, self.header.family_name, self.header.family_version);
+        let signature = self.check_signature();
+        if !signature.is_valid() {
+            println!("{}| {}", pad, format!("Signature: {}", signature.describe()).red().bold());
+        }
+        if batch.is_some_and(|batch| !batch.is_batcher_of(self)) {
+            println!("{}| {}", pad, "Batcher Pub Key isn't the signer of the batch".red().bold());
+        }
+        if !self.payload_matches_hash() {
+            println!("{}| {}", pad, "Payload SHA-512 doesn't match the payload".red().bold());
+        }
+
+        // A payload that can't be decoded is reported in place, and the rest of the data is still shown
+        match self.get_decoder(in_genesis, decoders).map(|method| registry.parse(method, &self.payload, depth as u8 + 1)) {
+            Some(Ok(decoded)) => println!("{}| Payload:\n{}", pad, decoded.blue()),
+            Some(Err(error)) => println!("{}| Payload:\n{}", pad, describe_decode_error(&self.payload, &error, depth as u8 + 1).red()),
+            None => println!("{}| Payload:\n{}", pad, self.payload.blue()),
+        }
+    }
+
+    /// Displays the transaction like [display](#method.display), but with no terminal color, so use this for piping to a file.
+    pub(super) fn display_no_color(&self, count: usize, depth: usize, in_genesis: bool, batch: Option<&Batch>, (full_id, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry) {
+        let pad = "\t".repeat(depth);
+        println!("{}|Transaction {} ", pad, count);
+        println!("{}| ID: {}", pad, label_id(&self.header_signature, full_id));
+        println!("{}| Signer Pub Key: {}", pad, label_id(&self.header.signer_public_key, full_id));
+        println!("{}| Family: {} {}", pad, self.header.family_name, self.header.family_version);
+        let signature = self.check_signature();
+        if !signature.is_valid() {
+            println!("{}| Signature: {}", pad, signature.describe());
+        }
+        if batch.is_some_and(|batch| !batch.is_batcher_of(self)) {
+            println!("{}| Batcher Pub Key isn't the signer of the batch", pad);
+        }
+        if !self.payload_matches_hash() {
+            println!("{}| Payload SHA-512 doesn't match the payload", pad);
+        }
+
+        // A payload that can't be decoded is reported in place, and the rest of the data is still shown
+        match self.get_decoder(in_genesis, decoders).map(|method| registry.parse(method, &self.payload, depth as u8 + 1)) {
+            Some(Ok(decoded)) => println!("{}| Payload:\n{}", pad, decoded),
+            Some(Err(error)) => println!("{}| Payload:\n{}", pad, describe_decode_error(&self.payload, &error, depth as u8 + 1)),
+            None => println!("{}| Payload:\n{}", pad, self.payload),
+        }
+    }
+
+    /// Writes the transaction as a collapsible HTML section titled `Transaction {count}`, with its payload inside.
+    /// The payload of a transaction `in_genesis` is written in base64 unless its family is mapped to a decoder. The
+    /// batcher is only checked for a transaction written in its `batch`.
+    pub(super) fn write_html(&self, body: &mut String, count: usize, in_genesis: bool, batch: Option<&Batch>, (full_id, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry) {
+        let _ = writeln!(body, "<details class=\"transaction\">");
+        let signature = self.check_signature();
+        let wrong_batcher = batch.is_some_and(|batch| !batch.is_batcher_of(self));
+        let payload_matches = self.payload_matches_hash();
+        let mismatch = match payload_matches {
+            true => "",
+            false => " <span class=\"invalid\">payload SHA-512 mismatch</span>",
+        };
+        let batcher_mismatch = match wrong_batcher {
+            true => " <span class=\"invalid\">batcher mismatch</span>",
+            false => "",
+        };
+        let _ = writeln!(body, "<summary>Transaction {} {} {} {}{}{}{}</summary>", count, html::escape(&self.header.family_name),
+            html::escape(&self.header.family_version), html::id_span(&self.header_signature, full_id), mismatch,
+            html::invalid_signature_flag(signature), batcher_mismatch);
+        let _ = writeln!(body, "<dl>");
+        let _ = writeln!(body, "<dt>Signer Pub Key</dt><dd>{}</dd>", html::id_span(&self.header.signer_public_key, full_id));
+        let _ = writeln!(body, "{}", html::signature_field(signature));
+        if wrong_batcher {
+            let _ = writeln!(body, "<dt>Batcher Pub Key</dt><dd class=\"invalid\">{} isn't the signer of the batch</dd>",
+                html::id_span(&self.header.batcher_public_key, full_id));
+        }
+        let _ = writeln!(body, "<dt>Nonce</dt><dd>{}</dd>", html::escape(&self.header.nonce));
+        if !payload_matches {
+            let _ = writeln!(body, "<dt>Payload SHA-512</dt><dd class=\"invalid\">{} doesn't match the payload</dd>", html::escape(&self.header.payload_sha512));
+        }
+        let _ = writeln!(body, "</dl>");
+
+        match self.get_decoder(in_genesis, decoders) {
+            Some(method) => { let _ = writeln!(body, "{}", html::decoded_payload(registry, method, &self.payload)); },
+            None => { let _ = writeln!(body, "<pre>{}</pre>", html::escape(&self.payload)); },
+        }
+        let _ = writeln!(body, "</details>");
+    }
+
+    /// Returns the transaction as a [Node](../../browser/struct.Node.html) with every field of its header and its payload.
+    /// The payload of a transaction `in_genesis` is shown in base64 unless its family is mapped to a decoder. The batcher
+    /// is only checked for a transaction shown in its `batch`.
+    pub(super) fn to_node(&self, in_genesis: bool, batch: Option<&Batch>, (full_id, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry) -> Node {
+        let payload = match self.get_decoder(in_genesis, decoders) {
+            Some(method) => match registry.parse(method, &self.payload, 0) {
+                Ok(decoded) => decoded,
+                Err(error) => describe_decode_error(&self.payload, &error, 0),
+            },
+            None => self.payload.clone(),
+        };
+        let (mismatch, payload_sha512) = match self.payload_matches_hash() {
+            true => ("", self.header.payload_sha512.clone()),
+            false => (" (payload SHA-512 mismatch)", format!("{}\ndoesn't match the payload", self.header.payload_sha512)),
+        };
+        let (batcher_mismatch, batcher_public_key) = match batch.is_some_and(|batch| !batch.is_batcher_of(self)) {
+            true => (" (batcher mismatch)", format!("{}\nisn't the signer of the batch", self.header.batcher_public_key)),
+            false => ("", self.header.batcher_public_key.clone()),
+        };
+        let signature = self.check_signature();
+        Node::new(
+            format!("{} {} {}{}{}{}", self.header.family_name, self.header.family_version, label_id(&self.header_signature, full_id),
+                mismatch, signature_label(signature), batcher_mismatch),
+            vec![
+                ("ID", self.header_signature.clone()),
+                ("Family", format!("{} {}", self.header.family_name, self.header.family_version)),
+                ("Signer Pub Key", self.header.signer_public_key.clone()),
+                ("Signature", String::from(signature.describe())),
+                ("Batcher Pub Key", batcher_public_key),
+                ("Nonce", self.header.nonce.clone()),
+                ("Payload SHA-512", payload_sha512),
+                ("Inputs", self.header.inputs.join("\n")),
+                ("Outputs", self.header.outputs.join("\n")),
+                ("Dependencies", self.header.dependencies.join("\n")),
+            ],
+            Some(payload),
+            Vec::new())
+    }
+
+    /// Returns the name of the decoder selected for the payload, or `None` if the payload should be shown in base64,
+    /// which is the case for a transaction `in_genesis` whose family isn't mapped to a decoder.
+    pub(crate) fn get_decoder<'a>(&self, in_genesis: bool, decoders: &'a DecoderSelection) -> Option<&'a str> {
+        match (in_genesis, decoders.get_family_decoder(&self.header.family_name, &self.header.family_version)) {
+            (true, None) => None,
+            _ => Some(decoders.for_transaction(&self.header.family_name, &self.header.family_version)),
+        }
+    }
+}
+
 /// A structure that represents the metadata of a [Transaction](struct.Transaction.html). 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct TransactionHeader {
     batcher_public_key: String,
     dependencies: Vec<String>,
@@ -276,11 +844,123 @@ pub struct TransactionHeader {
     signer_public_key: String
 }
 
+impl TransactionHeader {
+    /// Returns the name of the transaction family, e.g. `intkey`.
+    pub fn get_family_name(&self) -> &str {
+        &self.family_name
+    }
+
+    /// Returns the version of the transaction family, e.g. `1.0`.
+    pub fn get_family_version(&self) -> &str {
+        &self.family_version
+    }
+
+    /// Returns the IDs of the transactions that must be committed before this one.
+    pub fn get_dependencies(&self) -> &[String] {
+        &self.dependencies
+    }
+
+    /// Returns the state addresses, or address prefixes, that the transaction may read.
+    pub fn get_inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    /// Returns the state addresses, or address prefixes, that the transaction may write.
+    pub fn get_outputs(&self) -> &[String] {
+        &self.outputs
+    }
+
+    /// Returns the nonce that makes the header unique.
+    pub fn get_nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// Returns the public key of the client that signed the transaction.
+    pub fn get_signer_public_key(&self) -> &str {
+        &self.signer_public_key
+    }
+
+    /// Returns the public key of the client that signed the batch containing the transaction.
+    pub fn get_batcher_public_key(&self) -> &str {
+        &self.batcher_public_key
+    }
+
+    /// Returns the SHA-512 hash of the payload in hex, as written by the client that signed the transaction.
+    pub fn get_payload_sha512(&self) -> &str {
+        &self.payload_sha512
+    }
+}
+
 /// A structure that represents the paging element found at the `/blocks` endpoint. Only useful if the requester asked for paging.
-#[derive(Deserialize, Debug, Default)]
+///
+/// When there are more entries than fit in one response, `next` links to the following page.
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Paging {
-    limit: Option<String>,
+    #[serde(deserialize_with = "deserialize_limit")]
+    limit: Option<u64>,
     start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next_position: Option<String>,
+}
+
+impl Paging {
+    /// Returns the link to the next page, or `None` if this is the last page.
+    pub fn get_next(&self) -> Option<&str> {
+        self.next.as_deref()
+    }
+
+    /// Continues this paging with the paging of the following `page`, so only the last link to a next page is kept.
+    pub(super) fn append(&mut self, page: Paging) {
+        self.next = page.next;
+        self.next_position = page.next_position;
+    }
+}
+
+/// Reads the `limit` of a [Paging](struct.Paging.html), which Sawtooth writes as a number but older responses as a string.
+fn deserialize_limit<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Limit {
+        Number(u64),
+        Text(String),
+    }
+
+    match Option::<Limit>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Limit::Number(limit)) => Ok(Some(limit)),
+        Some(Limit::Text(limit)) => limit.parse().map(Some).map_err(de::Error::custom),
+    }
+}
+
+/// A structure that represents the data found at the `/blocks/{block_id}` endpoint, which holds a single block.
+///
+/// Convert it into a [BlockData](struct.BlockData.html) to display the block. The response doesn't name the head
+/// of the chain, so the `head` of the converted data is empty.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BlockResponse {
+    data: Block,
+    link: String,
+}
+
+impl BlockResponse {
+    /// Returns the block held by the response.
+    pub fn get_block(&self) -> &Block {
+        &self.data
+    }
+}
+
+impl From<BlockResponse> for BlockData {
+    fn from(response: BlockResponse) -> BlockData {
+        BlockData { data: vec![response.data], head: String::new(), link: response.link, paging: Paging::default() }
+    }
+}
+
+impl From<Vec<Block>> for BlockData {
+    fn from(blocks: Vec<Block>) -> BlockData {
+        BlockData { data: blocks, head: String::new(), link: String::new(), paging: Paging::default() }
+    }
 }
 
 #[cfg(test)]
@@ -307,6 +987,50 @@ mod test_blockdata_struct {
         data.data.push(Block::default());
         assert_eq!(2, data.get_num_blocks());
     }
+
+    #[test]
+    fn paging_limit_as_number_or_string() {
+        let paging: Paging = serde_json::from_str("{\"limit\": 100, \"start\": null, \"next\": \"http://localhost:8008/blocks?start=0x05\"}").unwrap();
+        assert_eq!(Some(100), paging.limit);
+        assert_eq!(Some("http://localhost:8008/blocks?start=0x05"), paging.get_next());
+
+        let paging: Paging = serde_json::from_str("{\"limit\": \"100\", \"start\": \"0x05\"}").unwrap();
+        assert_eq!(Some(100), paging.limit);
+        assert_eq!(None, paging.get_next());
+
+        assert!(serde_json::from_str::<Paging>("{\"limit\": \"many\", \"start\": null}").is_err());
+    }
+
+    #[test]
+    fn appending_a_page() {
+        let mut data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let page = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        data.append_page(page);
+        assert_eq!(12, data.get_num_blocks());
+    }
+
+    #[test]
+    fn displays_short_ids() {
+        let block = Block {
+            header_signature: String::from("abc"),
+            header: BlockHeader { block_num: String::from("1"), ..BlockHeader::default() },
+            ..Block::default()
+        };
+        let data = BlockData::from(vec![block]);
+        // The previous block ID and signer are empty, and no ID is long enough to be shortened
+        data.display_full_data((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        data.display_full_data_no_color((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+    }
+
+    #[test]
+    fn from_single_block() {
+        let response = ::json_reader::read_block_from_file("example-blockchain/block.json").unwrap();
+        let id = String::from(response.get_block().get_header_signature());
+        let data = BlockData::from(response);
+        assert_eq!(id, data.data[0].get_header_signature());
+        assert_eq!(1, data.get_num_blocks());
+        assert_eq!(1, data.data[0].get_num_batches());
+    }
 }
 
 #[cfg(test)]
@@ -359,4 +1083,330 @@ mod test_batch_struct {
         data.transactions.push(Transaction::default());
         assert_eq!(2, data.get_num_txns());
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_transaction_struct {
+    use super::*;
+
+    fn read_first_txn() -> Transaction {
+        let data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let mut batch = data.into_blocks().remove(0).batches.remove(0);
+        batch.transactions.remove(0)
+    }
+
+    #[test]
+    fn example_payloads_match_their_hash() {
+        let data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        assert!(data.get_blocks().iter()
+            .flat_map(|block| block.get_batches())
+            .flat_map(|batch| batch.get_transactions())
+            .all(|txn| txn.payload_matches_hash()));
+    }
+
+    #[test]
+    fn changed_payload_does_not_match() {
+        let mut txn = read_first_txn();
+        // {"Name": "a"} in CBOR
+        txn.payload = String::from("oWROYW1lYWE=");
+        assert!(!txn.payload_matches_hash());
+        assert_eq!(Some(sha512_hex(&[0xa1, 0x64, 0x4e, 0x61, 0x6d, 0x65, 0x61, 0x61])), txn.hash_payload());
+    }
+
+    #[test]
+    fn invalid_base64_does_not_match() {
+        let mut txn = read_first_txn();
+        txn.payload = String::from("not base64!");
+        assert!(!txn.payload_matches_hash());
+        assert_eq!(None, txn.hash_payload());
+    }
+
+    #[test]
+    fn hash_case_is_ignored() {
+        let mut txn = read_first_txn();
+        txn.header.payload_sha512 = txn.header.payload_sha512.to_uppercase();
+        assert!(txn.payload_matches_hash());
+    }
+}
+
+#[cfg(test)]
+mod test_blockdata_json {
+    use super::*;
+    use super::serde_json::Value;
+
+    fn read_example() -> BlockData {
+        ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap()
+    }
+
+    #[test]
+    fn includes_decoded_payloads() {
+        let registry = DecoderRegistry::default();
+        let decoders = DecoderSelection::new("cbor");
+        let json: Value = serde_json::from_str(&read_example().to_json((false, false, &decoders), &registry)).unwrap();
+
+        let blocks = json["data"].as_array().unwrap();
+        assert_eq!(5, blocks.len());
+        let txn = &blocks[0]["batches"][0]["transactions"][0];
+        assert_eq!("intkey", txn["header"]["family_name"]);
+        assert_eq!("cbor", txn["decoded_payload"]["decoder"]);
+        assert_eq!("num1", txn["decoded_payload"]["value"]["Name"]);
+        assert!(txn["payload"].is_string());
+        assert_eq!(true, txn["payload_sha512_matches"]);
+        assert_eq!("valid", txn["signature"]);
+        assert_eq!(true, txn["batcher_matches"]);
+        assert_eq!("valid", blocks[0]["signature"]);
+        assert_eq!("valid", blocks[0]["batches"][0]["signature"]);
+    }
+
+    #[test]
+    fn genesis_is_raw_unless_mapped() {
+        let registry = DecoderRegistry::default();
+        let mut decoders = DecoderSelection::new("cbor");
+        let json: Value = serde_json::from_str(&read_example().to_json((false, true, &decoders), &registry)).unwrap();
+        let genesis = &json["data"][5]["batches"][0]["transactions"][0];
+        assert_eq!("0", json["data"][5]["header"]["block_num"]);
+        assert!(genesis.get("decoded_payload").is_none());
+
+        decoders.add_well_known_family_rules(&registry);
+        let json: Value = serde_json::from_str(&read_example().to_json((false, true, &decoders), &registry)).unwrap();
+        let genesis = &json["data"][5]["batches"][0]["transactions"][0];
+        assert_eq!("sawtooth.settings.vote.authorized_keys", genesis["decoded_payload"]["value"]["setting"]);
+    }
+
+    #[test]
+    fn undecodable_payload_has_error() {
+        let registry = DecoderRegistry::default();
+        let decoders = DecoderSelection::new("json");
+        let json: Value = serde_json::from_str(&read_example().to_json((false, false, &decoders), &registry)).unwrap();
+        let decoded = &json["data"][0]["batches"][0]["transactions"][0]["decoded_payload"];
+        assert!(decoded["error"].as_str().unwrap().starts_with("invalid json"));
+        assert!(decoded.get("value").is_none());
+    }
+}
+
+#[cfg(test)]
+mod test_fixtures {
+    use super::*;
+
+    /// Returns a block with one batch, holding the intkey transaction `txn{num}`. Its payload is `{"Name": "a"}` in
+    /// CBOR, and it depends on the transactions with the IDs in `dependencies`. Nothing is signed.
+    fn block(num: &str, id: &str, previous: &str, dependencies: Vec<&str>) -> Block {
+        let txn = Transaction {
+            header: TransactionHeader {
+                family_name: String::from("intkey"),
+                family_version: String::from("1.0"),
+                dependencies: dependencies.into_iter().map(String::from).collect(),
+                payload_sha512: sha512_hex(&base64::decode("oWROYW1lYWE=").unwrap()),
+                ..TransactionHeader::default()
+            },
+            header_signature: format!("txn{}", num),
+            payload: String::from("oWROYW1lYWE="),
+        };
+        let batch = Batch { header_signature: format!("batch{}", num), transactions: vec![txn], ..Batch::default() };
+        Block {
+            header: BlockHeader { block_num: String::from(num), previous_block_id: String::from(previous), ..BlockHeader::default() },
+            header_signature: String::from(id),
+            batches: vec![batch],
+        }
+    }
+
+    /// Returns a chain of the blocks 2, 1 and 0. The transaction of block 2 depends on the one of block 1, and on a
+    /// transaction that isn't in the chain.
+    pub(super) fn example() -> BlockData {
+        BlockData {
+            data: vec![
+                block("2", "block2", "block1", vec!["txn1", "outside"]),
+                block("1", "block1", "block0", vec![]),
+                block("0", "block0", "0000000000000000", vec![]),
+            ],
+            ..BlockData::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_blockdata_dot {
+    use super::*;
+    use super::test_fixtures::{example};
+
+    #[test]
+    fn links_blocks_batches_and_transactions() {
+        // The blocks aren't signed, so every node is flagged
+        let dot = example().to_dot((true, true, &DecoderSelection::new("cbor")));
+        assert!(dot.starts_with("digraph blockchain {"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("\"block2\" -> \"block1\" [label=\"previous\""));
+        assert!(dot.contains("\"block1\" -> \"block0\" [label=\"previous\""));
+        assert!(dot.contains("\"block2\" -> \"batch2\";"));
+        assert!(dot.contains("\"batch2\" -> \"txn2\";"));
+        assert!(dot.contains("\"txn2\" [label=\"intkey 1.0\\ntxn2\\ninvalid signature\", shape=note, color=red, fontcolor=red];"));
+    }
+
+    #[test]
+    fn draws_dependencies() {
+        let dot = example().to_dot((true, true, &DecoderSelection::new("cbor")));
+        assert!(dot.contains("\"txn2\" -> \"txn1\" [label=\"depends on\", style=dotted];"));
+        assert!(dot.contains("\"outside\" [label=\"outside\", shape=note, style=dashed];"));
+        assert!(!dot.contains("\"txn1\" [label=\"txn1\", shape=note, style=dashed]"));
+    }
+
+    #[test]
+    fn shortens_ids() {
+        let mut data = example();
+        data.data[0].header_signature = String::from("33f7390123456789f51");
+        let dot = data.to_dot((false, true, &DecoderSelection::new("cbor")));
+        assert!(dot.contains("[label=\"Block 2\\n33f739...9f51\\ninvalid signature\", style=bold, color=red, fontcolor=red];"));
+        assert!(dot.contains("[label=\"Batch 0\\nbatch2\\ninvalid signature\", shape=folder, color=red, fontcolor=red];"));
+    }
+
+    #[test]
+    fn omits_genesis() {
+        let dot = example().to_dot((true, false, &DecoderSelection::new("cbor")));
+        assert!(!dot.contains("\"block0\""));
+        assert!(!dot.contains("\"block1\" -> \"block0\""));
+    }
+
+    #[test]
+    fn flags_payload_mismatch() {
+        let mut data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        // The payload isn't part of the signed header, so only the payload is flagged
+        data.data[0].batches[0].transactions[0].payload = String::from("oWROYW1lYWE=");
+        let dot = data.to_dot((false, false, &DecoderSelection::new("cbor")));
+        assert!(dot.contains("[label=\"intkey 1.0\\n62980d...16f0\\npayload SHA-512 mismatch\", shape=note, color=red, fontcolor=red];"));
+        assert_eq!(1, dot.matches(", color=red").count());
+    }
+
+    #[test]
+    fn flags_invalid_signature_and_batcher() {
+        let mut data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        data.data[0].batches[0].transactions[0].header.batcher_public_key = String::from("02abcd");
+        let dot = data.to_dot((false, false, &DecoderSelection::new("cbor")));
+        assert!(dot.contains("[label=\"intkey 1.0\\n62980d...16f0\\ninvalid signature\\nbatcher mismatch\", shape=note, color=red, fontcolor=red];"));
+        assert!(dot.contains("[label=\"Block 5\\n0b5874...4771\", style=bold];"));
+        assert_eq!(1, dot.matches(", color=red").count());
+    }
+
+    #[test]
+    fn escapes_ids() {
+        let mut data = example();
+        data.data[0].header.previous_block_id = String::from("block\"1\\");
+        data.data[1].header_signature = String::from("block\"1\\");
+        let dot = data.to_dot((true, true, &DecoderSelection::new("cbor")));
+        assert!(dot.contains("\"block2\" -> \"block\\\"1\\\\\" [label=\"previous\""));
+        assert!(dot.contains("\"block\\\"1\\\\\" -> \"batch1\";"));
+    }
+
+    #[test]
+    fn escapes_labels() {
+        assert_eq!("a\\\"b\\\\", dot_escape("a\"b\\"));
+    }
+}
+
+#[cfg(test)]
+mod test_blockdata_html {
+    use super::*;
+    use super::test_fixtures::{example};
+
+    #[test]
+    fn links_blocks_to_their_neighbours() {
+        let html = example().to_html((true, true, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<details class=\"block\" id=\"block-block1\" open>"));
+        assert!(html.contains("<dt>Previous Block</dt><dd><a href=\"#block-block0\">"));
+        assert!(html.contains("<dt>Next Block</dt><dd><a href=\"#block-block2\">"));
+        // The genesis block's predecessor isn't on the page
+        assert!(!html.contains("#block-0000000000000000"));
+    }
+
+    #[test]
+    fn shows_decoded_payloads() {
+        let html = example().to_html((true, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        // The transactions aren't signed
+        assert!(html.contains("<summary>Transaction 0 intkey 1.0 <span class=\"id\" title=\"txn2\">txn2</span> <span class=\"invalid\">invalid signature</span></summary>"));
+        assert!(html.contains("<pre class=\"payload\">&quot;Name&quot; : &quot;a&quot;\n</pre>"));
+        assert!(!html.contains("id=\"block-block0\""));
+        assert!(!html.contains("<pre>oWROYW1lYWE=</pre>"));
+    }
+
+    #[test]
+    fn genesis_payload_is_raw() {
+        let html = example().to_html((true, true, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert!(html.contains("<pre>oWROYW1lYWE=</pre>"));
+    }
+
+    #[test]
+    fn flags_payload_mismatch() {
+        let mut data = example();
+        data.data[0].batches[0].transactions[0].header.payload_sha512 = String::from("abcd");
+        let html = data.to_html((true, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert!(html.contains("<span class=\"id\" title=\"txn2\">txn2</span> <span class=\"invalid\">payload SHA-512 mismatch</span> <span class=\"invalid\">invalid signature</span></summary>"));
+        assert!(html.contains("<dt>Payload SHA-512</dt><dd class=\"invalid\">abcd doesn't match the payload</dd>"));
+        assert_eq!(1, html.matches("class=\"invalid\">payload").count());
+    }
+
+    #[test]
+    fn shows_signatures_and_flags_batcher() {
+        let mut data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        data.data[0].batches[0].transactions[0].header.batcher_public_key = String::from("02abcd");
+        let html = data.to_html((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        let block_summary = html.lines().find(|line| line.starts_with("<summary>Block 5 ")).unwrap();
+        assert!(!block_summary.contains("invalid"));
+        assert_eq!(5 + 5 + 4, html.matches("<dt>Signature</dt><dd>valid</dd>").count());
+        assert!(html.contains("<dt>Signature</dt><dd class=\"invalid\">invalid</dd>"));
+        assert!(html.contains(" <span class=\"invalid\">invalid signature</span> <span class=\"invalid\">batcher mismatch</span></summary>"));
+        assert!(html.contains("<dt>Batcher Pub Key</dt><dd class=\"invalid\"><span class=\"id\" title=\"02abcd\">02abcd</span> isn't the signer of the batch</dd>"));
+    }
+
+    #[test]
+    fn full_ids_on_hover() {
+        let mut data = example();
+        data.data[0].header_signature = String::from("33f7390123456789f51");
+        let html = data.to_html((false, true, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert!(html.contains("<span class=\"id\" title=\"33f7390123456789f51\">33f739...9f51</span>"));
+    }
+}
+
+#[cfg(test)]
+mod test_blockdata_browser {
+    use super::*;
+
+    #[test]
+    fn nests_batches_and_transactions() {
+        let data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let browser = data.to_browser((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+
+        let block = browser.get_selected().unwrap();
+        assert!(block.get_label().starts_with("Block 5 "));
+        let txn = &block.get_children()[0].get_children()[0];
+        assert!(txn.get_label().starts_with("intkey 1.0 "));
+    }
+
+    #[test]
+    fn omits_genesis() {
+        let data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let with_genesis = data.to_browser((false, true, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        let without_genesis = data.to_browser((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert_eq!(with_genesis.get_num_nodes(), without_genesis.get_num_nodes() + 1);
+    }
+
+    #[test]
+    fn flags_payload_mismatch() {
+        let mut data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        data.data[0].batches[0].transactions[0].header.payload_sha512 = String::from("abcd");
+        let browser = data.to_browser((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        let txn = &browser.get_selected().unwrap().get_children()[0].get_children()[0];
+        // Changing the header also breaks its signature
+        assert!(txn.get_label().ends_with(" (payload SHA-512 mismatch) (invalid signature)"));
+    }
+
+    #[test]
+    fn flags_batcher_mismatch() {
+        let mut data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        data.data[0].batches[0].transactions[0].header.batcher_public_key = String::from("02abcd");
+        let browser = data.to_browser((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        let block = browser.get_selected().unwrap();
+        assert!(block.get_label().ends_with("0b5874...4771"));
+        let txn = &block.get_children()[0].get_children()[0];
+        assert!(txn.get_label().ends_with(" (invalid signature) (batcher mismatch)"));
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `json_receipts` contains the structures that represent the JSON structured data from the
+//! `/receipts` endpoint of Hyperledger Sawtooth, which tells what committed transactions did.
+//!
+//! The root [ReceiptList](struct.ReceiptList.html) structure holds a [Receipt](struct.Receipt.html) for each
+//! transaction ID that was asked for. A receipt lists the [StateChanges](struct.StateChange.html) made by the
+//! transaction, the [Events](struct.Event.html) it emitted, and any extra data added by its transaction processor.
+
+extern crate serde_json;
+
+use colored::*;
+use decoder_selection::{DecoderSelection};
+use json_deserialize::*;
+use super::{label_id, paint, DecodedPayload};
+
+use std::fmt;
+
+/// A structure that represents the root data item found at the `/receipts` endpoint.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ReceiptList {
+    data: Vec<Receipt>,
+    link: String,
+}
+
+impl ReceiptList {
+
+    /// Returns the number of receipts contained.
+    pub fn get_num_receipts(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the receipts contained.
+    pub fn get_receipts(&self) -> &[Receipt] {
+        &self.data
+    }
+
+    /// Removes the receipt of every transaction whose ID isn't in `ids`. Does nothing if `ids` is empty.
+    pub fn retain_ids(&mut self, ids: &[&str]) {
+        if !ids.is_empty() {
+            self.data.retain(|receipt| ids.contains(&receipt.transaction_id.as_str()));
+        }
+    }
+
+    /// Display the state changes, events and data of each receipt.
+    /// Uses colored text so use this for terminal printing.
+    ///
+    /// IDs and addresses are shortened to their first 6 and last 4 characters unless `full_id` is set. The value of a
+    /// state change is decoded with the decoder that `decoders` selects for its address, and event and receipt data
+    /// with the default decoder.
+    pub fn display_full_data(&self, options: (bool, &DecoderSelection), registry: &DecoderRegistry) {
+        print!("{}", self.format(options, registry, true));
+    }
+
+    /// Display the state changes, events and data of each receipt.
+    /// No terminal color, so use this for piping to a file.
+    pub fn display_full_data_no_color(&self, options: (bool, &DecoderSelection), registry: &DecoderRegistry) {
+        print!("{}", self.format(options, registry, false));
+    }
+
+    /// Returns the receipts as pretty printed JSON, in the same structure as the `/receipts` endpoint. Each state
+    /// change that sets a value has an extra `decoded_value` element, each event with data a `decoded_data` element, and
+    /// each receipt a `decoded_data` list, written like the `decoded_payload` of
+    /// [BlockData::to_json](../json_blocks/struct.BlockData.html#method.to_json).
+    pub fn to_json(&self, decoders: &DecoderSelection, registry: &DecoderRegistry) -> String {
+        let default_method = decoders.get_default_method();
+        let output = ReceiptListOutput {
+            data: self.data.iter().map(|receipt| ReceiptOutput {
+                data: &receipt.data,
+                state_changes: receipt.state_changes.iter().map(|change| StateChangeOutput {
+                    change,
+                    decoded_value: match change.change_type {
+                        ChangeType::Set => Some(DecodedPayload::new(registry, decoders.for_address(&change.address), &change.value)),
+                        ChangeType::Delete => None,
+                    },
+                }).collect(),
+                events: receipt.events.iter().map(|event| EventOutput {
+                    event,
+                    decoded_data: match event.data.is_empty() {
+                        true => None,
+                        false => Some(DecodedPayload::new(registry, default_method, &event.data)),
+                    },
+                }).collect(),
+                decoded_data: receipt.data.iter().map(|data| DecodedPayload::new(registry, default_method, data)).collect(),
+                transaction_id: &receipt.transaction_id,
+            }).collect(),
+            link: &self.link,
+        };
+        serde_json::to_string_pretty(&output).expect("receipts can always be written as JSON")
+    }
+
+    fn format(&self, (full_id, decoders): (bool, &DecoderSelection), registry: &DecoderRegistry, color: bool) -> String {
+        // A value that can't be decoded is reported in place, and the rest of the receipt is still shown
+        let decode = |method: &str, value: &str, tab_padding: u8| match registry.parse(method, value, tab_padding) {
+            Ok(decoded) => paint(&decoded, Color::Blue, color),
+            Err(error) => paint(&describe_decode_error(value, &error, tab_padding), Color::Red, color),
+        };
+
+        let mut out = String::new();
+        for (count, receipt) in self.data.iter().enumerate() {
+            let title = format!("|Receipt {}", count);
+            out.push_str(&format!("{}\n", match color {
+                true => title.green().bold().on_black(),
+                false => title.normal(),
+            }));
+            out.push_str(&format!("| Transaction ID: {}\n", label_id(&receipt.transaction_id, full_id)));
+
+            out.push_str(&format!("| State Changes: {}\n", receipt.state_changes.len()));
+            for change in receipt.state_changes.iter() {
+                out.push_str(&format!("\t|{} {}\n", change.change_type, label_id(&change.address, full_id)));
+                if change.change_type == ChangeType::Set {
+                    out.push_str(&format!("\t| Value:\n{}", decode(decoders.for_address(&change.address), &change.value, 2)));
+                }
+            }
+
+            out.push_str(&format!("| Events: {}\n", receipt.events.len()));
+            for event in receipt.events.iter() {
+                out.push_str(&format!("\t|Event {}\n", paint(&event.event_type, Color::Yellow, color)));
+                for attribute in event.attributes.iter() {
+                    out.push_str(&format!("\t| {}: {}\n", attribute.key, attribute.value));
+                }
+                if !event.data.is_empty() {
+                    out.push_str(&format!("\t| Data:\n{}", decode(decoders.get_default_method(), &event.data, 2)));
+                }
+            }
+
+            for data in receipt.data.iter() {
+                out.push_str(&format!("| Data:\n{}", decode(decoders.get_default_method(), data, 1)));
+            }
+        }
+        out
+    }
+}
+
+/// A structure that represents what a committed transaction did.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Receipt {
+    data: Vec<String>,
+    events: Vec<Event>,
+    state_changes: Vec<StateChange>,
+    transaction_id: String,
+}
+
+impl Receipt {
+    /// Returns the ID of the transaction.
+    pub fn get_transaction_id(&self) -> &str {
+        &self.transaction_id
+    }
+
+    /// Returns the changes made to state by the transaction.
+    pub fn get_state_changes(&self) -> &[StateChange] {
+        &self.state_changes
+    }
+
+    /// Returns the events emitted by the transaction.
+    pub fn get_events(&self) -> &[Event] {
+        &self.events
+    }
+}
+
+/// A structure that represents a value set at, or deleted from, a state address.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct StateChange {
+    address: String,
+    #[serde(rename = "type")]
+    change_type: ChangeType,
+    /// The new value in base64, empty for a delete.
+    #[serde(default)]
+    value: String,
+}
+
+impl StateChange {
+    /// Returns the address that was changed.
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+
+    /// Returns whether the address was set or deleted.
+    pub fn get_change_type(&self) -> ChangeType {
+        self.change_type
+    }
+
+    /// Returns the new value in base64, which is empty for a delete.
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// The kind of a [StateChange](struct.StateChange.html).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ChangeType {
+    /// A value was stored at the address.
+    #[default]
+    Set,
+    /// The value at the address was removed.
+    Delete,
+}
+
+impl fmt::Display for ChangeType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ChangeType::Set => write!(f, "SET"),
+            ChangeType::Delete => write!(f, "DELETE"),
+        }
+    }
+}
+
+/// A structure that represents an event emitted by a transaction processor.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Event {
+    event_type: String,
+    attributes: Vec<EventAttribute>,
+    /// Opaque data in base64, whose meaning depends on the event type.
+    #[serde(default)]
+    data: String,
+}
+
+impl Event {
+    /// Returns the type of the event, e.g. `sawtooth/state-delta`.
+    pub fn get_event_type(&self) -> &str {
+        &self.event_type
+    }
+}
+
+/// A key/value pair that describes an [Event](struct.Event.html).
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct EventAttribute {
+    key: String,
+    value: String,
+}
+
+/// The JSON output of a [ReceiptList](struct.ReceiptList.html).
+#[derive(Serialize)]
+struct ReceiptListOutput<'a> {
+    data: Vec<ReceiptOutput<'a>>,
+    link: &'a str,
+}
+
+/// The JSON output of a [Receipt](struct.Receipt.html).
+#[derive(Serialize)]
+struct ReceiptOutput<'a> {
+    data: &'a [String],
+    decoded_data: Vec<DecodedPayload<'a>>,
+    events: Vec<EventOutput<'a>>,
+    state_changes: Vec<StateChangeOutput<'a>>,
+    transaction_id: &'a str,
+}
+
+/// The JSON output of a [StateChange](struct.StateChange.html).
+#[derive(Serialize)]
+struct StateChangeOutput<'a> {
+    #[serde(flatten)]
+    change: &'a StateChange,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoded_value: Option<DecodedPayload<'a>>,
+}
+
+/// The JSON output of an [Event](struct.Event.html).
+#[derive(Serialize)]
+struct EventOutput<'a> {
+    #[serde(flatten)]
+    event: &'a Event,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoded_data: Option<DecodedPayload<'a>>,
+}
+
+#[cfg(test)]
+mod test_receipts {
+    use super::*;
+    use super::serde_json::Value;
+
+    const EVENTS: &str = "{
+        \"data\": [{
+            \"data\": [\"oWRudW0xAQ==\"],
+            \"events\": [{
+                \"event_type\": \"intkey/set\",
+                \"attributes\": [{\"key\": \"name\", \"value\": \"num1\"}],
+                \"data\": \"oWRudW0xAQ==\"
+            }],
+            \"state_changes\": [{\"address\": \"1cf126aaaaaaaaaa\", \"type\": \"DELETE\"}],
+            \"transaction_id\": \"dddddddddddddddd\"
+        }],
+        \"link\": \"\"
+    }";
+
+    fn example() -> ReceiptList {
+        ::json_reader::read_receipts_from_file("example-blockchain/receipts.json").unwrap()
+    }
+
+    #[test]
+    fn reads_example_file() {
+        let data = example();
+        assert_eq!(1, data.get_num_receipts());
+        let change = &data.get_receipts()[0].get_state_changes()[0];
+        assert_eq!(ChangeType::Set, change.get_change_type());
+        assert_eq!("oWRudW0xAQ==", change.get_value());
+    }
+
+    #[test]
+    fn formats_decoded_state_changes() {
+        let text = example().format((false, &DecoderSelection::new("cbor")), &DecoderRegistry::default(), false);
+        assert!(text.starts_with("|Receipt 0\n| Transaction ID: 62980d...16f0\n| State Changes: 1\n\t|SET 1cf126...0e0c\n\t| Value:\n"));
+        assert!(text.contains("\"num1\" : 1"));
+        assert!(text.ends_with("| Events: 0\n"));
+    }
+
+    #[test]
+    fn formats_events_and_deletes() {
+        let data: ReceiptList = serde_json::from_str(EVENTS).unwrap();
+        let text = data.format((true, &DecoderSelection::new("cbor")), &DecoderRegistry::default(), false);
+        assert!(text.contains("\t|DELETE 1cf126aaaaaaaaaa\n| Events: 1\n\t|Event intkey/set\n\t| name: num1\n\t| Data:\n"));
+        assert_eq!(2, text.matches("\"num1\" : 1").count());
+    }
+
+    #[test]
+    fn undecodable_value_is_reported() {
+        let text = example().format((false, &DecoderSelection::new("json")), &DecoderRegistry::default(), false);
+        assert!(text.contains("could not decode"));
+        assert!(text.contains("Base64: oWRudW0xAQ=="));
+    }
+
+    #[test]
+    fn json_includes_decoded_values() {
+        let data: ReceiptList = serde_json::from_str(EVENTS).unwrap();
+        let json: Value = serde_json::from_str(&data.to_json(&DecoderSelection::new("cbor"), &DecoderRegistry::default())).unwrap();
+
+        let receipt = &json["data"][0];
+        assert_eq!("dddddddddddddddd", receipt["transaction_id"]);
+        assert_eq!(1, receipt["decoded_data"][0]["value"]["num1"]);
+        assert_eq!(1, receipt["events"][0]["decoded_data"]["value"]["num1"]);
+        assert_eq!("DELETE", receipt["state_changes"][0]["type"]);
+        assert!(receipt["state_changes"][0].get("decoded_value").is_none());
+    }
+
+    #[test]
+    fn retains_ids() {
+        let mut data = example();
+        data.retain_ids(&["dddddddddddddddd"]);
+        assert_eq!(0, data.get_num_receipts());
+    }
+}
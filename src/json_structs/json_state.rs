@@ -11,15 +11,24 @@
 //! [StateData](struct.StateData.html) structure. That structure contains methods for printing out
 //! the contents of the blockchain. Use [display_full_data](struct.StateData.html#method.display_full_data) when 
 //! printing to the terminal, and [display_full_data_no_color](struct.StateData.html#method.display_full_data_no_color)
-//! if you want to pipe the output to a file.
+//! if you want to pipe the output to a file. Use [to_json](struct.StateData.html#method.to_json) for output
+//! that other programs can read, and [to_html](struct.StateData.html#method.to_html) for a report that can be browsed.
+//! To page through a large state in the terminal, use [to_browser](struct.StateData.html#method.to_browser).
+
+extern crate serde_json;
 
 use super::json_blocks::{Paging};
+use browser::{Browser, Node};
+use decoder_selection::{DecoderSelection, get_well_known_family};
+use html;
 use json_deserialize::*;
-use super::{get_partial_string};
+use super::{get_partial_string, label_id, DecodedPayload, Paged};
 use colored::*;
 
+use std::fmt::Write;
+
 /// A structure that represents the root data item found at the `/state` endpoint.
-#[derive(Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct StateData {
     data: Vec<State>,
     head: String,
@@ -34,20 +43,28 @@ impl StateData {
         self.data.len()
     }
 
+    /// Returns the addresses that contain state, and the state stored.
+    pub fn get_states(&self) -> &[State] {
+        &self.data
+    }
+
     /// Display the addresses that contain state, and the state stored.
     /// Uses colored text so use this for terminal printing.
     /// 
     /// If instead you want to print this to a terminal window, see [display_full_data](struct.StateData.html#method.display_full_data)
     /// since it prints with colored text.
     /// 
-    /// The function takes in a tuple (full_id, show_settings, method) of settings:
+    /// The function takes in a tuple (full_id, show_settings, decoders) of settings:
     /// - `full_id` -> Setting this to false will only print the first 6 and last 4 characters of addresses, 
-    ///     otherwise the full address is shown
+    ///   otherwise the full address is shown
     /// - `show_settings` -> Setting this to false will omit displaying address that contains blockchain setting data. It is
-    ///     recommended that this stay false as its serialization will often be different from the rest of the data.
-    /// - `method` -> What method to use when deserializing data. See
-    ///     [supported methods](../../index.html#supported-deserialization-methods) for a list of valid options
-    pub fn display_full_data(self, (full_id, show_settings, method): (bool, bool, String)) {
+    ///   recommended that this stay false as its serialization will often be different from the rest of the data.
+    /// - `decoders` -> Which [decoder](../../decoder_selection/struct.DecoderSelection.html) to use for each address,
+    ///   based on the namespace prefix of the address. See
+    ///   [supported methods](../../index.html#supported-deserialization-methods) for a list of valid options
+    ///
+    /// The decoder names are looked up in `registry`, which holds the available [decoders](../../json_deserialize/trait.PayloadDecoder.html).
+    pub fn display_full_data(self, (full_id, show_settings, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) {
         for state in self.data.iter() {
             if show_settings || state.get_address_namespace() != "000000" {
                 if full_id {
@@ -58,15 +75,17 @@ impl StateData {
 
                 let payload_encoded = String::from(state.data.as_str());
 
-                // If printing out the settings block, don't deserialize it
-                if show_settings && state.get_address_namespace() == "000000" {
+                if let Some(family) = get_well_known_family(&state.address) {
+                    println!("\tFamily: {}", family);
+                }
+
+                // If printing out the settings block, don't deserialize it unless its namespace is mapped
+                if show_settings && state.get_address_namespace() == "000000" && decoders.get_namespace_decoder(&state.address).is_none() {
                     println!("\tData:\n{}", payload_encoded.blue())
                 } else {
-                    match method.as_str() {
-                    "cbor" => println!("\tData:\n{}", parse_cbor(payload_encoded, 2).blue()),
-                    "json" => println!("\tData:\n{}", parse_json(payload_encoded, 2).blue()),
-                    "custom" => println!("\tData:\n{}", parse_custom(payload_encoded, 2).blue()),
-                    _ => panic!("Unsupported deserialization method: {}", method)
+                    match registry.parse(decoders.for_address(&state.address), &payload_encoded, 2) {
+                        Ok(decoded) => println!("\tData:\n{}", decoded.blue()),
+                        Err(error) => println!("\tData:\n{}", describe_decode_error(&payload_encoded, &error, 2).red()),
                     }
                 }
             }
@@ -79,42 +98,171 @@ impl StateData {
     /// If instead you want to print this to a terminal window, see [display_full_data](struct.StateData.html#method.display_full_data)
     /// since it prints with colored text.
     /// 
-    /// The function takes in a tuple (full_id, show_settings, method) of settings:
+    /// The function takes in a tuple (full_id, show_settings, decoders) of settings:
     /// - `full_id` -> Setting this to false will only print the first 6 and last 4 characters of addresses, 
-    ///     otherwise the full address is shown
+    ///   otherwise the full address is shown
     /// - `show_settings` -> Setting this to false will omit displaying address that contains blockchain setting data. It is
-    ///     recommended that this stay false as its serialization will often be different from the rest of the data.
-    /// - `method` -> What method to use when deserializing data. See
-    ///     [supported methods](../../index.html#supported-deserialization-methods) for a list of valid options
-    pub fn display_full_data_no_color(self, (full_id, show_settings, method): (bool, bool, String)) {
+    ///   recommended that this stay false as its serialization will often be different from the rest of the data.
+    /// - `decoders` -> Which [decoder](../../decoder_selection/struct.DecoderSelection.html) to use for each address,
+    ///   based on the namespace prefix of the address. See
+    ///   [supported methods](../../index.html#supported-deserialization-methods) for a list of valid options
+    ///
+    /// The decoder names are looked up in `registry`, which holds the available [decoders](../../json_deserialize/trait.PayloadDecoder.html).
+    pub fn display_full_data_no_color(self, (full_id, show_settings, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) {
         for state in self.data.iter() {
             if show_settings || state.get_address_namespace() != "000000" {
                 if full_id {
-                    println!("{} {}", "State Address:", state.get_address_full());
+                    println!("State Address: {}", state.get_address_full());
                 } else {
-                    println!("{} {}", "State Address:", get_partial_string(state.get_address_full(), 6, 4));
+                    println!("State Address: {}", get_partial_string(state.get_address_full(), 6, 4));
                 }
 
                 let payload_encoded = String::from(state.data.as_str());
 
-                // If printing out the settings block, don't deserialize it
-                if show_settings && state.get_address_namespace() == "000000" {
+                if let Some(family) = get_well_known_family(&state.address) {
+                    println!("\tFamily: {}", family);
+                }
+
+                // If printing out the settings block, don't deserialize it unless its namespace is mapped
+                if show_settings && state.get_address_namespace() == "000000" && decoders.get_namespace_decoder(&state.address).is_none() {
                     println!("\tData:\n{}", payload_encoded)
                 } else {
-                    match method.as_str() {
-                    "cbor" => println!("\tData:\n{}", parse_cbor(payload_encoded, 2)),
-                    "json" => println!("\tData:\n{}", parse_json(payload_encoded, 2)),
-                    "custom" => println!("\tData:\n{}", parse_custom(payload_encoded, 2)),
-                    _ => panic!("Unsupported deserialization method: {}", method)
+                    match registry.parse(decoders.for_address(&state.address), &payload_encoded, 2) {
+                        Ok(decoded) => println!("\tData:\n{}", decoded),
+                        Err(error) => println!("\tData:\n{}", describe_decode_error(&payload_encoded, &error, 2)),
                     }
                 }
             }
         }
     }
+
+    /// Returns the state as pretty printed JSON, in the same structure as the `/state` endpoint. Each
+    /// address has an extra `family` element if it belongs to a well-known transaction family, and a
+    /// `decoded_data` element with the name of the `decoder` used, and either the decoded `value` or the
+    /// `error` that stopped the data from being decoded.
+    ///
+    /// The function takes in the same tuple (full_id, show_settings, decoders) of settings as
+    /// [display_full_data](struct.StateData.html#method.display_full_data). Addresses are always written in full, so `full_id`
+    /// is ignored. Settings addresses have no `decoded_data` unless their namespace is mapped to a decoder.
+    pub fn to_json(&self, (_, show_settings, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> String {
+        let states = self.data.iter()
+            .filter(|state| show_settings || state.get_address_namespace() != "000000")
+            .map(|state| {
+                let decoded_data = match (state.get_address_namespace().as_str(), decoders.get_namespace_decoder(&state.address)) {
+                    ("000000", None) => None,
+                    _ => Some(DecodedPayload::new(registry, decoders.for_address(&state.address), &state.data)),
+                };
+                StateOutput { state, family: get_well_known_family(&state.address), decoded_data }
+            })
+            .collect();
+
+        let output = StateDataOutput { data: states, head: &self.head, link: &self.link, paging: &self.paging };
+        serde_json::to_string_pretty(&output).expect("state can always be written as JSON")
+    }
+
+    /// Returns the state as a single static HTML page, which can be opened in any browser without other files.
+    ///
+    /// Every address is a collapsible section with the family it belongs to, if it is well-known, and its
+    /// decoded data. Addresses show their full value on hover.
+    ///
+    /// The function takes in the same tuple (full_id, show_settings, decoders) of settings as
+    /// [display_full_data](struct.StateData.html#method.display_full_data). Settings addresses are shown in base64
+    /// unless their namespace is mapped to a decoder.
+    pub fn to_html(&self, (full_id, show_settings, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> String {
+        let mut body = String::new();
+        for state in self.data.iter().filter(|state| show_settings || state.get_address_namespace() != "000000") {
+            let _ = writeln!(body, "<details class=\"state\" open>");
+            let _ = writeln!(body, "<summary>State Address {}</summary>", html::id_span(&state.address, full_id));
+            if let Some(family) = get_well_known_family(&state.address) {
+                let _ = writeln!(body, "<dl><dt>Family</dt><dd>{}</dd></dl>", html::escape(family));
+            }
+
+            match (state.get_address_namespace().as_str(), decoders.get_namespace_decoder(&state.address)) {
+                ("000000", None) => { let _ = writeln!(body, "<pre>{}</pre>", html::escape(&state.data)); },
+                _ => { let _ = writeln!(body, "{}", html::decoded_payload(registry, decoders.for_address(&state.address), &state.data)); },
+            }
+            let _ = writeln!(body, "</details>");
+        }
+        html::page("State", &body)
+    }
+
+    /// Returns an interactive [Browser](../../browser/struct.Browser.html) over the state, which lists the addresses
+    /// and shows the decoded data of the selected address.
+    ///
+    /// The function takes in the same tuple (full_id, show_settings, decoders) of settings as
+    /// [display_full_data](struct.StateData.html#method.display_full_data). `full_id` only shortens the addresses in
+    /// the list, since the details always show them in full. Settings addresses are shown in base64 unless their
+    /// namespace is mapped to a decoder.
+    pub fn to_browser(&self, (full_id, show_settings, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> Browser {
+        let states = self.data.iter()
+            .filter(|state| show_settings || state.get_address_namespace() != "000000")
+            .map(|state| {
+                let data = match (state.get_address_namespace().as_str(), decoders.get_namespace_decoder(&state.address)) {
+                    ("000000", None) => state.data.clone(),
+                    _ => match registry.parse(decoders.for_address(&state.address), &state.data, 0) {
+                        Ok(decoded) => decoded,
+                        Err(error) => describe_decode_error(&state.data, &error, 0),
+                    },
+                };
+                let label = label_id(&state.address, full_id);
+                let mut fields = vec![("Address", state.get_address_full())];
+                if let Some(family) = get_well_known_family(&state.address) {
+                    fields.push(("Family", String::from(family)));
+                }
+                Node::new(label, fields, Some(data), Vec::new())
+            })
+            .collect();
+        Browser::new("State", states)
+    }
+}
+
+impl Paged for StateData {
+    fn get_next_page(&self) -> Option<&str> {
+        self.paging.get_next()
+    }
+
+    fn append_page(&mut self, page: StateData) {
+        self.data.extend(page.data);
+        self.paging.append(page.paging);
+    }
+}
+
+/// The JSON output of a [StateData](struct.StateData.html), with the addresses that are shown.
+#[derive(Serialize)]
+struct StateDataOutput<'a> {
+    data: Vec<StateOutput<'a>>,
+    head: &'a str,
+    link: &'a str,
+    paging: &'a Paging,
+}
+
+/// The JSON output of a [State](struct.State.html), with its decoded data.
+#[derive(Serialize)]
+struct StateOutput<'a> {
+    #[serde(flatten)]
+    state: &'a State,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    family: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoded_data: Option<DecodedPayload<'a>>,
+}
+
+/// Returns true if `address` is written as a state address, which is 70 hex characters.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_saw_view::json_structs::json_state::{is_address};
+///
+/// assert!(is_address("1cf1266258bf86bf61f911799bbfee3c2796a89c255d59a7f3371f91876980d5da0e0c"));
+/// assert!(!is_address("1cf126"));
+/// ```
+pub fn is_address(address: &str) -> bool {
+    address.len() == 70 && address.chars().all(|c| c.is_ascii_hexdigit())
 }
 
 /// A structure that represents a state found at the `/state` endpoint. States contain an address and the data stored at that address
-#[derive(Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct State {
     address: String,
     data: String,
@@ -135,6 +283,33 @@ impl State {
     pub fn get_address_full(&self) -> String {
         String::from(&self.address[0..])
     }
+
+    /// Returns the data stored at the address, encoded in base64.
+    pub fn get_data(&self) -> &str {
+        &self.data
+    }
+}
+
+/// A structure that represents the data found at the `/state/{address}` endpoint, which holds the data stored at a
+/// single address.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct StateResponse {
+    data: String,
+    head: String,
+    link: String,
+}
+
+impl StateResponse {
+    /// Converts the response into a [StateData](struct.StateData.html) to display it. The response only holds
+    /// the data, so the `address` it was read from has to be given.
+    pub fn into_state_data(self, address: &str) -> StateData {
+        StateData {
+            data: vec![State { address: String::from(address), data: self.data }],
+            head: self.head,
+            link: self.link,
+            paging: Paging::default(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -161,6 +336,16 @@ mod test_statedata_struct {
         data.data.push(State::default());
         assert_eq!(2, data.get_num_states());
     }
+
+    #[test]
+    fn from_single_address() {
+        let address = "1cf1266258bf86bf61f911799bbfee3c2796a89c255d59a7f3371f91876980d5da0e0c";
+        let response = ::json_reader::read_state_address_from_file("example-blockchain/state_address.json").unwrap();
+        let data = response.into_state_data(address);
+        assert_eq!(1, data.get_num_states());
+        assert_eq!(address, data.data[0].get_address_full());
+        assert_eq!("oWRudW0xAQ==", data.data[0].data);
+    }
 }
 
 #[cfg(test)]
@@ -169,23 +354,98 @@ mod test_state_struct {
 
     #[test]
     fn namespace_is_six_characters_long() {
-        let mut data = State::default();
-        data.address = String::from("1cf126e83dbe4cdd233ab6402f1c19b0d93543f5da490356beab9c53435eef849dfcab");
+        let data = State { address: String::from("1cf126e83dbe4cdd233ab6402f1c19b0d93543f5da490356beab9c53435eef849dfcab"), ..State::default() };
         assert_eq!(6, data.get_address_namespace().len());
     }
 
     #[test]
     fn namespace_is_first_six_characters() {
-        let mut data = State::default();
-        data.address = String::from("1cf126e83dbe4cdd233ab6402f1c19b0d93543f5da490356beab9c53435eef849dfcab");
+        let data = State { address: String::from("1cf126e83dbe4cdd233ab6402f1c19b0d93543f5da490356beab9c53435eef849dfcab"), ..State::default() };
         assert_eq!(String::from("1cf126"), data.get_address_namespace());
     }
 
+    #[test]
+    fn address_is_seventy_hex_characters() {
+        assert!(is_address("1CF126e83dbe4cdd233ab6402f1c19b0d93543f5da490356beab9c53435eef849dfcab"));
+        assert!(!is_address(&format!("aaaaa\u{e9}{}", "a".repeat(63))));
+        assert!(!is_address(&format!("{}g", "a".repeat(69))));
+        assert!(!is_address(&"a".repeat(71)));
+    }
+
     #[test]
     #[should_panic(expected = "Invalid address")]
     fn invalid_namespace_panics() {
-        let mut data = State::default();
-        data.address = String::from("123ABC");
+        let data = State { address: String::from("123ABC"), ..State::default() };
         data.get_address_namespace();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_statedata_json {
+    use super::*;
+    use super::serde_json::Value;
+
+    #[test]
+    fn includes_family_and_decoded_data() {
+        let data = ::json_reader::read_state_data_from_file("example-blockchain/state.json").unwrap();
+        let registry = DecoderRegistry::default();
+        let mut decoders = DecoderSelection::new("cbor");
+        decoders.add_well_known_namespace_rules(&registry);
+        let json: Value = serde_json::from_str(&data.to_json((false, true, &decoders), &registry)).unwrap();
+
+        let states = json["data"].as_array().unwrap();
+        assert_eq!(data.get_num_states(), states.len());
+        let settings = states.iter().find(|state| state["family"] == "sawtooth_settings").unwrap();
+        assert_eq!("sawtooth_settings_state", settings["decoded_data"]["decoder"]);
+        assert!(settings["decoded_data"]["value"]["sawtooth.settings.vote.authorized_keys"].is_string());
+        let intkey = states.iter().find(|state| state["family"] == "intkey").unwrap();
+        assert!(intkey["decoded_data"]["value"].is_object());
+    }
+
+    #[test]
+    fn settings_are_omitted_by_default() {
+        let data = ::json_reader::read_state_data_from_file("example-blockchain/state.json").unwrap();
+        let json: Value = serde_json::from_str(&data.to_json((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default())).unwrap();
+        assert!(json["data"].as_array().unwrap().iter().all(|state| state["family"] != "sawtooth_settings"));
+    }
+}
+
+#[cfg(test)]
+mod test_statedata_html {
+    use super::*;
+
+    #[test]
+    fn includes_family_and_decoded_data() {
+        let data = ::json_reader::read_state_data_from_file("example-blockchain/state.json").unwrap();
+        let registry = DecoderRegistry::default();
+        let mut decoders = DecoderSelection::new("cbor");
+        decoders.add_well_known_namespace_rules(&registry);
+        let html = data.to_html((false, true, &decoders), &registry);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(data.get_num_states(), html.matches("<details class=\"state\" open>").count());
+        assert!(html.contains("<dt>Family</dt><dd>intkey</dd>"));
+        assert!(html.contains("&quot;sawtooth.settings.vote.authorized_keys&quot; : "));
+        assert!(!html.contains("<pre class=\"error\">"));
+    }
+
+    #[test]
+    fn settings_are_omitted_by_default() {
+        let data = ::json_reader::read_state_data_from_file("example-blockchain/state.json").unwrap();
+        let html = data.to_html((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert!(!html.contains("<dd>sawtooth_settings</dd>"));
+    }
+}
+
+#[cfg(test)]
+mod test_statedata_browser {
+    use super::*;
+
+    #[test]
+    fn lists_addresses() {
+        let data = ::json_reader::read_state_data_from_file("example-blockchain/state.json").unwrap();
+        let browser = data.to_browser((true, true, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert_eq!(data.get_num_states(), browser.get_num_nodes());
+        assert_eq!(70, browser.get_selected().unwrap().get_label().len());
+    }
+}
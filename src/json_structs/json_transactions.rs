@@ -0,0 +1,306 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `json_transactions` contains the structure that represents the JSON structured data from the
+//! `/transactions` endpoint of Hyperledger Sawtooth, along with methods for filtering and displaying it.
+//!
+//! The endpoint lists transactions on their own, without the batches and blocks that contain them, so the
+//! root [TransactionList](struct.TransactionList.html) structure reuses the
+//! [Transaction](../json_blocks/struct.Transaction.html) structure of the `/blocks` endpoint. A
+//! [TransactionFilter](struct.TransactionFilter.html) narrows the list down to the transactions of one family
+//! or client, before it is displayed with the same methods as [BatchList](../json_batches/struct.BatchList.html).
+
+extern crate serde_json;
+
+use super::json_blocks::{Paging, Transaction, TransactionOutput};
+use super::Paged;
+use browser::{Browser};
+use decoder_selection::{DecoderSelection};
+use html;
+use json_deserialize::*;
+
+/// A structure that represents the root data item found at the `/transactions` endpoint.
+///
+/// Transactions aren't part of a block here, so there is no genesis block to leave out. The `show_genesis` setting
+/// taken by the display methods is ignored, and every payload is decoded.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TransactionList {
+    data: Vec<Transaction>,
+    head: String,
+    link: String,
+    paging: Paging
+}
+
+impl TransactionList {
+
+    /// Returns the number of transactions contained.
+    pub fn get_num_txns(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Removes every transaction that doesn't match `filter`.
+    pub fn retain_matching(&mut self, filter: &TransactionFilter) {
+        self.data.retain(|txn| filter.matches(txn));
+    }
+
+    /// Display the individual transactions and their payloads.
+    /// Uses colored text so use this for terminal printing.
+    ///
+    /// If instead you want to pipe this data to a file, see [display_full_data_no_color](struct.TransactionList.html#method.display_full_data_no_color)
+    /// since it prints with no coloring.
+    ///
+    /// The function takes in the same tuple (full_id, show_genesis, decoders) of settings as
+    /// [BlockData::display_full_data](../json_blocks/struct.BlockData.html#method.display_full_data), except that
+    /// `show_genesis` is ignored.
+    pub fn display_full_data(&self, (full_id, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) {
+        for (count, txn) in self.data.iter().enumerate() {
+            txn.display(count, 0, false, None, (full_id, decoders), registry);
+        }
+    }
+
+    /// Display the individual transactions and their payloads.
+    /// No terminal color, so use this for piping to a file.
+    ///
+    /// If instead you want to print this to a terminal window, see [display_full_data](struct.TransactionList.html#method.display_full_data)
+    /// since it prints with coloring.
+    ///
+    /// The function takes in the same tuple (full_id, show_genesis, decoders) of settings as
+    /// [BlockData::display_full_data](../json_blocks/struct.BlockData.html#method.display_full_data), except that
+    /// `show_genesis` is ignored.
+    pub fn display_full_data_no_color(&self, (full_id, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) {
+        for (count, txn) in self.data.iter().enumerate() {
+            txn.display_no_color(count, 0, false, None, (full_id, decoders), registry);
+        }
+    }
+
+    /// Returns the transactions as pretty printed JSON, in the same structure as the `/transactions` endpoint.
+    /// Each transaction has extra `decoded_payload` and `payload_sha512_matches` elements, as described in
+    /// [BlockData::to_json](../json_blocks/struct.BlockData.html#method.to_json).
+    pub fn to_json(&self, (_, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> String {
+        let output = TransactionListOutput {
+            data: self.data.iter().map(|txn| TransactionOutput::new(txn, false, None, decoders, registry)).collect(),
+            head: &self.head,
+            link: &self.link,
+            paging: &self.paging,
+        };
+        serde_json::to_string_pretty(&output).expect("transactions can always be written as JSON")
+    }
+
+    /// Returns the transactions as a single static HTML page, with a collapsible section for every transaction.
+    /// See [BlockData::to_html](../json_blocks/struct.BlockData.html#method.to_html).
+    pub fn to_html(&self, (full_id, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> String {
+        let mut body = String::new();
+        for (count, txn) in self.data.iter().enumerate() {
+            txn.write_html(&mut body, count, false, None, (full_id, decoders), registry);
+        }
+        html::page("Transactions", &body)
+    }
+
+    /// Returns an interactive [Browser](../../browser/struct.Browser.html) that lists the transactions. See
+    /// [BlockData::to_browser](../json_blocks/struct.BlockData.html#method.to_browser).
+    pub fn to_browser(&self, (full_id, _, decoders): (bool, bool, &DecoderSelection), registry: &DecoderRegistry) -> Browser {
+        let txns = self.data.iter()
+            .map(|txn| txn.to_node(false, None, (full_id, decoders), registry))
+            .collect();
+        Browser::new("Transactions", txns)
+    }
+}
+
+impl Paged for TransactionList {
+    fn get_next_page(&self) -> Option<&str> {
+        self.paging.get_next()
+    }
+
+    fn append_page(&mut self, page: TransactionList) {
+        self.data.extend(page.data);
+        self.paging.append(page.paging);
+    }
+}
+
+/// The JSON output of a [TransactionList](struct.TransactionList.html).
+#[derive(Serialize)]
+struct TransactionListOutput<'a> {
+    data: Vec<TransactionOutput<'a>>,
+    head: &'a str,
+    link: &'a str,
+    paging: &'a Paging,
+}
+
+/// A structure that represents the data found at the `/transactions/{transaction_id}` endpoint, which holds a single
+/// transaction.
+///
+/// Convert it into a [TransactionList](struct.TransactionList.html) to display the transaction. The response doesn't
+/// name the head of the chain, so the `head` of the converted list is empty.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TransactionResponse {
+    data: Transaction,
+    link: String,
+}
+
+impl TransactionResponse {
+    /// Returns the transaction held by the response.
+    pub fn get_transaction(&self) -> &Transaction {
+        &self.data
+    }
+}
+
+impl From<TransactionResponse> for TransactionList {
+    fn from(response: TransactionResponse) -> TransactionList {
+        TransactionList { data: vec![response.data], head: String::new(), link: response.link, paging: Paging::default() }
+    }
+}
+
+/// Selects transactions by their family and the clients that signed them. A transaction matches if it
+/// matches every criteria that is set, so an empty filter matches every transaction.
+///
+/// Public keys match if they start with the given key, so the shortened keys printed without `--full-addr`
+/// can be used after dropping the `...` and the last characters.
+#[derive(Debug, Default, Clone)]
+pub struct TransactionFilter {
+    family_name: Option<String>,
+    signer_public_key: Option<String>,
+    batcher_public_key: Option<String>,
+}
+
+impl TransactionFilter {
+    /// Only match transactions of the family `family_name`, e.g. `intkey`.
+    pub fn set_family_name(&mut self, family_name: &str) {
+        self.family_name = Some(String::from(family_name));
+    }
+
+    /// Only match transactions signed by the public key `signer_public_key`, or a key starting with it.
+    pub fn set_signer_public_key(&mut self, signer_public_key: &str) {
+        self.signer_public_key = Some(signer_public_key.to_lowercase());
+    }
+
+    /// Only match transactions whose batch was signed by the public key `batcher_public_key`, or a key starting with it.
+    pub fn set_batcher_public_key(&mut self, batcher_public_key: &str) {
+        self.batcher_public_key = Some(batcher_public_key.to_lowercase());
+    }
+
+    /// Returns true if no criteria is set.
+    pub fn is_empty(&self) -> bool {
+        self.family_name.is_none() && self.signer_public_key.is_none() && self.batcher_public_key.is_none()
+    }
+
+    /// Returns true if `txn` matches every criteria that is set.
+    pub fn matches(&self, txn: &Transaction) -> bool {
+        let header = txn.get_header();
+        let key_matches = |key: &str, prefix: &Option<String>| prefix.as_ref().is_none_or(|prefix| key.to_lowercase().starts_with(prefix.as_str()));
+
+        self.family_name.as_ref().is_none_or(|family| header.get_family_name() == family)
+            && key_matches(header.get_signer_public_key(), &self.signer_public_key)
+            && key_matches(header.get_batcher_public_key(), &self.batcher_public_key)
+    }
+}
+
+#[cfg(test)]
+mod test_transactionlist_struct {
+    use super::*;
+
+    #[test]
+    fn num_txns() {
+        let data = ::json_reader::read_transaction_list_from_file("example-blockchain/transactions.json").unwrap();
+        assert_eq!(6, data.get_num_txns());
+    }
+
+    #[test]
+    fn num_txns_empty() {
+        assert_eq!(0, TransactionList::default().get_num_txns());
+    }
+
+    #[test]
+    fn from_single_transaction() {
+        let response = ::json_reader::read_transaction_from_file("example-blockchain/transaction.json").unwrap();
+        let data = TransactionList::from(response);
+        assert_eq!(1, data.get_num_txns());
+        assert_eq!("intkey", data.data[0].get_header().get_family_name());
+    }
+}
+
+#[cfg(test)]
+mod test_transaction_filter {
+    use super::*;
+
+    const INTKEY_SIGNER: &str = "03150ea949d5a35fd7026e9e7b6fc5fae6a322f49ed96e41bdf8ecdb677c4696b4";
+    const SETTINGS_SIGNER: &str = "026fbc85";
+
+    fn filtered(filter: &TransactionFilter) -> TransactionList {
+        let mut data = ::json_reader::read_transaction_list_from_file("example-blockchain/transactions.json").unwrap();
+        data.retain_matching(filter);
+        data
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        let filter = TransactionFilter::default();
+        assert!(filter.is_empty());
+        assert_eq!(6, filtered(&filter).get_num_txns());
+    }
+
+    #[test]
+    fn by_family_name() {
+        let mut filter = TransactionFilter::default();
+        filter.set_family_name("intkey");
+        assert!(!filter.is_empty());
+        assert_eq!(5, filtered(&filter).get_num_txns());
+
+        filter.set_family_name("xo");
+        assert_eq!(0, filtered(&filter).get_num_txns());
+    }
+
+    #[test]
+    fn by_full_signer_key() {
+        let mut filter = TransactionFilter::default();
+        filter.set_signer_public_key(INTKEY_SIGNER);
+        assert_eq!(5, filtered(&filter).get_num_txns());
+    }
+
+    #[test]
+    fn by_key_prefix() {
+        let mut filter = TransactionFilter::default();
+        filter.set_batcher_public_key(&SETTINGS_SIGNER.to_uppercase());
+        assert_eq!(1, filtered(&filter).get_num_txns());
+    }
+
+    #[test]
+    fn every_criteria_must_match() {
+        let mut filter = TransactionFilter::default();
+        filter.set_family_name("intkey");
+        filter.set_signer_public_key(SETTINGS_SIGNER);
+        assert_eq!(0, filtered(&filter).get_num_txns());
+    }
+}
+
+#[cfg(test)]
+mod test_transactionlist_output {
+    use super::*;
+    use super::serde_json::Value;
+
+    #[test]
+    fn json_includes_decoded_payloads() {
+        let data = ::json_reader::read_transaction_list_from_file("example-blockchain/transactions.json").unwrap();
+        let json: Value = serde_json::from_str(&data.to_json((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default())).unwrap();
+
+        let txns = json["data"].as_array().unwrap();
+        assert_eq!(6, txns.len());
+        assert_eq!("num1", txns[0]["decoded_payload"]["value"]["Name"]);
+        assert_eq!("intkey", txns[0]["header"]["family_name"]);
+        assert_eq!("valid", txns[0]["signature"]);
+        // There is no batch to check the batcher against
+        assert!(txns[0].get("batcher_matches").is_none());
+    }
+
+    #[test]
+    fn html_and_browser_list_transactions() {
+        let data = ::json_reader::read_transaction_list_from_file("example-blockchain/transactions.json").unwrap();
+        let html = data.to_html((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert_eq!(6, html.matches("<details class=\"transaction\">").count());
+
+        let browser = data.to_browser((false, false, &DecoderSelection::new("cbor")), &DecoderRegistry::default());
+        assert_eq!(6, browser.get_num_nodes());
+        assert!(browser.get_selected().unwrap().get_label().starts_with("intkey 1.0 "));
+    }
+}
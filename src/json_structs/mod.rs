@@ -6,17 +6,75 @@
 //! `json_structs` is a collection of submodules that define structures that map to the JSON
 //! data found at various endpoints for Hyperledger Sawtooth.
 //! 
-//! As of now there are only modules that represent the `/blocks` and `/state` endpoints.
+//! As of now there are modules that represent the `/blocks`, `/batches`, `/transactions`, `/state`, `/batch_statuses`
+//! and `/receipts` endpoints.
 
+pub mod json_batch_statuses;
+pub mod json_batches;
 pub mod json_blocks;
+pub mod json_receipts;
 pub mod json_state;
+pub mod json_transactions;
+
+extern crate sha2;
+
+use colored::{Color, ColoredString, Colorize};
+use decoded_value::{DecodedValue};
+use json_deserialize::{DecoderRegistry, to_hex};
+
+use self::sha2::{Digest, Sha512};
+
+/// A list found at an endpoint that splits its entries into pages, such as `/blocks` or `/state`.
+/// The [json_reader](../json_reader/index.html) uses it to follow the links to the next pages and merge them.
+pub trait Paged {
+    /// Returns the link to the next page, or `None` if this is the last page.
+    fn get_next_page(&self) -> Option<&str>;
+
+    /// Appends the entries of `page`, which is the page that follows this one.
+    fn append_page(&mut self, page: Self);
+}
+
+/// The decoded form of a payload, as written next to the base64 payload in the JSON output.
+/// Holds either the decoded `value`, or the `error` that stopped the payload from being decoded.
+#[derive(Serialize, Debug)]
+pub(crate) struct DecodedPayload<'a> {
+    decoder: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<DecodedValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl<'a> DecodedPayload<'a> {
+    /// Decodes the base64 `payload` with the decoder registered under `method`.
+    pub(crate) fn new(registry: &DecoderRegistry, method: &'a str, payload: &str) -> DecodedPayload<'a> {
+        match registry.decode(method, payload) {
+            Ok(value) => DecodedPayload { decoder: method, value: Some(value), error: None },
+            Err(error) => DecodedPayload { decoder: method, value: None, error: Some(error.to_string()) },
+        }
+    }
+}
+
+/// Returns the SHA-512 hash of `data` as a lowercase hex string, the way Sawtooth writes hashes such as the
+/// `payload_sha512` of a transaction header.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_saw_view::json_structs::{sha512_hex};
+///
+/// assert!(sha512_hex(b"abc").starts_with("ddaf35a193617aba"));
+/// ```
+pub fn sha512_hex(data: &[u8]) -> String {
+    to_hex(&Sha512::digest(data))
+}
 
 /// Returns a string of the first *n* and last *m* characters of the given string
 /// 
 /// # Examples
 ///
 /// ```
-/// use json_structs::{get_partial_string}
+/// use rusty_saw_view::json_structs::{get_partial_string};
 ///
 /// let st = String::from("ABCddddABC");
 /// assert_eq!(String::from("ABC...ABC"), get_partial_string(st, 3, 3));
@@ -32,6 +90,32 @@ pub fn get_partial_string(full_string: String, n: usize, m: usize) -> String {
         String::from(&full_string[(full_string.len() - m)..]))
 }
 
+/// Returns `id` as it is shown when `full_id` isn't set, which is its first 6 and last 4 characters. IDs of 10
+/// characters or less are returned in full.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_saw_view::json_structs::{label_id};
+///
+/// assert_eq!("1cf126...0e0c", label_id("1cf1266e694f1d8d8e2c40a9d2c6cba1b5d1d9ca2e3e95a5a8e3f1e3f8c50e0c", false));
+/// assert_eq!("abc", label_id("abc", false));
+/// ```
+pub fn label_id(id: &str, full_id: bool) -> String {
+    match full_id || id.len() <= 10 {
+        true => String::from(id),
+        false => get_partial_string(String::from(id), 6, 4),
+    }
+}
+
+/// Returns `text` in bold `text_color` if `color` is set, or as plain text for piping to a file.
+pub(crate) fn paint(text: &str, text_color: Color, color: bool) -> ColoredString {
+    match color {
+        true => text.color(text_color).bold(),
+        false => text.normal(),
+    }
+}
+
 #[cfg(test)]
 mod test_partial_string {
     use super::*;
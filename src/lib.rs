@@ -0,0 +1,130 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `rusty-saw-view` is a Rust program that helps visualize blockchain data from 
+//! [Hyperledger Sawtooth](https://www.hyperledger.org/projects/sawtooth). Sawtooth is a blockchain framework that 
+//! provides a REST API to query information such as the current state of the ledger, individual blocks and
+//! transaction, and the entire blockchain itself. Requesting this information from the respective HTTP endpoints returns a 
+//! JSON formatted block of data. 
+//! 
+//! From my own experience, Sawtooth is great for including a blockchain based distributed ledger into an application. However 
+//! I learned that explaining how transactions were being made and how the blockchain worked was difficult with people who were 
+//! unfamiliar with the concept of a blockchain. For transactions and blockchains that were simple I could easily draw it on a 
+//! whiteboard to help explain it. As the transactions and application got more complex, so did the difficulty in explaining what 
+//! was going on. Showing the JSON data wasn't really to helpful because (1) the data in each block is serialized and encoded (2) 
+//! and while it's in human readable JSON format, it's hard to connect each block/transaction to each other when it's just one 
+//! stream of text.
+//! 
+//! `rusty-saw-view` attempts to solve this problem by contacting the HTTP endpoints of a Sawtooth node and requesting the JSON data. 
+//! It's job is to parse and visually display this data in a way thats easy to understand and explain.
+//! 
+//! ## Usage
+//! Running the command `cargo run -- -help` will display the folowing:
+//! ```bash
+//! USAGE:
+//!     rusty-saw-view [FLAGS] [OPTIONS] <endpoint> <method> <source> <location>
+//! 
+//! FLAGS:
+//!         --follow       Keeps reading the blocks endpoint of a url source, and prints each block as it is committed
+//!     -f, --full-addr    Prints out full addresses & PubKeys
+//!     -g, --genesis      Prints out the settings state or genesis block depending on the context
+//!     -h, --help         Prints help information
+//!     -n, --no-color     Prints without colored text. Use for piping to file
+//!         --reverse      Reads the blocks from the oldest to the newest
+//!     -V, --version      Prints version information
+//!         --verify       Checks that the blocks link up into a chain, match the IDs and payload hashes in their headers
+//!                        and were signed by their signers, and prints every problem instead of the blocks. Only for
+//!                        the blocks endpoint
+//! OPTIONS:
+//!         --address <PREFIX>       Only reads the state under addresses starting with this prefix, e.g. a namespace
+//!         --batcher <KEY>          Only shows transactions batched by this public key, or a key starting with it
+//!     -c, --config <FILE>          JSON config file with decoder rules. Rules passed with --family or --namespace take precedence
+//!         --count <N>              Only reads this many blocks
+//!         --family <RULE>...       Decodes a transaction family with its own method. Written as NAME[:VERSION]=DECODER
+//!         --family-name <NAME>     Only shows transactions of this family. Only for the transactions endpoint
+//!         --head <BLOCK_ID>        Reads the state or blocks as of this block, instead of the current chain head
+//!         --header <NAME:VALUE>... Sends this header with every request to a url source, e.g. an API key for a proxy
+//!         --history <KEY>          Lists every transaction of the blocks that touched this state address, or the intkey
+//!                                  value with this name, with the operation and the value it left. Only for the blocks
+//!                                  endpoint
+//!         --id <ID>...             Batch IDs for the batch_statuses endpoint, or transaction IDs for the receipts endpoint.
+//!                                  For the other endpoints, looks up the one block, batch, transaction or state address
+//!         --interval <SECONDS>     How often --follow checks for new blocks. Defaults to 2
+//!         --limit <N>              How many entries Sawtooth returns on each page of a url source
+//!         --message <NAME>...      Adds a decoder for a message of the --proto schema, named after the full message name
+//!         --namespace <RULE>...    Decodes the state under a namespace with its own method. Written as PREFIX=DECODER
+//!     -o, --output <FORMAT>        How to print the data. json includes the decoded payloads as structured values, dot
+//!                                  draws the blocks as a Graphviz graph, html writes a report to open in a browser, tui
+//!                                  browses the data in the terminal [default: text]  [possible values: text, json, dot,
+//!                                  html, tui]
+//!         --pages <N>              Reads at most this many pages of a url source. Every page is read by default
+//!         --proto <FILE>...        Protobuf schema as a compiled FileDescriptorSet or .proto files
+//!         --replay <STATE>         Replays the intkey transactions of the blocks, and compares the state they leave
+//!                                  with the state at this location, which is read from the same source as the blocks.
+//!                                  Prints every difference instead of the blocks. Only for the blocks endpoint
+//!         --retries <N>            Retries a request to a url source this many times if it fails to connect, or gets a
+//!                                  429 or 5xx status
+//!         --signer <KEY>           Only shows transactions signed by this public key, or a key starting with it
+//!         --start <POSITION>       The block ID, batch ID, transaction ID or state address the list of a url source starts at
+//!         --timeout <SECONDS>      Gives up on a request to a url source after this many seconds. Defaults to 30
+//!         --user <USER>            Authenticates every request to a url source with HTTP basic authentication.
+//!                                  Written as NAME or NAME:PASSWORD
+//! ARGS:
+//!     <endpoint>    From which endpoint is the data coming from? [possible values: state, blocks, batches, transactions,
+//!                   batch_statuses, receipts]
+//!     <method>      What deserialization method to use? [built-in: cbor, json, sawtooth_settings, sawtooth_settings_state]
+//!                   or a message passed with --message
+//!     <source>      Where is the data coming from? [possible values: file, url]
+//!     <location>    File path, or base URL of the REST API, e.g. http://localhost:8008
+//! ```
+//! 
+//! ## Supported Deserialization Methods
+//! Out of the box the following deserialization methods are supported:
+//! - CBOR
+//! - JSON
+//! - The Protocol Buffers messages of the `sawtooth_settings` family, see [sawtooth_settings](sawtooth_settings/index.html)
+//! - Any Protocol Buffers message of a schema passed at run time, see [protobuf_schema](protobuf_schema/index.html)
+//! 
+//! Since Sawtooth is modular by design, it allows developers to use whatever serialization scheme they want.
+//! This made the development of this application difficult because it can't possibly support every scheme
+//! out of the box. For methods not listed in the previous section, implement the
+//! [PayloadDecoder](json_deserialize/trait.PayloadDecoder.html) trait and register it into a
+//! [DecoderRegistry](json_deserialize/struct.DecoderRegistry.html). The `<method>` values accepted by the CLI
+//! are the names of the decoders in the registry.
+//!
+//! Chains that run several transaction families can decode each family, or each state namespace, with a
+//! different method. See [decoder_selection](decoder_selection/index.html).
+//! 
+//! ## Additional Info
+//! For details on how to use this crate, see the README located at the projects [Github page](https://github.com/jvenetucci/rusty-saw-view)
+//! 
+//! Contact the author:
+//! 
+//! Joseph Venetucci <venetuc@pdx.edu>
+
+#[macro_use]
+extern crate serde_derive;
+
+extern crate serde;
+extern crate colored;
+extern crate prost;
+
+pub mod browser;
+pub mod client;
+pub mod config;
+pub mod decoded_value;
+pub mod decoder_selection;
+pub mod error;
+pub mod follower;
+pub mod history;
+pub mod html;
+pub mod json_structs;
+pub mod json_reader;
+pub mod json_deserialize;
+pub mod protobuf_schema;
+pub mod replay;
+pub mod sawtooth_settings;
+pub mod signatures;
+pub mod verify;
@@ -3,89 +3,71 @@
 // Please see the file LICENSE.md in the
 // source distribution of this software for license terms.
 
-//! `rusty-saw-view` is a Rust program that helps visualize blockchain data from 
-//! [Hyperledger Sawtooth](https://www.hyperledger.org/projects/sawtooth). Sawtooth is a blockchain framework that 
-//! provides a REST API to query information such as the current state of the ledger, individual blocks and
-//! transaction, and the entire blockchain itself. Requesting this information from the respective HTTP endpoints returns a 
-//! JSON formatted block of data. 
-//! 
-//! From my own experience, Sawtooth is great for including a blockchain based distributed ledger into an application. However 
-//! I learned that explaining how transactions were being made and how the blockchain worked was difficult with people who were 
-//! unfamiliar with the concept of a blockchain. For transactions and blockchains that were simple I could easily draw it on a 
-//! whiteboard to help explain it. As the transactions and application got more complex, so did the difficulty in explaining what 
-//! was going on. Showing the JSON data wasn't really to helpful because (1) the data in each block is serialized and encoded (2) 
-//! and while it's in human readable JSON format, it's hard to connect each block/transaction to each other when it's just one 
-//! stream of text.
-//! 
-//! `rusty-saw-view` attempts to solve this problem by contacting the HTTP endpoints of a Sawtooth node and requesting the JSON data. 
-//! It's job is to parse and visually display this data in a way thats easy to understand and explain.
-//! 
-//! ## Usage
-//! Running the command `cargo run -- -help` will display the folowing:
-//! ```bash
-//! USAGE:
-//!     rusty-saw-view [FLAGS] <endpoint> <method> <source> <location>
-//! 
-//! FLAGS:
-//!     -f, --full-addr    Prints out full addresses & PubKeys
-//!     -g, --genesis      Prints out the settings state or genesis block depending on the context
-//!     -h, --help         Prints help information
-//!     -n, --no-color     Prints without colored text. Use for piping to file
-//!     -V, --version      Prints version information
-//! ARGS:
-//!     <endpoint>    From which endpoint is the data coming from? [possible values: state, blocks]
-//!     <method>      What deserialization method to use? [possible values: cbor, json, custom]
-//!     <source>      Where is the data coming from? [possible values: file, url]
-//!     <location>    File path, or URL to data
-//! ```
-//! 
-//! ## Supported Deserialization Methods
-//! Out of the box the following deserialization methods are supported:
-//! - CBOR
-//! - JSON
-//! 
-//! Since Sawtooth is modular by design, it allows developers to use whatever serialization scheme they want.
-//! This made the development of this application difficult because it can't possibly support every scheme
-//! out of the box. For methods not listed in the previous section, users will have to add it to a section of
-//! the project in order to use it. If you want to use a custom method or one not supported
-//! see the [parse_custom()](json_deserialize/fn.parse_custom.html) method
-//! 
-//! ## Additional Info
-//! For details on how to use this crate, see the README located at the projects [Github page](https://github.com/jvenetucci/rusty-saw-view)
-//! 
-//! Contact the author:
-//! 
-//! Joseph Venetucci <venetuc@pdx.edu>
+//! The command line interface for `rusty-saw-view`. It parses the CLI arguments, reads in the
+//! blockchain data using the [rusty_saw_view](../rusty_saw_view/index.html) library, and displays it.
+//!
+//! If the data can't be read then a short message is printed to stderr and the program exits
+//! with the code given by [Error::exit_code](../rusty_saw_view/error/enum.Error.html#method.exit_code).
 
-#[macro_use]
-extern crate serde_derive;
+extern crate rusty_saw_view;
 #[macro_use]
 extern crate clap;
 
-extern crate serde;
-extern crate colored;
-
-pub mod json_structs;
-pub mod json_reader;
-pub mod json_deserialize;
+use clap::{App, Arg, ArgMatches};
 
-use clap::{App, Arg};
+use std::process;
+use std::thread;
+use std::time::Duration;
 
-use json_structs::json_blocks::{BlockData};
-use json_structs::json_state::{StateData};
-use json_reader::{read_block_data_from_file, read_state_data_from_file,
-    read_block_data_from_endpoint, read_state_data_from_endpoint};
+use rusty_saw_view::client::{ListQuery, SawtoothClient};
+use rusty_saw_view::config::{read_config_from_file};
+use rusty_saw_view::decoder_selection::{DecoderSelection, FamilyRule, NamespaceRule};
+use rusty_saw_view::error::{Error};
+use rusty_saw_view::follower::{BlockFollower};
+use rusty_saw_view::history::{key_history};
+use rusty_saw_view::json_deserialize::{DecoderRegistry};
+use rusty_saw_view::json_structs::json_batch_statuses::{BatchStatusList};
+use rusty_saw_view::json_structs::json_batches::{BatchList};
+use rusty_saw_view::json_structs::json_blocks::{BlockData};
+use rusty_saw_view::json_structs::json_receipts::{ReceiptList};
+use rusty_saw_view::json_structs::json_state::{StateData, is_address};
+use rusty_saw_view::json_structs::json_transactions::{TransactionFilter, TransactionList};
+use rusty_saw_view::protobuf_schema::{ProtobufDecoder, read_descriptor_pool};
+use rusty_saw_view::replay::{INTKEY_NAMESPACE, replay_intkey};
+use rusty_saw_view::verify::{verify_blocks};
+use rusty_saw_view::json_reader::{read_block_data_from_file, read_state_data_from_file, read_batch_list_from_file,
+    read_transaction_list_from_file, read_batch_statuses_from_file, read_receipts_from_file, read_block_from_file,
+    read_batch_from_file, read_transaction_from_file, read_state_address_from_file};
 
 #[derive(Debug)]
 enum DataTypes {
     Blocks(BlockData),
     State(StateData),
+    Batches(BatchList),
+    Transactions(TransactionList),
+    BatchStatuses(BatchStatusList),
+    Receipts(ReceiptList),
 }
 
+/// The args that select which part of a list is read from a url, with the endpoints that accept each of them.
+const LIST_ARGS: [(&str, &[&str]); 7] = [
+    ("limit", &["state", "blocks", "batches", "transactions"]),
+    ("start", &["state", "blocks", "batches", "transactions"]),
+    ("pages", &["state", "blocks", "batches", "transactions"]),
+    ("head", &["state", "blocks"]),
+    ("address", &["state"]),
+    ("count", &["blocks"]),
+    ("reverse", &["blocks"]),
+];
+
 fn main() {
-    let endpoints = ["state", "blocks",];
-    let methods = ["cbor", "json", "custom",];
+    let endpoints = ["state", "blocks", "batches", "transactions", "batch_statuses", "receipts",];
     let sources = ["file", "url",];
+    let outputs = ["text", "json", "dot", "html", "tui",];
+
+    // The deserialization methods available are the built-in decoders, and the protobuf messages passed with --message
+    let method_help = format!("What deserialization method to use? [built-in: {}] or a message passed with --message",
+        DecoderRegistry::default().names().join(", "));
 
     // CLI clap configuration
     let matches = App::new("rusty-saw-view")
@@ -94,14 +76,61 @@ fn main() {
         .about("An application for parsing and viewing blockchain data from Hyperledger Sawtooth")
         .arg(Arg::from_usage("<endpoint> 'From which endpoint is the data coming from?'")
             .possible_values(&endpoints))
-        .arg(Arg::from_usage("<method> 'What deserialization method to use?'")
-            .possible_values(&methods))
+        .arg(Arg::from_usage("<method>")
+            .help(&method_help))
         .arg(Arg::from_usage("<source> 'Where is the data coming from?'")
             .possible_values(&sources))
-        .arg(Arg::from_usage("<location> 'File path, or URL to data'"))
+        .arg(Arg::from_usage("<location> 'File path, or base URL of the REST API, e.g. http://localhost:8008. The URL of the endpoint itself works too'"))
         .arg(Arg::from_usage("[no-color] -n --no-color 'Prints without colored text. Use for piping to file'"))
+        .arg(Arg::from_usage("[output] -o --output <FORMAT> 'How to print the data. json includes the decoded payloads as structured values, dot draws the blocks as a Graphviz graph, html writes a report to open in a browser, tui browses the data in the terminal'")
+            .possible_values(&outputs)
+            .default_value("text"))
         .arg(Arg::from_usage("[full-addr] -f --full-addr 'Prints out full addresses & PubKeys'"))
         .arg(Arg::from_usage("[genesis] -g --genesis 'Prints out the settings state or genesis block depending on the context'"))
+        .arg(Arg::from_usage("[family] --family <RULE>... 'Decodes a transaction family with its own method. Written as NAME[:VERSION]=DECODER, e.g. intkey=cbor'")
+            .number_of_values(1)
+            .validator(|rule| rule.parse::<FamilyRule>().map(|_| ())))
+        .arg(Arg::from_usage("[namespace] --namespace <RULE>... 'Decodes the state under a namespace with its own method. Written as PREFIX=DECODER, e.g. 1cf126=cbor'")
+            .number_of_values(1)
+            .validator(|rule| rule.parse::<NamespaceRule>().map(|_| ())))
+        .arg(Arg::from_usage("[config] -c --config <FILE> 'JSON config file with decoder rules. Rules passed with --family or --namespace take precedence'"))
+        .arg(Arg::from_usage("[proto] --proto <FILE>... 'Protobuf schema as a compiled FileDescriptorSet or .proto files'")
+            .number_of_values(1))
+        .arg(Arg::from_usage("[message] --message <NAME>... 'Adds a decoder for a message of the --proto schema, named after the full message name, e.g. xo.XoPayload'")
+            .number_of_values(1)
+            .requires("proto"))
+        .arg(Arg::from_usage("[family-name] --family-name <NAME> 'Only shows transactions of this family. Only for the transactions endpoint'"))
+        .arg(Arg::from_usage("[signer] --signer <KEY> 'Only shows transactions signed by this public key, or a key starting with it. Only for the transactions endpoint'"))
+        .arg(Arg::from_usage("[batcher] --batcher <KEY> 'Only shows transactions batched by this public key, or a key starting with it. Only for the transactions endpoint'"))
+        .arg(Arg::from_usage("[id] --id <ID>... 'Batch IDs for the batch_statuses endpoint, or transaction IDs for the receipts endpoint. Required with a url source. For the other endpoints, looks up the one block, batch, transaction or state address with this ID'")
+            .number_of_values(1))
+        .arg(Arg::from_usage("[limit] --limit <N> 'How many entries Sawtooth returns on each page of a url source'")
+            .validator(validate_count))
+        .arg(Arg::from_usage("[start] --start <POSITION> 'The block ID, batch ID, transaction ID or state address the list of a url source starts at'"))
+        .arg(Arg::from_usage("[pages] --pages <N> 'Reads at most this many pages of a url source. Every page is read by default'")
+            .validator(validate_count))
+        .arg(Arg::from_usage("[head] --head <BLOCK_ID> 'Reads the state or blocks as of this block, instead of the current chain head'"))
+        .arg(Arg::from_usage("[address] --address <PREFIX> 'Only reads the state under addresses starting with this prefix, e.g. a namespace'"))
+        .arg(Arg::from_usage("[count] --count <N> 'Only reads this many blocks'")
+            .validator(validate_count))
+        .arg(Arg::from_usage("[reverse] --reverse 'Reads the blocks from the oldest to the newest'"))
+        .arg(Arg::from_usage("[timeout] --timeout <SECONDS> 'Gives up on a request to a url source after this many seconds. Defaults to 30'")
+            .validator(validate_seconds))
+        .arg(Arg::from_usage("[retries] --retries <N> 'Retries a request to a url source this many times if it fails to connect, or gets a 429 or 5xx status'")
+            .validator(|retries| retries.parse::<u32>().map(|_| ()).map_err(|_| format!("`{}` isn't a number of retries", retries))))
+        .arg(Arg::from_usage("[header] --header <NAME:VALUE>... 'Sends this header with every request to a url source, e.g. an API key for a proxy'")
+            .number_of_values(1)
+            .validator(validate_header))
+        .arg(Arg::from_usage("[verify] --verify 'Checks that the blocks link up into a chain, match the IDs and payload hashes in their headers and were signed by their signers, and prints every problem instead of the blocks. Only for the blocks endpoint'"))
+        .arg(Arg::from_usage("[replay] --replay <STATE> 'Replays the intkey transactions of the blocks, and compares the state they leave with the state at this location, which is read from the same source as the blocks. Prints every difference instead of the blocks. Only for the blocks endpoint'")
+            .conflicts_with("verify"))
+        .arg(Arg::from_usage("[history] --history <KEY> 'Lists every transaction of the blocks that touched this state address, or the intkey value with this name, with the operation and the value it left. Only for the blocks endpoint'")
+            .conflicts_with_all(&["verify", "replay"]))
+        .arg(Arg::from_usage("[follow] --follow 'Keeps reading the blocks endpoint of a url source, and prints each block as it is committed. Stop with Ctrl-C'"))
+        .arg(Arg::from_usage("[interval] --interval <SECONDS> 'How often --follow checks for new blocks. Defaults to 2'")
+            .validator(validate_seconds)
+            .requires("follow"))
+        .arg(Arg::from_usage("[user] --user <USER> 'Authenticates every request to a url source with HTTP basic authentication. Written as NAME or NAME:PASSWORD'"))
         .get_matches();
 
 
@@ -109,25 +138,411 @@ fn main() {
     // Safe to unwrap since these are required by clap
     let config = (matches.value_of("endpoint").unwrap(), matches.value_of("source").unwrap());
 
+    // Add a decoder for each protobuf message to the built-in ones
+    let registry = match build_decoder_registry(&matches) {
+        Ok(registry) => registry,
+        Err(error) => exit_with_error(error),
+    };
+
+    // Work out which decoder to use for each transaction family
+    let decoders = match build_decoder_selection(&matches, &registry) {
+        Ok(decoders) => decoders,
+        Err(error) => exit_with_error(error),
+    };
+
     // Do the same for options that will be passed to display
-    let options = (matches.is_present("full-addr"), matches.is_present("genesis"), String::from(matches.value_of("method").unwrap()));
+    let options = (matches.is_present("full-addr"), matches.is_present("genesis"), &decoders);
+
+    // Work out which transactions to show, which only applies to the /transactions endpoint
+    let filter = build_transaction_filter(&matches);
+    if !filter.is_empty() && config.0 != "transactions" {
+        exit_with_error(Error::Config { reason: String::from("--family-name, --signer and --batcher only apply to the transactions endpoint") });
+    }
+
+    // Work out which batches or transactions to look up. The /batch_statuses and /receipts endpoints take a list of IDs,
+    // the other endpoints look up a single entity
+    let ids: Vec<&str> = matches.values_of("id").into_iter().flatten().collect();
+    match (config, ids.len()) {
+        (("batch_statuses", "url"), 0) | (("receipts", "url"), 0) =>
+            exit_with_error(Error::Config { reason: format!("--id is required to read the {} endpoint from a url", config.0) }),
+        (("state", _), 1) if !is_address(ids[0]) =>
+            exit_with_error(Error::Config { reason: format!("`{}` isn't a state address, which is 70 hex characters long", ids[0]) }),
+        (("batch_statuses", _), _) | (("receipts", _), _) | (_, 0) | (_, 1) => (),
+        _ => exit_with_error(Error::Config { reason: format!("only one --id can be looked up on the {} endpoint", config.0) }),
+    }
 
     let loc = matches.value_of("location").unwrap();
 
+    // Verifying prints a report about the blocks instead of the blocks themselves
+    let verify = matches.is_present("verify");
+    if verify {
+        if config.0 != "blocks" {
+            exit_with_error(Error::Config { reason: String::from("--verify only applies to the blocks endpoint") });
+        }
+        if !["text", "json"].contains(&matches.value_of("output").unwrap()) {
+            exit_with_error(Error::Config { reason: String::from("--verify can only print text or json") });
+        }
+    }
+
+    // Replaying prints how the state rebuilt from the blocks compares with the state read from the --replay location
+    let replay = matches.value_of("replay");
+    if replay.is_some() {
+        if config.0 != "blocks" || !ids.is_empty() {
+            exit_with_error(Error::Config { reason: String::from("--replay only applies to the blocks endpoint without --id") });
+        }
+        if !["text", "json"].contains(&matches.value_of("output").unwrap()) {
+            exit_with_error(Error::Config { reason: String::from("--replay can only print text or json") });
+        }
+    }
+
+    // The history of a key lists the transactions that touched it instead of the blocks
+    let history = matches.value_of("history");
+    if history.is_some() {
+        if config.0 != "blocks" || !ids.is_empty() {
+            exit_with_error(Error::Config { reason: String::from("--history only applies to the blocks endpoint without --id") });
+        }
+        if !["text", "json"].contains(&matches.value_of("output").unwrap()) {
+            exit_with_error(Error::Config { reason: String::from("--history can only print text or json") });
+        }
+    }
+
+    // Following the chain reads new blocks as they come, rather than a part of a list
+    let follow = matches.is_present("follow");
+    if follow {
+        if config != ("blocks", "url") || !ids.is_empty() || verify || replay.is_some() || history.is_some() {
+            exit_with_error(Error::Config { reason: String::from("--follow only applies to the blocks endpoint read from a url without --id, --verify, --replay or --history") });
+        }
+        if matches.value_of("output") != Some("text") {
+            exit_with_error(Error::Config { reason: String::from("--follow can only print text") });
+        }
+        if let Some(&(arg, _)) = LIST_ARGS.iter().find(|&&(arg, _)| matches.is_present(arg)) {
+            exit_with_error(Error::Config { reason: format!("--{} can't be used with --follow", arg) });
+        }
+    }
+
+    // Work out which pages to read and which query parameters to pass, which only applies to lists read from a url
+    let is_list_url = config.1 == "url" && ids.is_empty();
+    if let Some(&(arg, endpoints)) = LIST_ARGS.iter()
+        .find(|&&(arg, endpoints)| matches.is_present(arg) && !(is_list_url && endpoints.contains(&config.0))) {
+        exit_with_error(Error::Config { reason: format!("--{} only applies to lists read from a url without --id, from these endpoints: {}", arg, endpoints.join(", ")) });
+    }
+    let query = build_list_query(&matches);
+
+    // Connect to the REST API, which only applies to a url source
+    if config.1 != "url" {
+        if let Some(arg) = ["timeout", "retries", "header", "user"].iter().find(|&&arg| matches.is_present(arg)) {
+            exit_with_error(Error::Config { reason: format!("--{} only applies to a url source", arg) });
+        }
+    }
+    let client = build_client(&matches, loc, config.0);
+
+    if follow {
+        // Safe to unwrap since the count was checked by the clap validator
+        let interval = matches.value_of("interval").map_or(2, |interval| interval.parse().unwrap());
+        follow_blocks(client, Duration::from_secs(interval), options, &registry, matches.is_present("no-color"));
+    }
+
     // Read in the data to an enum.
-    let data: DataTypes = match config {
-        ("state", "file") => DataTypes::State(read_state_data_from_file(loc)),
-        ("state", "url") => DataTypes::State(read_state_data_from_endpoint(loc)),
-        ("blocks", "file") => DataTypes::Blocks(read_block_data_from_file(loc)),
-        ("blocks", "url") => DataTypes::Blocks(read_block_data_from_endpoint(loc)),
-        _ => panic!("This should be unreachable")
+    // A single entity is read into the same structure as a list, so it's displayed the same way.
+    // With a file source the file holds the saved response, and the --id only names the state address
+    let read_result = match (config, ids.first()) {
+        (("state", "file"), None) => read_state_data_from_file(loc).map(DataTypes::State),
+        (("state", "url"), None) => client.get_state(&query).map(DataTypes::State),
+        (("state", "file"), Some(address)) => read_state_address_from_file(loc).map(|state| DataTypes::State(state.into_state_data(address))),
+        (("state", "url"), Some(address)) => client.get_state_address(address).map(|state| DataTypes::State(state.into_state_data(address))),
+        (("blocks", "file"), None) => read_block_data_from_file(loc).map(DataTypes::Blocks),
+        (("blocks", "url"), None) => client.get_blocks(&query).map(DataTypes::Blocks),
+        (("blocks", "file"), Some(id)) => read_block_from_file(loc)
+            .and_then(|block| check_file_id(loc, "block", id, block.get_block().get_header_signature()).map(|_| DataTypes::Blocks(block.into()))),
+        (("blocks", "url"), Some(id)) => client.get_block(id).map(|block| DataTypes::Blocks(block.into())),
+        (("batches", "file"), None) => read_batch_list_from_file(loc).map(DataTypes::Batches),
+        (("batches", "url"), None) => client.get_batches(&query).map(DataTypes::Batches),
+        (("batches", "file"), Some(id)) => read_batch_from_file(loc)
+            .and_then(|batch| check_file_id(loc, "batch", id, batch.get_batch().get_header_signature()).map(|_| DataTypes::Batches(batch.into()))),
+        (("batches", "url"), Some(id)) => client.get_batch(id).map(|batch| DataTypes::Batches(batch.into())),
+        (("transactions", "file"), None) => read_transaction_list_from_file(loc).map(DataTypes::Transactions),
+        (("transactions", "url"), None) => client.get_transactions(&query).map(DataTypes::Transactions),
+        (("transactions", "file"), Some(id)) => read_transaction_from_file(loc)
+            .and_then(|txn| check_file_id(loc, "transaction", id, txn.get_transaction().get_header_signature()).map(|_| DataTypes::Transactions(txn.into()))),
+        (("transactions", "url"), Some(id)) => client.get_transaction(id).map(|txn| DataTypes::Transactions(txn.into())),
+        (("batch_statuses", "file"), _) => read_batch_statuses_from_file(loc).map(DataTypes::BatchStatuses),
+        (("batch_statuses", "url"), _) => client.get_batch_statuses(&ids).map(DataTypes::BatchStatuses),
+        (("receipts", "file"), _) => read_receipts_from_file(loc).map(DataTypes::Receipts),
+        (("receipts", "url"), _) => client.get_receipts(&ids).map(DataTypes::Receipts),
+        _ => unreachable!()
     };
 
-    // Print out the data, selecting the correct method based on the no-color flag
-    match (data, matches.is_present("no-color")) {
-        (DataTypes::Blocks(block), true) => block.display_full_data_no_color(options),
-        (DataTypes::Blocks(block), false) => block.display_full_data(options),
-        (DataTypes::State(state), true) => state.display_full_data_no_color(options),
-        (DataTypes::State(state), false) => state.display_full_data(options),
+    let data = match read_result {
+        Ok(DataTypes::Transactions(mut txns)) => {
+            txns.retain_matching(&filter);
+            DataTypes::Transactions(txns)
+        },
+        // A file can hold more than the IDs that were asked for
+        Ok(DataTypes::BatchStatuses(mut statuses)) => {
+            statuses.retain_ids(&ids);
+            DataTypes::BatchStatuses(statuses)
+        },
+        Ok(DataTypes::Receipts(mut receipts)) => {
+            receipts.retain_ids(&ids);
+            DataTypes::Receipts(receipts)
+        },
+        Ok(data) => data,
+        Err(error) => exit_with_error(error),
+    };
+
+    if let DataTypes::Blocks(ref blocks) = data {
+        if verify {
+            let verification = verify_blocks(blocks);
+            match (matches.value_of("output").unwrap(), matches.is_present("no-color")) {
+                ("json", _) => println!("{}", verification.to_json()),
+                (_, true) => verification.display_no_color(options.0),
+                (_, false) => verification.display(options.0),
+            }
+            match verification.get_violations().len() {
+                0 => process::exit(0),
+                problems => exit_with_error(Error::Verification { problems }),
+            }
+        }
+        if let Some(state_loc) = replay {
+            let replayed = replay_intkey(blocks);
+            // The state is read as of the newest block, so later blocks don't show up as differences
+            let state = match config.1 {
+                "file" => read_state_data_from_file(state_loc),
+                _ => {
+                    let mut state_query = ListQuery::default();
+                    state_query.set_address(INTKEY_NAMESPACE);
+                    if let Some(head) = replayed.get_head() {
+                        state_query.set_head(head);
+                    }
+                    build_client(&matches, state_loc, "state").get_state(&state_query)
+                },
+            };
+            let report = replayed.compare(&state.unwrap_or_else(|error| exit_with_error(error)));
+            match (matches.value_of("output").unwrap(), matches.is_present("no-color")) {
+                ("json", _) => println!("{}", report.to_json()),
+                (_, true) => report.display_no_color(options.0),
+                (_, false) => report.display(options.0),
+            }
+            match report.get_differences().len() {
+                0 => process::exit(0),
+                differences => exit_with_error(Error::Divergence { differences }),
+            }
+        }
+        if let Some(key) = history {
+            let history = key_history(blocks, key, (options.1, options.2), &registry);
+            match (matches.value_of("output").unwrap(), matches.is_present("no-color")) {
+                ("json", _) => println!("{}", history.to_json()),
+                (_, true) => history.display_no_color(options.0),
+                (_, false) => history.display(options.0),
+            }
+            process::exit(0);
+        }
+    }
+
+    // Print out the data, selecting the correct method based on the output format and no-color flag.
+    // Safe to unwrap since output has a default value
+    match (data, matches.value_of("output").unwrap(), matches.is_present("no-color")) {
+        (DataTypes::Blocks(block), "json", _) => println!("{}", block.to_json(options, &registry)),
+        (DataTypes::State(state), "json", _) => println!("{}", state.to_json(options, &registry)),
+        (DataTypes::Batches(batches), "json", _) => println!("{}", batches.to_json(options, &registry)),
+        (DataTypes::Transactions(txns), "json", _) => println!("{}", txns.to_json(options, &registry)),
+        (DataTypes::BatchStatuses(statuses), "json", _) => println!("{}", statuses.to_json()),
+        (DataTypes::Receipts(receipts), "json", _) => println!("{}", receipts.to_json(options.2, &registry)),
+        (DataTypes::BatchStatuses(_), "html", _) | (DataTypes::BatchStatuses(_), "tui", _) | (DataTypes::BatchStatuses(_), "dot", _) |
+        (DataTypes::Receipts(_), "html", _) | (DataTypes::Receipts(_), "tui", _) | (DataTypes::Receipts(_), "dot", _) =>
+            exit_with_error(Error::Config { reason: String::from("the batch_statuses and receipts endpoints can only be printed as text or json") }),
+        (DataTypes::Blocks(block), "html", _) => print!("{}", block.to_html(options, &registry)),
+        (DataTypes::State(state), "html", _) => print!("{}", state.to_html(options, &registry)),
+        (DataTypes::Batches(batches), "html", _) => print!("{}", batches.to_html(options, &registry)),
+        (DataTypes::Transactions(txns), "html", _) => print!("{}", txns.to_html(options, &registry)),
+        (DataTypes::Blocks(block), "tui", _) => if let Err(error) = block.to_browser(options, &registry).run() { exit_with_error(error) },
+        (DataTypes::State(state), "tui", _) => if let Err(error) = state.to_browser(options, &registry).run() { exit_with_error(error) },
+        (DataTypes::Batches(batches), "tui", _) => if let Err(error) = batches.to_browser(options, &registry).run() { exit_with_error(error) },
+        (DataTypes::Transactions(txns), "tui", _) => if let Err(error) = txns.to_browser(options, &registry).run() { exit_with_error(error) },
+        (DataTypes::Blocks(block), "dot", _) => print!("{}", block.to_dot(options)),
+        (DataTypes::State(_), "dot", _) | (DataTypes::Batches(_), "dot", _) | (DataTypes::Transactions(_), "dot", _) =>
+            exit_with_error(Error::Config { reason: String::from("dot output is only available for the blocks endpoint") }),
+        (DataTypes::Blocks(block), _, true) => block.display_full_data_no_color(options, &registry),
+        (DataTypes::Blocks(block), _, false) => block.display_full_data(options, &registry),
+        (DataTypes::State(state), _, true) => state.display_full_data_no_color(options, &registry),
+        (DataTypes::State(state), _, false) => state.display_full_data(options, &registry),
+        (DataTypes::Batches(batches), _, true) => batches.display_full_data_no_color(options, &registry),
+        (DataTypes::Batches(batches), _, false) => batches.display_full_data(options, &registry),
+        (DataTypes::Transactions(txns), _, true) => txns.display_full_data_no_color(options, &registry),
+        (DataTypes::Transactions(txns), _, false) => txns.display_full_data(options, &registry),
+        (DataTypes::BatchStatuses(statuses), _, true) => statuses.display_full_data_no_color(options.0),
+        (DataTypes::BatchStatuses(statuses), _, false) => statuses.display_full_data(options.0),
+        (DataTypes::Receipts(receipts), _, true) => receipts.display_full_data_no_color((options.0, options.2), &registry),
+        (DataTypes::Receipts(receipts), _, false) => receipts.display_full_data((options.0, options.2), &registry),
+    }
+}
+
+/// Builds the registry of the built-in decoders, and a [ProtobufDecoder](../rusty_saw_view/protobuf_schema/struct.ProtobufDecoder.html)
+/// for each `--message`. Returns an error if the `--proto` schema can't be read or doesn't define a message.
+fn build_decoder_registry(matches: &ArgMatches) -> Result<DecoderRegistry, Error> {
+    let mut registry = DecoderRegistry::default();
+
+    if let Some(paths) = matches.values_of("proto") {
+        let pool = read_descriptor_pool(&paths.collect::<Vec<_>>())?;
+        for message in matches.values_of("message").into_iter().flatten() {
+            registry.register(ProtobufDecoder::new(&pool, message)?);
+        }
+    }
+    Ok(registry)
+}
+
+/// Builds the decoder selection from the `<method>` arg, the well-known families and namespaces, the config file, and the
+/// `--family` and `--namespace` rules, in that order of precedence from lowest to highest. Returns an error if the config file can't be read, or if a rule names
+/// a decoder that isn't in `registry`.
+fn build_decoder_selection(matches: &ArgMatches, registry: &DecoderRegistry) -> Result<DecoderSelection, Error> {
+    let mut decoders = DecoderSelection::new(matches.value_of("method").unwrap());
+    decoders.add_well_known_family_rules(registry);
+    decoders.add_well_known_namespace_rules(registry);
+
+    // Each source is its own group, so a rule passed on the command line beats any rule of the config file
+    decoders.start_rule_group();
+    if let Some(path) = matches.value_of("config") {
+        read_config_from_file(path)?.apply_to(&mut decoders);
+    }
+    decoders.start_rule_group();
+
+    // Safe to unwrap since the rules were checked by the clap validator
+    for rule in matches.values_of("family").into_iter().flatten() {
+        decoders.add_family_rule(rule.parse().unwrap());
+    }
+    for rule in matches.values_of("namespace").into_iter().flatten() {
+        decoders.add_namespace_rule(rule.parse().unwrap());
+    }
+
+    match decoders.decoder_names().into_iter().find(|name| registry.get(name).is_none()) {
+        Some(name) => Err(Error::Config { reason: format!("no decoder is registered for `{}`, expected one of: {}", name, registry.names().join(", ")) }),
+        None => Ok(decoders),
+    }
+}
+
+/// Builds the filter for the `/transactions` endpoint from the `--family-name`, `--signer` and `--batcher` args.
+fn build_transaction_filter(matches: &ArgMatches) -> TransactionFilter {
+    let mut filter = TransactionFilter::default();
+    if let Some(family_name) = matches.value_of("family-name") {
+        filter.set_family_name(family_name);
+    }
+    if let Some(key) = matches.value_of("signer") {
+        filter.set_signer_public_key(key);
+    }
+    if let Some(key) = matches.value_of("batcher") {
+        filter.set_batcher_public_key(key);
+    }
+    filter
+}
+
+/// Builds the query of a list read from a url from the `--limit`, `--start`, `--head`, `--address`, `--count`,
+/// `--reverse` and `--pages` args.
+fn build_list_query(matches: &ArgMatches) -> ListQuery {
+    let mut query = ListQuery::default();
+    // Safe to unwrap since the counts were checked by the clap validator
+    if let Some(limit) = matches.value_of("limit") {
+        query.set_limit(limit.parse().unwrap());
+    }
+    if let Some(start) = matches.value_of("start") {
+        query.set_start(start);
     }
-}
\ No newline at end of file
+    if let Some(head) = matches.value_of("head") {
+        query.set_head(head);
+    }
+    if let Some(prefix) = matches.value_of("address") {
+        query.set_address(prefix);
+    }
+    if let Some(count) = matches.value_of("count") {
+        query.set_count(count.parse().unwrap());
+    }
+    if matches.is_present("reverse") {
+        query.set_reverse();
+    }
+    if let Some(pages) = matches.value_of("pages") {
+        query.set_max_pages(pages.parse().unwrap());
+    }
+    query
+}
+
+/// Checks that the `entity` saved in the file at `location` has the `id` it's looked up by, since a file source can't
+/// look it up.
+fn check_file_id(location: &str, entity: &str, id: &str, found: &str) -> Result<(), Error> {
+    match found.eq_ignore_ascii_case(id) {
+        true => Ok(()),
+        false => Err(Error::Config { reason: format!("{} holds the {} {}, not the one with the --id {}", location, entity, found, id) }),
+    }
+}
+
+/// Builds the client for the REST API at `location`, with the `--timeout`, `--retries`, `--header` and `--user` args.
+/// The location may be the URL of the `endpoint` itself, as it had to be before, in which case the endpoint is
+/// removed to leave the base URL.
+fn build_client(matches: &ArgMatches, location: &str, endpoint: &str) -> SawtoothClient {
+    let location = location.trim_end_matches('/');
+    let base_url = location.strip_suffix(endpoint)
+        .and_then(|base_url| base_url.strip_suffix('/'))
+        .unwrap_or(location);
+    let mut client = SawtoothClient::new(base_url);
+
+    // Safe to unwrap since the args were checked by the clap validators
+    if let Some(timeout) = matches.value_of("timeout") {
+        client.set_timeout(Duration::from_secs(timeout.parse().unwrap()));
+    }
+    if let Some(retries) = matches.value_of("retries") {
+        client.set_retries(retries.parse().unwrap());
+    }
+    for header in matches.values_of("header").into_iter().flatten() {
+        let mut parts = header.splitn(2, ':');
+        client.add_header(parts.next().unwrap().trim(), parts.next().unwrap().trim());
+    }
+    if let Some(user) = matches.value_of("user") {
+        let mut parts = user.splitn(2, ':');
+        client.set_basic_auth(parts.next().unwrap(), parts.next());
+    }
+    client
+}
+
+/// Checks that a `--limit`, `--pages` or `--count` arg is a count of at least 1.
+fn validate_count(count: String) -> Result<(), String> {
+    match count.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(()),
+        _ => Err(format!("`{}` isn't a count of at least 1", count)),
+    }
+}
+
+/// Checks that a `--timeout` or `--interval` arg is a whole number of seconds, of at least 1.
+fn validate_seconds(seconds: String) -> Result<(), String> {
+    match seconds.parse::<u64>() {
+        Ok(seconds) if seconds > 0 => Ok(()),
+        _ => Err(format!("`{}` isn't a number of seconds of at least 1", seconds)),
+    }
+}
+
+/// Prints the block at the head of the chain, and then every block that is committed after it, checking for new blocks
+/// every `interval`. A poll that fails for a reason that may go away, like the node restarting, is reported to stderr
+/// and the node is polled again. Only returns by exiting with any other error.
+fn follow_blocks(client: SawtoothClient, interval: Duration, options: (bool, bool, &DecoderSelection), registry: &DecoderRegistry, no_color: bool) -> ! {
+    let mut follower = BlockFollower::new(client);
+    loop {
+        match follower.poll() {
+            Ok(blocks) if no_color => blocks.display_full_data_no_color(options, registry),
+            Ok(blocks) => blocks.display_full_data(options, registry),
+            Err(ref error) if error.is_transient() => eprintln!("warning: {}", error),
+            Err(error) => exit_with_error(error),
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Checks that a `--header` arg is written as `NAME:VALUE`, with a name.
+fn validate_header(header: String) -> Result<(), String> {
+    match header.find(':') {
+        Some(colon) if !header[..colon].trim().is_empty() => Ok(()),
+        _ => Err(format!("`{}` isn't a header, written as NAME:VALUE", header)),
+    }
+}
+
+/// Prints `error` to stderr and exits with the code that corresponds to it.
+fn exit_with_error(error: Error) -> ! {
+    eprintln!("error: {}", error);
+    process::exit(error.exit_code());
+}
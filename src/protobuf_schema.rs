@@ -0,0 +1,273 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `protobuf_schema` decodes payloads with any Protocol Buffers message, using a schema that is loaded at run time
+//! instead of code that is compiled into the crate.
+//!
+//! The schema is read with [read_descriptor_pool](fn.read_descriptor_pool.html) from compiled `FileDescriptorSet` files,
+//! as written by `protoc --descriptor_set_out`, or from `.proto` files directly. A [ProtobufDecoder](struct.ProtobufDecoder.html)
+//! is then created for each message that payloads are serialized with, and registered into a
+//! [DecoderRegistry](../json_deserialize/struct.DecoderRegistry.html) like any other decoder. Each decoder is named after
+//! the full name of its message, e.g. `xo.XoPayload`.
+//!
+//! Decoded messages become objects with one entry per field. Enums are shown by the name of their value,
+//! nested messages become nested objects, and fields of a `oneof` are only shown if they are set.
+
+extern crate prost_reflect;
+extern crate protox;
+
+use decoded_value::{DecodedValue};
+use error::{DecodeError, Error, Result};
+use json_deserialize::{PayloadDecoder};
+use json_reader::{read_bytes_from_file};
+
+use self::prost_reflect::{DescriptorPool, DynamicMessage, Kind, MapKey, MessageDescriptor, ReflectMessage};
+use self::protox::{Compiler};
+
+use std::path::Path;
+
+/// Returns a pool with the messages defined in each of the files at `paths`.
+///
+/// Files ending in `.proto` are parsed as protobuf source, with imports resolved relative to the
+/// directory of the file. Any other file is read as a compiled `FileDescriptorSet`.
+///
+/// # Errors
+/// Returns an error if a file can't be read, or if it isn't a valid schema.
+pub fn read_descriptor_pool(paths: &[&str]) -> Result<DescriptorPool> {
+    let mut pool = DescriptorPool::new();
+    for path in paths {
+        let schema_error = |reason: String| Error::ProtoSchema { path: String::from(*path), reason };
+
+        let descriptor_set = if path.ends_with(".proto") {
+            if !Path::new(path).exists() {
+                return Err(Error::FileNotFound { path: String::from(*path) });
+            }
+            compile_proto(path).map_err(|error| schema_error(error.to_string()))?
+        } else {
+            read_bytes_from_file(path)?
+        };
+
+        pool.decode_file_descriptor_set(descriptor_set.as_slice())
+            .map_err(|error| schema_error(error.to_string()))?;
+    }
+    Ok(pool)
+}
+
+/// Returns the encoded `FileDescriptorSet` of the `.proto` file at `path`, along with the files it imports.
+fn compile_proto(path: &str) -> ::std::result::Result<Vec<u8>, protox::Error> {
+    let include = match Path::new(path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut compiler = Compiler::new([include])?;
+    compiler.include_imports(true);
+    compiler.open_file(path)?;
+    Ok(compiler.encode_file_descriptor_set())
+}
+
+/// Decodes payloads that were serialized as one protobuf message of a schema loaded at run time.
+#[derive(Debug, Clone)]
+pub struct ProtobufDecoder {
+    message: MessageDescriptor,
+}
+
+impl ProtobufDecoder {
+    /// Returns a decoder for the message with the full name `message_name`, e.g. `xo.XoPayload`.
+    ///
+    /// # Errors
+    /// Returns an error if there is no message named `message_name` in `pool`.
+    pub fn new(pool: &DescriptorPool, message_name: &str) -> Result<ProtobufDecoder> {
+        match pool.get_message_by_name(message_name) {
+            Some(message) => Ok(ProtobufDecoder { message }),
+            None => Err(Error::Config { reason: format!("no protobuf message named `{}` in the schema", message_name) }),
+        }
+    }
+}
+
+impl PayloadDecoder for ProtobufDecoder {
+    fn name(&self) -> &str {
+        self.message.full_name()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> ::std::result::Result<DecodedValue, DecodeError> {
+        let message = DynamicMessage::decode(self.message.clone(), bytes)
+            .map_err(|error| DecodeError::Deserialize { method: String::from(self.name()), reason: error.to_string() })?;
+        Ok(message_to_value(&message))
+    }
+}
+
+/// Converts a decoded message into a map with one entry per field, in the order the fields are declared.
+fn message_to_value(message: &DynamicMessage) -> DecodedValue {
+    DecodedValue::Map(message.descriptor().fields()
+        .filter(|field| field.containing_oneof().is_none() || message.has_field(field))
+        .map(|field| (DecodedValue::from(field.name()), field_to_value(&message.get_field(&field), &field.kind())))
+        .collect())
+}
+
+/// Converts the value of a field into a [DecodedValue](../decoded_value/enum.DecodedValue.html). `kind` is the
+/// type of the field, which is needed to look up the names of enum values.
+fn field_to_value(val: &prost_reflect::Value, kind: &Kind) -> DecodedValue {
+    match *val {
+        prost_reflect::Value::Bool(b) => DecodedValue::Bool(b),
+        prost_reflect::Value::I32(n) => DecodedValue::Int(i64::from(n)),
+        prost_reflect::Value::I64(n) => DecodedValue::Int(n),
+        prost_reflect::Value::U32(n) => DecodedValue::UInt(u64::from(n)),
+        prost_reflect::Value::U64(n) => DecodedValue::UInt(n),
+        prost_reflect::Value::F32(n) => DecodedValue::Float(f64::from(n)),
+        prost_reflect::Value::F64(n) => DecodedValue::Float(n),
+        prost_reflect::Value::String(ref s) => DecodedValue::Text(s.clone()),
+        prost_reflect::Value::Bytes(ref bytes) => DecodedValue::Bytes(bytes.to_vec()),
+        prost_reflect::Value::EnumNumber(n) => match *kind {
+            Kind::Enum(ref enum_type) => enum_type.get_value(n)
+                .map_or_else(|| DecodedValue::Int(i64::from(n)), |enum_value| DecodedValue::from(enum_value.name())),
+            _ => DecodedValue::Int(i64::from(n)),
+        },
+        prost_reflect::Value::Message(ref message) => message_to_value(message),
+        prost_reflect::Value::List(ref vals) => DecodedValue::Array(vals.iter().map(|val| field_to_value(val, kind)).collect()),
+        prost_reflect::Value::Map(ref entries) => {
+            let value_kind = match *kind {
+                Kind::Message(ref entry) => entry.map_entry_value_field().kind(),
+                ref kind => kind.clone(),
+            };
+            // Protobuf maps are unordered, so sort them by key to keep the output stable
+            let mut entries: Vec<_> = entries.iter().collect();
+            entries.sort_by_key(|&(key, _)| key);
+            DecodedValue::Map(entries.into_iter()
+                .map(|(key, val)| (map_key_to_value(key), field_to_value(val, &value_kind)))
+                .collect())
+        },
+    }
+}
+
+/// Converts the key of a map field into a [DecodedValue](../decoded_value/enum.DecodedValue.html).
+fn map_key_to_value(key: &MapKey) -> DecodedValue {
+    match *key {
+        MapKey::Bool(b) => DecodedValue::Bool(b),
+        MapKey::I32(n) => DecodedValue::Int(i64::from(n)),
+        MapKey::I64(n) => DecodedValue::Int(n),
+        MapKey::U32(n) => DecodedValue::UInt(u64::from(n)),
+        MapKey::U64(n) => DecodedValue::UInt(n),
+        MapKey::String(ref s) => DecodedValue::Text(s.clone()),
+    }
+}
+
+#[cfg(test)]
+mod test_protobuf_decoder {
+    use super::*;
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// A temporary directory for the schema files of one test, which is removed when it's dropped.
+    struct SchemaDir {
+        dir: PathBuf,
+    }
+
+    impl SchemaDir {
+        fn new(test_name: &str) -> SchemaDir {
+            let dir = ::std::env::temp_dir().join(format!("rusty_saw_view_{}_{}", ::std::process::id(), test_name));
+            fs::create_dir_all(&dir).unwrap();
+            SchemaDir { dir }
+        }
+
+        /// Writes `source` to a file in the directory, and returns the path to it.
+        fn write(&self, file_name: &str, source: &[u8]) -> String {
+            let path = self.dir.join(file_name);
+            fs::write(&path, source).unwrap();
+            path.to_string_lossy().into_owned()
+        }
+    }
+
+    impl Drop for SchemaDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    const XO_PROTO: &str = "syntax = \"proto3\";\n\
+        package xo;\n\
+        message XoPayload {\n\
+            enum Action { UNSET = 0; CREATE = 1; TAKE = 2; }\n\
+            string name = 1;\n\
+            Action action = 2;\n\
+            uint32 space = 3;\n\
+            repeated string tags = 4;\n\
+            map<string, uint64> scores = 5;\n\
+            oneof extra { string note = 6; bytes blob = 7; }\n\
+        }\n";
+
+    #[test]
+    fn decodes_message_from_proto_file() {
+        let dir = SchemaDir::new("from_proto_file");
+        let path = dir.write("xo.proto", XO_PROTO.as_bytes());
+        let pool = read_descriptor_pool(&[path.as_str()]).unwrap();
+        let decoder = ProtobufDecoder::new(&pool, "xo.XoPayload").unwrap();
+        assert_eq!("xo.XoPayload", decoder.name());
+
+        // name: "game", action: TAKE, space: 4, tags: ["a"], scores: {"bob": 3}, blob: [1, 2]
+        let bytes = [0x0a, 0x04, b'g', b'a', b'm', b'e', 0x10, 0x02, 0x18, 0x04, 0x22, 0x01, b'a',
+            0x2a, 0x07, 0x0a, 0x03, b'b', b'o', b'b', 0x10, 0x03, 0x3a, 0x02, 0x01, 0x02];
+        let val = decoder.decode(&bytes).unwrap();
+        assert_eq!(Some("game"), val.get("name").and_then(DecodedValue::as_text));
+        assert_eq!(Some("TAKE"), val.get("action").and_then(DecodedValue::as_text));
+        assert_eq!(Some(&DecodedValue::UInt(4)), val.get("space"));
+        assert_eq!(Some(&DecodedValue::Array(vec![DecodedValue::from("a")])), val.get("tags"));
+        assert_eq!(Some(&DecodedValue::UInt(3)), val.get("scores").and_then(|scores| scores.get("bob")));
+        assert_eq!(Some(&DecodedValue::Bytes(vec![1, 2])), val.get("blob"));
+        assert!(val.get("note").is_none());
+    }
+
+    #[test]
+    fn decodes_message_from_descriptor_set() {
+        let dir = SchemaDir::new("from_descriptor_set");
+        let path = dir.write("xo.proto", XO_PROTO.as_bytes());
+        let set_path = dir.write("xo.pb", &compile_proto(&path).unwrap());
+
+        let pool = read_descriptor_pool(&[set_path.as_str()]).unwrap();
+        let val = ProtobufDecoder::new(&pool, "xo.XoPayload").unwrap().decode(&[0x0a, 0x01, b'x']).unwrap();
+        assert_eq!(Some("x"), val.get("name").and_then(DecodedValue::as_text));
+        assert_eq!(Some("UNSET"), val.get("action").and_then(DecodedValue::as_text));
+    }
+
+    #[test]
+    fn unknown_message() {
+        let dir = SchemaDir::new("unknown_message");
+        let path = dir.write("xo.proto", XO_PROTO.as_bytes());
+        let pool = read_descriptor_pool(&[path.as_str()]).unwrap();
+        match ProtobufDecoder::new(&pool, "xo.Missing") {
+            Err(Error::Config { .. }) => (),
+            other => panic!("Expected Config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_schema() {
+        let dir = SchemaDir::new("invalid_schema");
+        let path = dir.write("broken.proto", b"message {");
+        match read_descriptor_pool(&[path.as_str()]) {
+            Err(Error::ProtoSchema { .. }) => (),
+            other => panic!("Expected ProtoSchema error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn missing_schema_file() {
+        match read_descriptor_pool(&["does_not_exist.pb"]) {
+            Err(Error::FileNotFound { .. }) => (),
+            other => panic!("Expected FileNotFound error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_payload() {
+        let dir = SchemaDir::new("invalid_payload");
+        let path = dir.write("xo.proto", XO_PROTO.as_bytes());
+        let pool = read_descriptor_pool(&[path.as_str()]).unwrap();
+        match ProtobufDecoder::new(&pool, "xo.XoPayload").unwrap().decode(&[0x0a, 0x05, b'x']) {
+            Err(DecodeError::Deserialize { method, .. }) => assert_eq!("xo.XoPayload", method),
+            other => panic!("Expected Deserialize error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,494 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `replay` rebuilds the state of the `intkey` transaction family by replaying the transactions of a
+//! [BlockData](../json_structs/json_blocks/struct.BlockData.html), and compares it with the state read from the
+//! `/state` endpoint.
+//!
+//! [replay_intkey](fn.replay_intkey.html) applies every `intkey` transaction from the oldest block to the newest, the
+//! same way the intkey transaction processor does. Each name is stored at the address `1cf126` followed by the last
+//! 64 hex characters of the SHA-512 hash of the name, as a CBOR map of names to values. A transaction the transaction
+//! processor would have rejected, such as incrementing a name that isn't set, is skipped and reported.
+//!
+//! [IntkeyState::compare](struct.IntkeyState.html#method.compare) then lists every name whose replayed value differs
+//! from a [StateData](../json_structs/json_state/struct.StateData.html) snapshot. The blocks must reach back to the
+//! genesis block for the replay to give the whole state.
+//!
+//! ```
+//! use rusty_saw_view::json_reader::{read_block_data_from_file, read_state_data_from_file};
+//! use rusty_saw_view::replay::{replay_intkey};
+//!
+//! let blocks = read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+//! let state = read_state_data_from_file("example-blockchain/state.json").unwrap();
+//! let replayed = replay_intkey(&blocks);
+//! assert_eq!(Some(1), replayed.get_value("num1"));
+//! assert!(replayed.compare(&state).is_consistent());
+//! ```
+
+extern crate base64;
+extern crate serde_cbor;
+extern crate serde_json;
+
+use colored::*;
+use json_structs::{label_id, paint, sha512_hex};
+use json_structs::json_blocks::{Block, BlockData};
+use json_structs::json_state::{StateData};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The namespace of the `intkey` transaction family, which is the first 6 hex characters of the SHA-512 hash of `intkey`.
+pub const INTKEY_NAMESPACE: &str = "1cf126";
+
+/// The largest value the intkey transaction processor accepts, which is the largest 32 bit unsigned integer.
+const MAX_VALUE: u64 = 4_294_967_295;
+
+/// The longest name the intkey transaction processor accepts.
+const MAX_NAME_LENGTH: usize = 20;
+
+/// Returns the state address that the intkey transaction family stores `name` at.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_saw_view::replay::{intkey_address};
+///
+/// assert_eq!("1cf1266258bf86bf61f911799bbfee3c2796a89c255d59a7f3371f91876980d5da0e0c", intkey_address("num1"));
+/// ```
+pub fn intkey_address(name: &str) -> String {
+    let hash = sha512_hex(name.as_bytes());
+    format!("{}{}", INTKEY_NAMESPACE, &hash[hash.len() - 64..])
+}
+
+/// The payload of an intkey transaction, which is a CBOR map.
+#[derive(Deserialize, Debug)]
+struct IntkeyPayload {
+    #[serde(rename = "Verb")]
+    verb: String,
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Value")]
+    value: u64,
+}
+
+/// The intkey state rebuilt by replaying blocks, along with the transactions that were skipped.
+#[derive(Serialize, Debug, Default)]
+pub struct IntkeyState {
+    num_blocks: usize,
+    num_transactions: usize,
+    #[serde(skip)]
+    head: Option<String>,
+    state: BTreeMap<String, BTreeMap<String, u64>>,
+    skipped: Vec<SkippedTransaction>,
+}
+
+impl IntkeyState {
+    /// Returns the value of `name`, or `None` if it isn't set.
+    pub fn get_value(&self, name: &str) -> Option<u64> {
+        self.state.get(&intkey_address(name)).and_then(|values| values.get(name)).cloned()
+    }
+
+    /// Returns the ID of the newest block that was replayed, which is the head the state should be read at.
+    pub fn get_head(&self) -> Option<&str> {
+        self.head.as_deref()
+    }
+
+    /// Returns the transactions that the intkey transaction processor would have rejected, in the order they were replayed.
+    pub fn get_skipped(&self) -> &[SkippedTransaction] {
+        &self.skipped
+    }
+
+    /// Compares the replayed state with the intkey addresses of `state`, and returns every name whose value differs.
+    /// Addresses outside the intkey namespace are ignored.
+    pub fn compare(&self, state: &StateData) -> ReplayReport<'_> {
+        let mut found: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+        let mut differences = Vec::new();
+        for entry in state.get_states().iter().filter(|entry| entry.get_address_full().starts_with(INTKEY_NAMESPACE)) {
+            match decode_intkey_data(entry.get_data()) {
+                Ok(values) => { found.insert(entry.get_address_full(), values); },
+                Err(reason) => differences.push(Difference::UndecodableData { address: entry.get_address_full(), reason }),
+            }
+        }
+
+        // Addresses whose data can't be decoded are already reported, so they aren't compared name by name
+        let undecodable: BTreeSet<String> = differences.iter().map(|difference| String::from(difference.get_address())).collect();
+        let no_values = BTreeMap::new();
+        let addresses: BTreeSet<&String> = self.state.keys().chain(found.keys())
+            .filter(|address| !undecodable.contains(*address))
+            .collect();
+        for address in addresses {
+            let replayed = self.state.get(address).unwrap_or(&no_values);
+            let stored = found.get(address).unwrap_or(&no_values);
+            let names: BTreeSet<&String> = replayed.keys().chain(stored.keys()).collect();
+            for name in names {
+                let (replayed, stored) = (replayed.get(name).cloned(), stored.get(name).cloned());
+                if replayed != stored {
+                    differences.push(Difference::Value { address: address.clone(), name: name.clone(), replayed, found: stored });
+                }
+            }
+        }
+
+        ReplayReport {
+            num_blocks: self.num_blocks,
+            num_transactions: self.num_transactions,
+            state: &self.state,
+            skipped: &self.skipped,
+            differences,
+        }
+    }
+
+    /// Applies the intkey transaction with the base64 `payload` and returns the change it made, or returns why the
+    /// transaction processor would reject it.
+    pub(crate) fn apply(&mut self, payload: &str) -> Result<IntkeyChange, String> {
+        let bytes = base64::decode(payload).map_err(|error| format!("the payload isn't valid base64: {}", error))?;
+        let payload: IntkeyPayload = serde_cbor::from_slice(&bytes).map_err(|error| format!("the payload isn't an intkey payload: {}", error))?;
+        if payload.name.is_empty() || payload.name.chars().count() > MAX_NAME_LENGTH {
+            return Err(format!("`{}` isn't between 1 and {} characters long", payload.name, MAX_NAME_LENGTH));
+        }
+        if payload.value > MAX_VALUE {
+            return Err(format!("{} is larger than {}", payload.value, MAX_VALUE));
+        }
+
+        let current = self.get_value(&payload.name);
+        let value = match (payload.verb.as_str(), current) {
+            ("set", None) => payload.value,
+            ("set", Some(_)) => return Err(format!("set of {}, which is already set", payload.name)),
+            ("inc", Some(current)) => current.checked_add(payload.value).filter(|&value| value <= MAX_VALUE)
+                .ok_or_else(|| format!("inc of {} would go over {}", payload.name, MAX_VALUE))?,
+            ("dec", Some(current)) => current.checked_sub(payload.value)
+                .ok_or_else(|| format!("dec of {} would go under 0", payload.name))?,
+            ("inc", None) | ("dec", None) => return Err(format!("{} of {}, which isn't set", payload.verb, payload.name)),
+            (verb, _) => return Err(format!("`{}` isn't an intkey verb", verb)),
+        };
+        self.state.entry(intkey_address(&payload.name)).or_default().insert(payload.name.clone(), value);
+        Ok(IntkeyChange { verb: payload.verb, name: payload.name, operand: payload.value, value })
+    }
+}
+
+/// Replays the intkey transactions of `data` from the oldest block to the newest, and returns the state they leave.
+/// Transactions of other families are ignored.
+pub fn replay_intkey(data: &BlockData) -> IntkeyState {
+    let blocks = oldest_first(data);
+    let mut state = IntkeyState { num_blocks: blocks.len(), ..IntkeyState::default() };
+    state.head = blocks.last().map(|block| String::from(block.get_header_signature()));
+    for block in blocks {
+        let txns = block.get_batches().iter()
+            .flat_map(|batch| batch.get_transactions())
+            .filter(|txn| txn.get_header().get_family_name() == "intkey");
+        for txn in txns {
+            match state.apply(txn.get_payload()) {
+                Ok(_) => state.num_transactions += 1,
+                Err(reason) => state.skipped.push(SkippedTransaction {
+                    block_num: String::from(block.get_header().get_block_num()),
+                    transaction_id: String::from(txn.get_header_signature()),
+                    reason,
+                }),
+            }
+        }
+    }
+    state
+}
+
+/// Returns the blocks of `data` from the oldest to the newest, the order their transactions were applied in.
+pub(crate) fn oldest_first(data: &BlockData) -> Vec<&Block> {
+    let mut blocks: Vec<&Block> = data.get_blocks().iter().collect();
+    // Blocks with a number that can't be read are replayed first, so they don't hide the rest of the chain
+    blocks.sort_by_key(|block| block.get_header().get_block_num().parse::<u64>().ok());
+    blocks
+}
+
+/// Returns the names and values stored in the base64 `data` of an intkey address.
+fn decode_intkey_data(data: &str) -> Result<BTreeMap<String, u64>, String> {
+    let bytes = base64::decode(data).map_err(|error| format!("invalid base64: {}", error))?;
+    serde_cbor::from_slice(&bytes).map_err(|error| format!("not a CBOR map of names to values: {}", error))
+}
+
+/// The change an intkey transaction made: `verb` applied to `name` with the `operand` of the payload, leaving `value`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IntkeyChange {
+    pub(crate) verb: String,
+    pub(crate) name: String,
+    pub(crate) operand: u64,
+    pub(crate) value: u64,
+}
+
+/// An intkey transaction that was skipped because the transaction processor would have rejected it.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SkippedTransaction {
+    block_num: String,
+    transaction_id: String,
+    reason: String,
+}
+
+impl SkippedTransaction {
+    /// Returns the number of the block the transaction is in.
+    pub fn get_block_num(&self) -> &str {
+        &self.block_num
+    }
+
+    /// Returns the ID of the transaction.
+    pub fn get_transaction_id(&self) -> &str {
+        &self.transaction_id
+    }
+
+    /// Returns why the transaction was skipped.
+    pub fn get_reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+/// A way that the replayed state differs from the state it was compared with.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Difference {
+    /// The value of `name` isn't the same. `replayed` is `None` if no transaction set the name, and `found` is `None`
+    /// if the state doesn't hold it.
+    Value { address: String, name: String, replayed: Option<u64>, found: Option<u64> },
+    /// The data at an intkey address of the state can't be decoded, so it can't be compared.
+    UndecodableData { address: String, reason: String },
+}
+
+impl Difference {
+    /// Returns the intkey address that differs.
+    pub fn get_address(&self) -> &str {
+        match *self {
+            Difference::Value { ref address, .. } | Difference::UndecodableData { ref address, .. } => address,
+        }
+    }
+
+    /// Returns a sentence that describes the difference.
+    fn describe(&self) -> String {
+        match *self {
+            Difference::Value { replayed: Some(replayed), found: Some(found), .. } =>
+                format!("Replayed {}, but the state holds {}", replayed, found),
+            Difference::Value { replayed: Some(replayed), found: None, .. } =>
+                format!("Replayed {}, but the state doesn't hold it", replayed),
+            Difference::Value { replayed: None, found: Some(found), .. } =>
+                format!("The state holds {}, but no transaction set it", found),
+            Difference::Value { replayed: None, found: None, .. } =>
+                String::from("Neither the replay nor the state hold it"),
+            Difference::UndecodableData { ref reason, .. } =>
+                format!("The data can't be compared, since it's {}", reason),
+        }
+    }
+}
+
+/// The result of comparing a replayed intkey state with a state snapshot.
+#[derive(Serialize, Debug)]
+pub struct ReplayReport<'a> {
+    num_blocks: usize,
+    num_transactions: usize,
+    state: &'a BTreeMap<String, BTreeMap<String, u64>>,
+    skipped: &'a [SkippedTransaction],
+    differences: Vec<Difference>,
+}
+
+impl<'a> ReplayReport<'a> {
+    /// Returns every difference that was found.
+    pub fn get_differences(&self) -> &[Difference] {
+        &self.differences
+    }
+
+    /// Returns true if the replayed state matches the state it was compared with.
+    pub fn is_consistent(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Display the replayed state, the skipped transactions and every difference, followed by a summary.
+    /// Uses colored text so use this for terminal printing.
+    ///
+    /// Setting `full_id` to false will only print the first 6 and last 4 characters of addresses and IDs, otherwise
+    /// the full string is shown.
+    pub fn display(&self, full_id: bool) {
+        print!("{}", self.format(full_id, true));
+    }
+
+    /// Display the replayed state, the skipped transactions and every difference, followed by a summary.
+    /// No terminal color, so use this for piping to a file.
+    pub fn display_no_color(&self, full_id: bool) {
+        print!("{}", self.format(full_id, false));
+    }
+
+    /// Returns the report as pretty printed JSON, with the number of blocks and transactions replayed, the replayed
+    /// `state` as a map of addresses to names and values, the `skipped` transactions and the `differences` found.
+    /// The `type` of each difference tells whether a `value` differs or the data is `undecodable_data`.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a replay report can always be written as JSON")
+    }
+
+    fn format(&self, full_id: bool, color: bool) -> String {
+        let mut out = String::from("|Replayed State\n");
+        for (address, values) in self.state.iter() {
+            for (name, value) in values.iter() {
+                out.push_str(&format!("| {} : {} at {}\n", name, value, label_id(address, full_id)));
+            }
+        }
+        for skipped in self.skipped.iter() {
+            out.push_str(&format!("|Block {}, Transaction {}\n", skipped.block_num, label_id(&skipped.transaction_id, full_id)));
+            out.push_str(&format!("| {}\n", paint(&format!("Skipped: {}", skipped.reason), Color::Yellow, color)));
+        }
+        for difference in self.differences.iter() {
+            match *difference {
+                Difference::Value { ref address, ref name, .. } => out.push_str(&format!("|{} ({})\n", name, label_id(address, full_id))),
+                Difference::UndecodableData { ref address, .. } => out.push_str(&format!("|Address {}\n", label_id(address, full_id))),
+            }
+            out.push_str(&format!("| {}\n", paint(&difference.describe(), Color::Red, color)));
+        }
+
+        let mut replayed = format!("Replayed {} intkey transactions from {} blocks", self.num_transactions, self.num_blocks);
+        if !self.skipped.is_empty() {
+            replayed.push_str(&format!(", skipping {}", self.skipped.len()));
+        }
+        match self.differences.len() {
+            0 => out.push_str(&format!("{}: {}\n", replayed, paint("the state matches", Color::Green, color))),
+            1 => out.push_str(&format!("{}: {}\n", replayed, paint("found 1 difference with the state", Color::Red, color))),
+            count => out.push_str(&format!("{}: {}\n", replayed, paint(&format!("found {} differences with the state", count), Color::Red, color))),
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test_replay {
+    use super::*;
+    use super::serde_json::Value;
+
+    use json_reader::{read_block_data_from_file, read_state_data_from_file};
+
+    /// Returns `value` as base64 encoded CBOR, the way intkey payloads and state are stored.
+    fn cbor_base64(value: &BTreeMap<&str, Value>) -> String {
+        base64::encode(&serde_cbor::to_vec(value).unwrap())
+    }
+
+    fn payload(verb: &str, name: &str, value: u64) -> String {
+        let mut payload = BTreeMap::new();
+        payload.insert("Verb", Value::from(verb));
+        payload.insert("Name", Value::from(name));
+        payload.insert("Value", Value::from(value));
+        cbor_base64(&payload)
+    }
+
+    /// Returns the example state as JSON, for tests to tamper with.
+    fn state_json() -> Value {
+        serde_json::from_str(&::std::fs::read_to_string("example-blockchain/state.json").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn example_matches_state() {
+        let blocks = read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let replayed = replay_intkey(&blocks);
+        assert_eq!(Some(1), replayed.get_value("num1"));
+        assert_eq!(Some(12), replayed.get_value("num2"));
+        assert_eq!(None, replayed.get_value("num3"));
+        assert!(replayed.get_skipped().is_empty());
+        assert_eq!(Some("0b5874608603e25ad48bc4436662ea1b2d66a480852790c85f2086441669ccb8406f3c8429a9283babe6671f8b9c5a842baa052e5c07e9e5958a2251cb2d4771"),
+            replayed.get_head());
+
+        let state = read_state_data_from_file("example-blockchain/state.json").unwrap();
+        let report = replayed.compare(&state);
+        assert!(report.is_consistent());
+        assert_eq!("|Replayed State\n| num1 : 1 at 1cf126...0e0c\n| num2 : 12 at 1cf126...fcab\n\
+            Replayed 5 intkey transactions from 6 blocks: the state matches\n", report.format(false, false));
+    }
+
+    #[test]
+    fn replays_from_oldest_block() {
+        let blocks = read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let mut json = serde_json::to_value(&blocks).unwrap();
+        json["data"].as_array_mut().unwrap().reverse();
+        assert_eq!(Some(1), replay_intkey(&serde_json::from_value(json).unwrap()).get_value("num1"));
+    }
+
+    #[test]
+    fn applies_verbs() {
+        let mut state = IntkeyState::default();
+        state.apply(&payload("set", "a", 5)).unwrap();
+        state.apply(&payload("inc", "a", 3)).unwrap();
+        state.apply(&payload("dec", "a", 6)).unwrap();
+        assert_eq!(Some(2), state.get_value("a"));
+        assert_eq!(Some(&2), state.state[&intkey_address("a")].get("a"));
+        assert_eq!(Ok(IntkeyChange { verb: String::from("inc"), name: String::from("a"), operand: 4, value: 6 }),
+            state.apply(&payload("inc", "a", 4)));
+    }
+
+    #[test]
+    fn rejects_what_the_transaction_processor_would() {
+        let mut state = IntkeyState::default();
+        state.apply(&payload("set", "a", MAX_VALUE)).unwrap();
+        assert_eq!(Err(String::from("set of a, which is already set")), state.apply(&payload("set", "a", 1)));
+        assert_eq!(Err(String::from("inc of a would go over 4294967295")), state.apply(&payload("inc", "a", 1)));
+        assert_eq!(Err(String::from("dec of b, which isn't set")), state.apply(&payload("dec", "b", 1)));
+        state.apply(&payload("set", "b", 1)).unwrap();
+        assert_eq!(Err(String::from("dec of b would go under 0")), state.apply(&payload("dec", "b", 2)));
+        assert_eq!(Err(String::from("4294967296 is larger than 4294967295")), state.apply(&payload("set", "c", MAX_VALUE + 1)));
+        assert_eq!(Err(String::from("`mul` isn't an intkey verb")), state.apply(&payload("mul", "a", 1)));
+        assert!(state.apply(&payload("set", "name longer than twenty", 1)).is_err());
+        assert!(state.apply("not base64!").is_err());
+        assert_eq!(Some(MAX_VALUE), state.get_value("a"));
+    }
+
+    #[test]
+    fn finds_differences() {
+        let blocks = read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let mut json = state_json();
+        let mut num1 = BTreeMap::new();
+        num1.insert("num1", Value::from(3));
+        json["data"][1]["data"] = Value::from(cbor_base64(&num1));
+        // num2 is missing from the state
+        json["data"].as_array_mut().unwrap().remove(2);
+        let state: StateData = serde_json::from_value(json).unwrap();
+
+        let replayed = replay_intkey(&blocks);
+        let report = replayed.compare(&state);
+        assert_eq!(2, report.get_differences().len());
+        assert_eq!(Difference::Value { address: intkey_address("num1"), name: String::from("num1"), replayed: Some(1), found: Some(3) },
+            report.get_differences()[0]);
+        let text = report.format(false, false);
+        assert!(text.contains("|num1 (1cf126...0e0c)\n| Replayed 1, but the state holds 3\n"));
+        assert!(text.contains("|num2 (1cf126...fcab)\n| Replayed 12, but the state doesn't hold it\n"));
+        assert!(text.ends_with(": found 2 differences with the state\n"));
+    }
+
+    #[test]
+    fn undecodable_state() {
+        let blocks = read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let mut json = state_json();
+        json["data"][1]["data"] = Value::from("not base64!");
+        let state: StateData = serde_json::from_value(json).unwrap();
+
+        let replayed = replay_intkey(&blocks);
+        let report = replayed.compare(&state);
+        // The address that can't be decoded isn't compared name by name
+        assert_eq!(1, report.get_differences().len());
+        let output: Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!("undecodable_data", output["differences"][0]["type"]);
+        assert_eq!(1, output["state"][intkey_address("num1")]["num1"]);
+        assert_eq!(5, output["num_transactions"]);
+    }
+
+    #[test]
+    fn reports_skipped_transactions() {
+        let blocks = read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let mut json = serde_json::to_value(&blocks).unwrap();
+        // Block 2 sets num2, so setting it again in block 3 is rejected
+        json["data"][2]["batches"][0]["transactions"][0]["payload"] = Value::from(payload("set", "num2", 7));
+        let replayed = replay_intkey(&serde_json::from_value(json).unwrap());
+        assert_eq!(1, replayed.get_skipped().len());
+        assert_eq!("3", replayed.get_skipped()[0].get_block_num());
+        assert_eq!(Some(2), replayed.get_value("num2"));
+
+        let state = read_state_data_from_file("example-blockchain/state.json").unwrap();
+        let text = replayed.compare(&state).format(false, false);
+        assert!(text.contains("| Skipped: set of num2, which is already set\n"));
+        assert!(text.ends_with("Replayed 4 intkey transactions from 6 blocks, skipping 1: found 1 difference with the state\n"));
+    }
+
+    #[test]
+    fn ignores_other_namespaces() {
+        let mut json = state_json();
+        json["data"].as_array_mut().unwrap().truncate(1);
+        let state: StateData = serde_json::from_value(json).unwrap();
+        assert!(replay_intkey(&BlockData::default()).compare(&state).is_consistent());
+    }
+}
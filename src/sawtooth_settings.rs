@@ -0,0 +1,217 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `sawtooth_settings` contains the built-in decoders for the Protocol Buffers messages of the
+//! `sawtooth_settings` transaction family, which every Sawtooth network uses to store its on-chain settings.
+//!
+//! - [SettingsPayloadDecoder](struct.SettingsPayloadDecoder.html) (`sawtooth_settings`) decodes the payload of
+//!   a settings transaction, such as the ones found in the genesis block. The proposal or vote carried inside
+//!   the payload is decoded as well, so the setting and its proposed value are shown directly.
+//! - [SettingsStateDecoder](struct.SettingsStateDecoder.html) (`sawtooth_settings_state`) decodes the `Setting`
+//!   message stored at the `000000` state addresses into its `key : value` entries, e.g.
+//!   `sawtooth.settings.vote.authorized_keys`.
+//!
+//! The messages mirror the ones defined in `settings.proto` and `setting.proto` of `sawtooth-core`.
+
+use decoded_value::{DecodedValue};
+use error::{DecodeError};
+use json_deserialize::{PayloadDecoder};
+
+use prost::{Enumeration, Message};
+
+use std::convert::TryFrom;
+
+/// The payload of a `sawtooth_settings` transaction.
+#[derive(Clone, PartialEq, Message)]
+struct SettingsPayload {
+    #[prost(enumeration = "Action", tag = "1")]
+    action: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    data: Vec<u8>,
+}
+
+/// What a `SettingsPayload` does, which decides how its `data` is encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Enumeration)]
+#[repr(i32)]
+enum Action {
+    Unset = 0,
+    Propose = 1,
+    Vote = 2,
+}
+
+/// The `data` of a `PROPOSE` payload.
+#[derive(Clone, PartialEq, Message)]
+struct SettingProposal {
+    #[prost(string, tag = "1")]
+    setting: String,
+    #[prost(string, tag = "2")]
+    value: String,
+    #[prost(string, tag = "3")]
+    nonce: String,
+}
+
+/// The `data` of a `VOTE` payload.
+#[derive(Clone, PartialEq, Message)]
+struct SettingVote {
+    #[prost(string, tag = "1")]
+    proposal_id: String,
+    #[prost(enumeration = "Vote", tag = "2")]
+    vote: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Enumeration)]
+#[repr(i32)]
+enum Vote {
+    Unset = 0,
+    Accept = 1,
+    Reject = 2,
+}
+
+/// The settings stored at a `000000` state address.
+#[derive(Clone, PartialEq, Message)]
+struct Setting {
+    #[prost(message, repeated, tag = "1")]
+    entries: Vec<Entry>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Entry {
+    #[prost(string, tag = "1")]
+    key: String,
+    #[prost(string, tag = "2")]
+    value: String,
+}
+
+/// Decodes the `SettingsPayload` of `sawtooth_settings` transactions.
+///
+/// A proposal decodes to its `action`, `setting`, `value` and `nonce`, and a vote decodes to
+/// its `action`, `proposal_id` and `vote`.
+#[derive(Debug, Default)]
+pub struct SettingsPayloadDecoder;
+
+impl PayloadDecoder for SettingsPayloadDecoder {
+    fn name(&self) -> &str {
+        "sawtooth_settings"
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<DecodedValue, DecodeError> {
+        let error = |reason: String| DecodeError::Deserialize { method: String::from(self.name()), reason };
+
+        let payload = SettingsPayload::decode(bytes).map_err(|e| error(e.to_string()))?;
+
+        let entries = match Action::try_from(payload.action) {
+            Ok(Action::Propose) => {
+                let proposal = SettingProposal::decode(payload.data.as_slice()).map_err(|e| error(e.to_string()))?;
+                vec![
+                    ("action", DecodedValue::from("PROPOSE")),
+                    ("setting", DecodedValue::Text(proposal.setting)),
+                    ("value", DecodedValue::Text(proposal.value)),
+                    ("nonce", DecodedValue::Text(proposal.nonce)),
+                ]
+            },
+            Ok(Action::Vote) => {
+                let vote = SettingVote::decode(payload.data.as_slice()).map_err(|e| error(e.to_string()))?;
+                let vote_name = match Vote::try_from(vote.vote) {
+                    Ok(Vote::Accept) => "ACCEPT",
+                    Ok(Vote::Reject) => "REJECT",
+                    _ => return Err(error(format!("unknown vote {}", vote.vote))),
+                };
+                vec![
+                    ("action", DecodedValue::from("VOTE")),
+                    ("proposal_id", DecodedValue::Text(vote.proposal_id)),
+                    ("vote", DecodedValue::from(vote_name)),
+                ]
+            },
+            _ => return Err(error(format!("unknown action {}", payload.action))),
+        };
+        Ok(DecodedValue::Map(entries.into_iter().map(|(key, val)| (DecodedValue::from(key), val)).collect()))
+    }
+}
+
+/// Decodes the `Setting` message stored at the `000000` state addresses into one entry per setting.
+#[derive(Debug, Default)]
+pub struct SettingsStateDecoder;
+
+impl PayloadDecoder for SettingsStateDecoder {
+    fn name(&self) -> &str {
+        "sawtooth_settings_state"
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<DecodedValue, DecodeError> {
+        let setting = Setting::decode(bytes)
+            .map_err(|error| DecodeError::Deserialize { method: String::from(self.name()), reason: error.to_string() })?;
+
+        Ok(DecodedValue::Map(setting.entries.into_iter()
+            .map(|entry| (DecodedValue::Text(entry.key), DecodedValue::Text(entry.value)))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod test_settings_payload {
+    use super::*;
+
+    #[test]
+    fn decodes_proposal() {
+        let proposal = SettingProposal { setting: String::from("sawtooth.settings.vote.authorized_keys"), value: String::from("02abc"), nonce: String::from("1") };
+        let payload = SettingsPayload { action: Action::Propose as i32, data: proposal.encode_to_vec() };
+
+        let val = SettingsPayloadDecoder.decode(&payload.encode_to_vec()).unwrap();
+        assert_eq!(Some("PROPOSE"), val.get("action").and_then(DecodedValue::as_text));
+        assert_eq!(Some("sawtooth.settings.vote.authorized_keys"), val.get("setting").and_then(DecodedValue::as_text));
+        assert_eq!(Some("02abc"), val.get("value").and_then(DecodedValue::as_text));
+        assert_eq!(Some("1"), val.get("nonce").and_then(DecodedValue::as_text));
+    }
+
+    #[test]
+    fn decodes_vote() {
+        let vote = SettingVote { proposal_id: String::from("abc123"), vote: Vote::Reject as i32 };
+        let payload = SettingsPayload { action: Action::Vote as i32, data: vote.encode_to_vec() };
+
+        let val = SettingsPayloadDecoder.decode(&payload.encode_to_vec()).unwrap();
+        assert_eq!(Some("VOTE"), val.get("action").and_then(DecodedValue::as_text));
+        assert_eq!(Some("abc123"), val.get("proposal_id").and_then(DecodedValue::as_text));
+        assert_eq!(Some("REJECT"), val.get("vote").and_then(DecodedValue::as_text));
+    }
+
+    #[test]
+    fn unset_action() {
+        let payload = SettingsPayload { action: Action::Unset as i32, data: Vec::new() };
+        match SettingsPayloadDecoder.decode(&payload.encode_to_vec()) {
+            Err(DecodeError::Deserialize { method, .. }) => assert_eq!("sawtooth_settings", method),
+            other => panic!("Expected Deserialize error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_protobuf() {
+        assert!(SettingsPayloadDecoder.decode(&[0xff, 0xff, 0xff]).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_settings_state {
+    use super::*;
+
+    #[test]
+    fn decodes_entries() {
+        let setting = Setting { entries: vec![
+            Entry { key: String::from("sawtooth.settings.vote.authorized_keys"), value: String::from("02abc") },
+            Entry { key: String::from("sawtooth.consensus.algorithm"), value: String::from("devmode") },
+        ]};
+
+        let val = SettingsStateDecoder.decode(&setting.encode_to_vec()).unwrap();
+        assert_eq!(Some("02abc"), val.get("sawtooth.settings.vote.authorized_keys").and_then(DecodedValue::as_text));
+        assert_eq!(Some("devmode"), val.get("sawtooth.consensus.algorithm").and_then(DecodedValue::as_text));
+    }
+
+    #[test]
+    fn invalid_protobuf() {
+        match SettingsStateDecoder.decode(&[0x0a, 0x05, 0x01]) {
+            Err(DecodeError::Deserialize { method, .. }) => assert_eq!("sawtooth_settings_state", method),
+            other => panic!("Expected Deserialize error, got {:?}", other),
+        }
+    }
+}
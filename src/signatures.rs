@@ -0,0 +1,244 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `signatures` checks the `header_signature` of blocks, batches and transactions against the `signer_public_key`
+//! in their headers.
+//!
+//! Sawtooth signs the Protocol Buffers encoding of a header, but the REST API only returns the header as JSON.
+//! The header is encoded again with the messages defined in `block.proto`, `batch.proto` and `transaction.proto` of
+//! `sawtooth-core`, which gives back the signed bytes because fields are always written in the order of their tags.
+//! The signature is then checked as a 64 byte compact secp256k1 signature over the SHA-256 hash of those bytes.
+//!
+//! The result of each check is a [SignatureStatus](enum.SignatureStatus.html):
+//!
+//! ```
+//! use rusty_saw_view::json_reader::{read_block_data_from_file};
+//! use rusty_saw_view::signatures::{SignatureStatus};
+//!
+//! let data = read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+//! assert_eq!(SignatureStatus::Valid, data.get_blocks()[0].check_signature());
+//! ```
+
+extern crate base64;
+extern crate k256;
+
+use json_structs::json_blocks::{BatchHeader, BlockHeader, TransactionHeader};
+
+use prost::{Message};
+
+use self::k256::ecdsa::{Signature, VerifyingKey};
+use self::k256::ecdsa::signature::{Verifier};
+
+/// The header of a block, as defined in `block.proto`.
+#[derive(Clone, PartialEq, Message)]
+struct BlockHeaderMessage {
+    #[prost(uint64, tag = "1")]
+    block_num: u64,
+    #[prost(string, tag = "2")]
+    previous_block_id: String,
+    #[prost(string, tag = "3")]
+    signer_public_key: String,
+    #[prost(string, repeated, tag = "4")]
+    batch_ids: Vec<String>,
+    #[prost(bytes = "vec", tag = "5")]
+    consensus: Vec<u8>,
+    #[prost(string, tag = "6")]
+    state_root_hash: String,
+}
+
+/// The header of a batch, as defined in `batch.proto`.
+#[derive(Clone, PartialEq, Message)]
+struct BatchHeaderMessage {
+    #[prost(string, tag = "1")]
+    signer_public_key: String,
+    #[prost(string, repeated, tag = "2")]
+    transaction_ids: Vec<String>,
+}
+
+/// The header of a transaction, as defined in `transaction.proto`.
+#[derive(Clone, PartialEq, Message)]
+struct TransactionHeaderMessage {
+    #[prost(string, tag = "1")]
+    batcher_public_key: String,
+    #[prost(string, repeated, tag = "2")]
+    dependencies: Vec<String>,
+    #[prost(string, tag = "3")]
+    family_name: String,
+    #[prost(string, tag = "4")]
+    family_version: String,
+    #[prost(string, repeated, tag = "5")]
+    inputs: Vec<String>,
+    #[prost(string, tag = "6")]
+    nonce: String,
+    #[prost(string, repeated, tag = "7")]
+    outputs: Vec<String>,
+    #[prost(string, tag = "9")]
+    payload_sha512: String,
+    #[prost(string, tag = "10")]
+    signer_public_key: String,
+}
+
+/// The result of checking the `header_signature` of a block, batch or transaction.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// The header was signed by the private key of its `signer_public_key`.
+    Valid,
+    /// The signature is well formed, but wasn't made over the header by the private key of its `signer_public_key`.
+    Invalid,
+    /// The `signer_public_key` isn't a secp256k1 public key in hex.
+    MalformedKey,
+    /// The `header_signature` isn't a 64 byte compact signature in hex.
+    MalformedSignature,
+    /// The header can't be encoded, because the block number isn't a number or the consensus isn't valid base64.
+    MalformedHeader,
+}
+
+impl SignatureStatus {
+    /// Returns true if the header was signed by its signer.
+    pub fn is_valid(self) -> bool {
+        self == SignatureStatus::Valid
+    }
+
+    /// Returns a short description of the status, e.g. `valid` or `malformed public key`.
+    pub fn describe(self) -> &'static str {
+        match self {
+            SignatureStatus::Valid => "valid",
+            SignatureStatus::Invalid => "invalid",
+            SignatureStatus::MalformedKey => "malformed public key",
+            SignatureStatus::MalformedSignature => "malformed signature",
+            SignatureStatus::MalformedHeader => "header can't be encoded",
+        }
+    }
+}
+
+/// Returns the Protocol Buffers encoding of a block header, or `None` if the block number isn't a number or the
+/// consensus isn't valid base64.
+pub fn encode_block_header(header: &BlockHeader) -> Option<Vec<u8>> {
+    let message = BlockHeaderMessage {
+        block_num: header.get_block_num().parse().ok()?,
+        previous_block_id: String::from(header.get_previous_block_id()),
+        signer_public_key: String::from(header.get_signer_public_key()),
+        batch_ids: header.get_batch_ids().to_vec(),
+        consensus: base64::decode(header.get_consensus()).ok()?,
+        state_root_hash: String::from(header.get_state_root_hash()),
+    };
+    Some(message.encode_to_vec())
+}
+
+/// Returns the Protocol Buffers encoding of a batch header.
+pub fn encode_batch_header(header: &BatchHeader) -> Vec<u8> {
+    BatchHeaderMessage {
+        signer_public_key: String::from(header.get_signer_public_key()),
+        transaction_ids: header.get_transaction_ids().to_vec(),
+    }.encode_to_vec()
+}
+
+/// Returns the Protocol Buffers encoding of a transaction header.
+pub fn encode_transaction_header(header: &TransactionHeader) -> Vec<u8> {
+    TransactionHeaderMessage {
+        batcher_public_key: String::from(header.get_batcher_public_key()),
+        dependencies: header.get_dependencies().to_vec(),
+        family_name: String::from(header.get_family_name()),
+        family_version: String::from(header.get_family_version()),
+        inputs: header.get_inputs().to_vec(),
+        nonce: String::from(header.get_nonce()),
+        outputs: header.get_outputs().to_vec(),
+        payload_sha512: String::from(header.get_payload_sha512()),
+        signer_public_key: String::from(header.get_signer_public_key()),
+    }.encode_to_vec()
+}
+
+/// Checks that `signature` was made over the SHA-256 hash of `header` by the private key of `public_key`. The key
+/// and the signature are written in hex, as they are in the JSON of every endpoint.
+pub fn verify_signature(header: &[u8], public_key: &str, signature: &str) -> SignatureStatus {
+    let key = match decode_hex(public_key).and_then(|bytes| VerifyingKey::from_sec1_bytes(&bytes).ok()) {
+        Some(key) => key,
+        None => return SignatureStatus::MalformedKey,
+    };
+    let signature = match decode_hex(signature).and_then(|bytes| Signature::from_slice(&bytes).ok()) {
+        Some(signature) => signature,
+        None => return SignatureStatus::MalformedSignature,
+    };
+    match key.verify(header, &signature) {
+        Ok(()) => SignatureStatus::Valid,
+        Err(_) => SignatureStatus::Invalid,
+    }
+}
+
+/// Returns the bytes written in `hex`, or `None` if it isn't an even number of hex digits.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len()).step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test_signatures {
+    use super::*;
+
+    use json_reader::{read_block_data_from_file};
+    use json_structs::json_blocks::{BlockData};
+
+    fn example() -> BlockData {
+        read_block_data_from_file("example-blockchain/blocks.json").unwrap()
+    }
+
+    #[test]
+    fn example_signatures_are_valid() {
+        for block in example().get_blocks() {
+            assert_eq!(SignatureStatus::Valid, block.check_signature());
+            for batch in block.get_batches() {
+                assert_eq!(SignatureStatus::Valid, batch.check_signature());
+                for txn in batch.get_transactions() {
+                    assert_eq!(SignatureStatus::Valid, txn.check_signature());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn changed_header_is_invalid() {
+        let data = example();
+        let block = &data.get_blocks()[0];
+        let mut header = encode_block_header(block.get_header()).unwrap();
+        header.push(0);
+        assert_eq!(SignatureStatus::Invalid,
+            verify_signature(&header, block.get_header().get_signer_public_key(), block.get_header_signature()));
+    }
+
+    #[test]
+    fn signature_by_another_key_is_invalid() {
+        let data = example();
+        let block = &data.get_blocks()[0];
+        let batch = &block.get_batches()[0];
+        assert_ne!(block.get_header().get_signer_public_key(), batch.get_header().get_signer_public_key());
+        assert_eq!(SignatureStatus::Invalid, verify_signature(&encode_batch_header(batch.get_header()),
+            block.get_header().get_signer_public_key(), batch.get_header_signature()));
+    }
+
+    #[test]
+    fn malformed_key_and_signature() {
+        let data = example();
+        let block = &data.get_blocks()[0];
+        let header = encode_block_header(block.get_header()).unwrap();
+        assert_eq!(SignatureStatus::MalformedKey, verify_signature(&header, "02abc", block.get_header_signature()));
+        assert_eq!(SignatureStatus::MalformedKey, verify_signature(&header, "zz", block.get_header_signature()));
+        assert_eq!(SignatureStatus::MalformedSignature,
+            verify_signature(&header, block.get_header().get_signer_public_key(), "abcd"));
+    }
+
+    #[test]
+    fn decodes_hex() {
+        assert_eq!(Some(vec![0x01, 0xab, 0xff]), decode_hex("01abFF"));
+        assert_eq!(None, decode_hex("123"));
+        assert_eq!(None, decode_hex("0g"));
+        assert_eq!(None, decode_hex("éé"));
+        assert_eq!(None, decode_hex("+f"));
+    }
+}
@@ -0,0 +1,581 @@
+// Copyright (c) 2018 Joseph Venetucci
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE.md in the
+// source distribution of this software for license terms.
+
+//! `verify` checks that a [BlockData](../json_structs/json_blocks/struct.BlockData.html) holds together as a chain,
+//! which is useful to find out if an export was edited, truncated or corrupted.
+//!
+//! [verify_blocks](fn.verify_blocks.html) walks the blocks from the newest to the oldest and checks that:
+//! - each block points to the block before it with its `previous_block_id`,
+//! - the block numbers count down by one,
+//! - the `batch_ids` in each block header match the batches in the block, in order,
+//! - the `transaction_ids` in each batch header match the transactions in the batch, in order,
+//! - the payload of each transaction hashes to the `payload_sha512` in its header,
+//! - every header was [signed](../signatures/index.html) by the private key of its `signer_public_key`,
+//! - each transaction names the signer of its batch as its `batcher_public_key`.
+//!
+//! Every [Violation](struct.Violation.html) found is collected into a [Verification](struct.Verification.html), along
+//! with the [Location](struct.Location.html) it was found at, rather than stopping at the first one.
+
+extern crate serde_json;
+
+use colored::*;
+use json_structs::{label_id, paint};
+use json_structs::json_blocks::{Block, BlockData};
+use signatures::{SignatureStatus};
+
+/// The result of verifying blocks, with every violation that was found.
+#[derive(Serialize, Debug, Default)]
+pub struct Verification {
+    num_blocks: usize,
+    num_batches: usize,
+    num_transactions: usize,
+    violations: Vec<Violation>,
+}
+
+impl Verification {
+    /// Returns the violations that were found, from the newest block to the oldest.
+    pub fn get_violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Returns true if no violations were found.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Display each violation, followed by a summary of what was checked.
+    /// Uses colored text so use this for terminal printing.
+    ///
+    /// Setting `full_id` to false will only print the first 6 and last 4 characters of IDs, otherwise the full
+    /// string is shown.
+    pub fn display(&self, full_id: bool) {
+        print!("{}", self.format(full_id, true));
+    }
+
+    /// Display each violation, followed by a summary of what was checked.
+    /// No terminal color, so use this for piping to a file.
+    pub fn display_no_color(&self, full_id: bool) {
+        print!("{}", self.format(full_id, false));
+    }
+
+    /// Returns the verification as pretty printed JSON, with the number of blocks, batches and transactions checked,
+    /// and the `violations` found. Each violation has its `location` and `problem`, where the `type` of the
+    /// problem names the check that failed.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a verification can always be written as JSON")
+    }
+
+    fn format(&self, full_id: bool, color: bool) -> String {
+        let mut out = String::new();
+        for violation in self.violations.iter() {
+            out.push_str(&format!("|{}\n", violation.location.describe(full_id)));
+            out.push_str(&format!("| {}\n", paint(&violation.problem.describe(full_id), Color::Red, color)));
+        }
+        let checked = format!("Checked {} blocks, {} batches and {} transactions", self.num_blocks, self.num_batches, self.num_transactions);
+        match self.violations.len() {
+            0 => out.push_str(&format!("{}: {}\n", checked, paint("no problems found", Color::Green, color))),
+            1 => out.push_str(&format!("{}: {}\n", checked, paint("found 1 problem", Color::Red, color))),
+            count => out.push_str(&format!("{}: {}\n", checked, paint(&format!("found {} problems", count), Color::Red, color))),
+        }
+        out
+    }
+}
+
+/// A problem found while verifying blocks, and where it was found.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Violation {
+    location: Location,
+    problem: Problem,
+}
+
+impl Violation {
+    /// Returns where the problem was found.
+    pub fn get_location(&self) -> &Location {
+        &self.location
+    }
+
+    /// Returns the problem that was found.
+    pub fn get_problem(&self) -> &Problem {
+        &self.problem
+    }
+}
+
+/// The block, and optionally the batch and transaction within it, that a [Violation](struct.Violation.html) was
+/// found at. Batches and transactions are counted from 0 in the order they are listed, the same way they are
+/// numbered when blocks are displayed.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Location {
+    block_num: String,
+    block_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    batch: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transaction: Option<usize>,
+}
+
+impl Location {
+    /// Returns the location of `block`.
+    fn of_block(block: &Block) -> Location {
+        Location {
+            block_num: String::from(block.get_header().get_block_num()),
+            block_id: String::from(block.get_header_signature()),
+            batch: None,
+            transaction: None,
+        }
+    }
+
+    /// Returns the location of the batch at index `batch` of `block`.
+    fn of_batch(block: &Block, batch: usize) -> Location {
+        Location { batch: Some(batch), ..Location::of_block(block) }
+    }
+
+    /// Returns the location of the transaction at index `transaction` of the batch at index `batch` of `block`.
+    fn of_transaction(block: &Block, batch: usize, transaction: usize) -> Location {
+        Location { transaction: Some(transaction), ..Location::of_batch(block, batch) }
+    }
+
+    /// Returns the number of the block, as written in its header.
+    pub fn get_block_num(&self) -> &str {
+        &self.block_num
+    }
+
+    /// Returns the index of the batch within the block, if the violation is about a batch.
+    pub fn get_batch(&self) -> Option<usize> {
+        self.batch
+    }
+
+    /// Returns the index of the transaction within the batch, if the violation is about a transaction.
+    pub fn get_transaction(&self) -> Option<usize> {
+        self.transaction
+    }
+
+    /// Returns the location as e.g. `Block 5 (0b5874...4771), Batch 0`.
+    fn describe(&self, full_id: bool) -> String {
+        let mut out = format!("Block {} ({})", self.block_num, label_id(&self.block_id, full_id));
+        if let Some(batch) = self.batch {
+            out.push_str(&format!(", Batch {}", batch));
+        }
+        if let Some(transaction) = self.transaction {
+            out.push_str(&format!(", Transaction {}", transaction));
+        }
+        out
+    }
+}
+
+/// The checks that can fail when verifying blocks.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Problem {
+    /// The `previous_block_id` of the block isn't the ID of the block listed before it in the chain.
+    PreviousBlockId { expected: String, found: String },
+    /// The number of the block isn't one more than the number of the block before it.
+    BlockNum { previous: String, found: String },
+    /// The block header lists a batch that isn't in the block.
+    MissingBatch { id: String },
+    /// The block holds a batch that isn't listed in its header.
+    UnlistedBatch { id: String },
+    /// A batch is listed in the block header, or held in the block, more than once.
+    RepeatedBatch { id: String, listed: usize, present: usize },
+    /// The block holds the batches listed in its header, but in a different order.
+    BatchOrder,
+    /// The batch header lists a transaction that isn't in the batch.
+    MissingTransaction { id: String },
+    /// The batch holds a transaction that isn't listed in its header.
+    UnlistedTransaction { id: String },
+    /// A transaction is listed in the batch header, or held in the batch, more than once.
+    RepeatedTransaction { id: String, listed: usize, present: usize },
+    /// The batch holds the transactions listed in its header, but in a different order.
+    TransactionOrder,
+    /// The payload of the transaction doesn't hash to the `payload_sha512` in its header. `found` is the hash of the
+    /// payload, or `None` if the payload isn't valid base64.
+    PayloadHash { id: String, expected: String, found: Option<String> },
+    /// The `header_signature` of the block, batch or transaction with the ID `id` wasn't made by its signer.
+    Signature { id: String, status: SignatureStatus },
+    /// The `batcher_public_key` of the transaction isn't the `signer_public_key` of the batch it's in.
+    BatcherKey { id: String, expected: String, found: String },
+}
+
+impl Problem {
+    /// Returns a sentence that describes the problem.
+    fn describe(&self, full_id: bool) -> String {
+        match *self {
+            Problem::PreviousBlockId { ref expected, ref found } =>
+                format!("Previous block ID {} doesn't match the ID of the block before it, {}", label_id(found, full_id), label_id(expected, full_id)),
+            Problem::BlockNum { ref previous, ref found } =>
+                format!("Block number {} doesn't follow block number {}", found, previous),
+            Problem::MissingBatch { ref id } =>
+                format!("Batch {} is listed in the header but isn't in the block", label_id(id, full_id)),
+            Problem::UnlistedBatch { ref id } =>
+                format!("Batch {} is in the block but isn't listed in the header", label_id(id, full_id)),
+            Problem::RepeatedBatch { ref id, listed, present } =>
+                format!("Batch {} is listed {} times in the header, and is in the block {} times", label_id(id, full_id), listed, present),
+            Problem::BatchOrder =>
+                String::from("The batches aren't in the order listed in the header"),
+            Problem::MissingTransaction { ref id } =>
+                format!("Transaction {} is listed in the header but isn't in the batch", label_id(id, full_id)),
+            Problem::UnlistedTransaction { ref id } =>
+                format!("Transaction {} is in the batch but isn't listed in the header", label_id(id, full_id)),
+            Problem::RepeatedTransaction { ref id, listed, present } =>
+                format!("Transaction {} is listed {} times in the header, and is in the batch {} times", label_id(id, full_id), listed, present),
+            Problem::TransactionOrder =>
+                String::from("The transactions aren't in the order listed in the header"),
+            Problem::PayloadHash { ref id, ref expected, found: Some(ref found) } =>
+                format!("Payload of transaction {} hashes to {}, not the payload SHA-512 {} in the header", label_id(id, full_id),
+                    label_id(found, full_id), label_id(expected, full_id)),
+            Problem::PayloadHash { ref id, found: None, .. } =>
+                format!("Payload of transaction {} isn't valid base64", label_id(id, full_id)),
+            Problem::Signature { ref id, status: SignatureStatus::Invalid } =>
+                format!("Signature {} wasn't made over the header by its signer", label_id(id, full_id)),
+            Problem::Signature { ref id, status } =>
+                format!("Signature {} can't be checked: {}", label_id(id, full_id), status.describe()),
+            Problem::BatcherKey { ref id, ref expected, ref found } =>
+                format!("Transaction {} names {} as its batcher, but the batch was signed by {}", label_id(id, full_id),
+                    label_id(found, full_id), label_id(expected, full_id)),
+        }
+    }
+}
+
+/// Verifies that `data` holds together as a chain, and returns every violation found.
+///
+/// Blocks are expected to be listed from the newest to the oldest, as the `/blocks` endpoint lists them, but a list
+/// read with `reverse` from the oldest to the newest is verified the same way. The list doesn't have to start at the
+/// head of the chain or end at the genesis block.
+pub fn verify_blocks(data: &BlockData) -> Verification {
+    let mut blocks: Vec<&Block> = data.get_blocks().iter().collect();
+    if let (Some(first), Some(last)) = (blocks.first(), blocks.last()) {
+        if let (Ok(first_num), Ok(last_num)) = (first.get_header().get_block_num().parse::<u64>(), last.get_header().get_block_num().parse::<u64>()) {
+            if first_num < last_num {
+                blocks.reverse();
+            }
+        }
+    }
+
+    let mut verification = Verification { num_blocks: blocks.len(), ..Verification::default() };
+    for (index, block) in blocks.iter().enumerate() {
+        if let Some(previous) = blocks.get(index + 1) {
+            verify_link(block, previous, &mut verification.violations);
+        }
+        verify_batches(block, &mut verification);
+    }
+    verification
+}
+
+/// Checks that `block` comes right after `previous` in the chain.
+fn verify_link(block: &Block, previous: &Block, violations: &mut Vec<Violation>) {
+    let header = block.get_header();
+    if header.get_previous_block_id() != previous.get_header_signature() {
+        violations.push(Violation {
+            location: Location::of_block(block),
+            problem: Problem::PreviousBlockId {
+                expected: String::from(previous.get_header_signature()),
+                found: String::from(header.get_previous_block_id()),
+            },
+        });
+    }
+
+    let follows = match (header.get_block_num().parse::<u64>(), previous.get_header().get_block_num().parse::<u64>()) {
+        (Ok(num), Ok(previous_num)) => previous_num.checked_add(1) == Some(num),
+        _ => false,
+    };
+    if !follows {
+        violations.push(Violation {
+            location: Location::of_block(block),
+            problem: Problem::BlockNum {
+                previous: String::from(previous.get_header().get_block_num()),
+                found: String::from(header.get_block_num()),
+            },
+        });
+    }
+}
+
+/// Checks that the batches of `block` match its header, that the transactions of each batch match the header of the
+/// batch, and that each transaction has the right payload and batcher. Every header must be signed by its signer.
+fn verify_batches(block: &Block, verification: &mut Verification) {
+    let batch_ids: Vec<&str> = block.get_batches().iter().map(|batch| batch.get_header_signature()).collect();
+    for problem in compare_ids(block.get_header().get_batch_ids(), &batch_ids, |id| Problem::MissingBatch { id }, |id| Problem::UnlistedBatch { id },
+                               |id, listed, present| Problem::RepeatedBatch { id, listed, present }, Problem::BatchOrder) {
+        verification.violations.push(Violation { location: Location::of_block(block), problem });
+    }
+    let status = block.check_signature();
+    if !status.is_valid() {
+        verification.violations.push(Violation {
+            location: Location::of_block(block),
+            problem: Problem::Signature { id: String::from(block.get_header_signature()), status },
+        });
+    }
+
+    for (index, batch) in block.get_batches().iter().enumerate() {
+        let txn_ids: Vec<&str> = batch.get_transactions().iter().map(|txn| txn.get_header_signature()).collect();
+        for problem in compare_ids(batch.get_header().get_transaction_ids(), &txn_ids, |id| Problem::MissingTransaction { id }, |id| Problem::UnlistedTransaction { id },
+                                   |id, listed, present| Problem::RepeatedTransaction { id, listed, present }, Problem::TransactionOrder) {
+            verification.violations.push(Violation { location: Location::of_batch(block, index), problem });
+        }
+        let status = batch.check_signature();
+        if !status.is_valid() {
+            verification.violations.push(Violation {
+                location: Location::of_batch(block, index),
+                problem: Problem::Signature { id: String::from(batch.get_header_signature()), status },
+            });
+        }
+        for (txn_index, txn) in batch.get_transactions().iter().enumerate() {
+            let status = txn.check_signature();
+            if !status.is_valid() {
+                verification.violations.push(Violation {
+                    location: Location::of_transaction(block, index, txn_index),
+                    problem: Problem::Signature { id: String::from(txn.get_header_signature()), status },
+                });
+            }
+            if !batch.is_batcher_of(txn) {
+                verification.violations.push(Violation {
+                    location: Location::of_transaction(block, index, txn_index),
+                    problem: Problem::BatcherKey {
+                        id: String::from(txn.get_header_signature()),
+                        expected: String::from(batch.get_header().get_signer_public_key()),
+                        found: String::from(txn.get_header().get_batcher_public_key()),
+                    },
+                });
+            }
+            if !txn.payload_matches_hash() {
+                verification.violations.push(Violation {
+                    location: Location::of_transaction(block, index, txn_index),
+                    problem: Problem::PayloadHash {
+                        id: String::from(txn.get_header_signature()),
+                        expected: String::from(txn.get_header().get_payload_sha512()),
+                        found: txn.hash_payload(),
+                    },
+                });
+            }
+        }
+        verification.num_batches += 1;
+        verification.num_transactions += txn_ids.len();
+    }
+}
+
+/// Compares the IDs `listed` in a header with the IDs of the entities `present`. Returns a `missing` problem for every
+/// listed ID that isn't present, an `unlisted` problem for every present ID that isn't listed, a `repeated` problem
+/// with both counts for every ID that is listed or present more than once, and `order` if the same IDs are in
+/// a different order.
+fn compare_ids<M, U, R>(listed: &[String], present: &[&str], missing: M, unlisted: U, repeated: R, order: Problem) -> Vec<Problem>
+    where M: Fn(String) -> Problem, U: Fn(String) -> Problem, R: Fn(String, usize, usize) -> Problem {
+    let mut problems = Vec::new();
+    let mut compared: Vec<&str> = Vec::new();
+    for id in listed.iter().map(String::as_str).chain(present.iter().cloned()) {
+        if compared.contains(&id) {
+            continue;
+        }
+        compared.push(id);
+        let num_listed = listed.iter().filter(|listed_id| *listed_id == id).count();
+        let num_present = present.iter().filter(|&&present_id| present_id == id).count();
+        match (num_listed, num_present) {
+            (_, 0) => problems.push(missing(String::from(id))),
+            (0, _) => problems.push(unlisted(String::from(id))),
+            (1, 1) => (),
+            (num_listed, num_present) => problems.push(repeated(String::from(id), num_listed, num_present)),
+        }
+    }
+
+    if problems.is_empty() && listed.iter().zip(present.iter()).any(|(listed_id, &id)| listed_id != id) {
+        problems.push(order);
+    }
+    problems
+}
+
+#[cfg(test)]
+mod test_verify {
+    use super::*;
+    use super::serde_json::Value;
+    use json_structs::sha512_hex;
+
+    /// Returns the example blocks as JSON, for tests to tamper with.
+    fn example_json() -> Value {
+        serde_json::from_str(&::std::fs::read_to_string("example-blockchain/blocks.json").unwrap()).unwrap()
+    }
+
+    fn verify_json(json: Value) -> Verification {
+        verify_blocks(&serde_json::from_value(json).unwrap())
+    }
+
+    #[test]
+    fn example_chain_is_valid() {
+        let data = ::json_reader::read_block_data_from_file("example-blockchain/blocks.json").unwrap();
+        let verification = verify_blocks(&data);
+        assert!(verification.is_valid());
+        assert_eq!(6, verification.num_blocks);
+        assert_eq!(6, verification.num_batches);
+        assert_eq!(6, verification.num_transactions);
+    }
+
+    #[test]
+    fn reversed_chain_is_valid() {
+        let mut json = example_json();
+        json["data"].as_array_mut().unwrap().reverse();
+        assert!(verify_json(json).is_valid());
+    }
+
+    #[test]
+    fn empty_chain_is_valid() {
+        assert!(verify_blocks(&BlockData::default()).is_valid());
+    }
+
+    #[test]
+    fn broken_link() {
+        let mut json = example_json();
+        json["data"][1]["header"]["previous_block_id"] = Value::from("ffff");
+        let verification = verify_json(json);
+        // Changing the header also breaks its signature
+        assert_eq!(2, verification.get_violations().len());
+        let violation = &verification.get_violations()[0];
+        assert_eq!("4", violation.get_location().get_block_num());
+        match *violation.get_problem() {
+            Problem::PreviousBlockId { ref found, .. } => assert_eq!("ffff", found),
+            ref other => panic!("Expected PreviousBlockId, got {:?}", other),
+        }
+        match *verification.get_violations()[1].get_problem() {
+            Problem::Signature { status, .. } => assert_eq!(SignatureStatus::Invalid, status),
+            ref other => panic!("Expected Signature, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn missing_block() {
+        let mut json = example_json();
+        json["data"].as_array_mut().unwrap().remove(2);
+        let verification = verify_json(json);
+        // Block 4 points to the missing block 3, and its number doesn't follow block 2
+        assert_eq!(2, verification.get_violations().len());
+        assert!(verification.get_violations().iter().all(|violation| violation.get_location().get_block_num() == "4"));
+        assert_eq!(Problem::BlockNum { previous: String::from("2"), found: String::from("4") }, *verification.get_violations()[1].get_problem());
+    }
+
+    #[test]
+    fn missing_and_unlisted_batches() {
+        let mut json = example_json();
+        json["data"][0]["header"]["batch_ids"][0] = Value::from("aaaa");
+        let verification = verify_json(json);
+        let problems: Vec<&Problem> = verification.get_violations().iter().map(|violation| violation.get_problem()).collect();
+        assert_eq!(3, problems.len());
+        assert_eq!(Problem::MissingBatch { id: String::from("aaaa") }, *problems[0]);
+        match *problems[1] {
+            Problem::UnlistedBatch { .. } => (),
+            ref other => panic!("Expected UnlistedBatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn repeated_batch_ids() {
+        let mut json = example_json();
+        let id = json["data"][0]["header"]["batch_ids"][0].clone();
+        json["data"][0]["header"]["batch_ids"] = serde_json::json!([id, id]);
+        let verification = verify_json(json);
+        // The block header was changed, so its signature doesn't match either
+        assert_eq!(2, verification.get_violations().len());
+        assert_eq!(Problem::RepeatedBatch { id: String::from(id.as_str().unwrap()), listed: 2, present: 1 },
+                   *verification.get_violations()[0].get_problem());
+    }
+
+    #[test]
+    fn transactions_out_of_order() {
+        let mut json = example_json();
+        let batch = &mut json["data"][0]["batches"][0];
+        let txn = batch["transactions"][0].clone();
+        batch["transactions"].as_array_mut().unwrap().push(txn.clone());
+        batch["transactions"][0]["header_signature"] = Value::from("bbbb");
+        batch["header"]["transaction_ids"] = serde_json::json!([txn["header_signature"], "bbbb"]);
+        let verification = verify_json(json);
+        // The batch header was changed, and `bbbb` isn't a signature
+        assert_eq!(3, verification.get_violations().len());
+        assert_eq!(Problem::TransactionOrder, *verification.get_violations()[0].get_problem());
+        assert_eq!(Some(0), verification.get_violations()[0].get_location().get_batch());
+    }
+
+    #[test]
+    fn changed_payload() {
+        let mut json = example_json();
+        json["data"][1]["batches"][0]["transactions"][0]["payload"] = Value::from("oWROYW1lYWE=");
+        let verification = verify_json(json);
+        assert_eq!(1, verification.get_violations().len());
+        let violation = &verification.get_violations()[0];
+        assert_eq!("4", violation.get_location().get_block_num());
+        assert_eq!((Some(0), Some(0)), (violation.get_location().get_batch(), violation.get_location().get_transaction()));
+        match *violation.get_problem() {
+            Problem::PayloadHash { ref found, .. } => assert_eq!(Some(sha512_hex(&[0xa1, 0x64, 0x4e, 0x61, 0x6d, 0x65, 0x61, 0x61])), *found),
+            ref other => panic!("unexpected problem {:?}", other),
+        }
+    }
+
+    #[test]
+    fn payload_not_base64() {
+        let mut json = example_json();
+        json["data"][0]["batches"][0]["transactions"][0]["payload"] = Value::from("not base64!");
+        let verification = verify_json(json);
+        let text = verification.format(false, false);
+        assert!(text.starts_with("|Block 5 (0b5874...4771), Batch 0, Transaction 0\n| Payload of transaction "));
+        assert!(text.contains(" isn't valid base64\n"));
+        let output: Value = serde_json::from_str(&verification.to_json()).unwrap();
+        assert_eq!("payload_hash", output["violations"][0]["problem"]["type"]);
+        assert!(output["violations"][0]["problem"]["found"].is_null());
+        assert_eq!(0, output["violations"][0]["location"]["transaction"]);
+    }
+
+    #[test]
+    fn changed_transaction_header() {
+        let mut json = example_json();
+        let txn = &mut json["data"][2]["batches"][0]["transactions"][0];
+        let id = String::from(txn["header_signature"].as_str().unwrap());
+        txn["header"]["nonce"] = Value::from("0");
+        let verification = verify_json(json);
+        assert_eq!(1, verification.get_violations().len());
+        let violation = &verification.get_violations()[0];
+        assert_eq!(("3", Some(0), Some(0)), (violation.get_location().get_block_num(), violation.get_location().get_batch(),
+            violation.get_location().get_transaction()));
+        assert_eq!(Problem::Signature { id, status: SignatureStatus::Invalid }, *violation.get_problem());
+    }
+
+    #[test]
+    fn wrong_batcher() {
+        let mut json = example_json();
+        let batch = &mut json["data"][0]["batches"][0];
+        let block_signer = Value::from("03f0ed7e96f4eae4fb9e6cf5b66ab2ee64b8a3cd8e2f9a2e1c5a3d0bbab1e0a3f5");
+        batch["transactions"][0]["header"]["batcher_public_key"] = block_signer.clone();
+        let verification = verify_json(json);
+        let problems: Vec<&Problem> = verification.get_violations().iter().map(|violation| violation.get_problem()).collect();
+        assert_eq!(2, problems.len());
+        match *problems[1] {
+            Problem::BatcherKey { ref found, .. } => assert_eq!(block_signer, *found),
+            ref other => panic!("Expected BatcherKey, got {:?}", other),
+        }
+        let text = verification.format(false, false);
+        assert!(text.contains("| Transaction 62980d...16f0 names 03f0ed...a3f5 as its batcher, but the batch was signed by 03150e...96b4\n"));
+    }
+
+    #[test]
+    fn malformed_signature() {
+        let mut json = example_json();
+        json["data"][0]["header"]["signer_public_key"] = Value::from("nope");
+        let output: Value = serde_json::from_str(&verify_json(json).to_json()).unwrap();
+        assert_eq!("signature", output["violations"][0]["problem"]["type"]);
+        assert_eq!("malformed_key", output["violations"][0]["problem"]["status"]);
+    }
+
+    #[test]
+    fn formats_violations_and_summary() {
+        let mut json = example_json();
+        json["data"][0]["header"]["block_num"] = Value::from("7");
+        let text = verify_json(json).format(false, false);
+        assert_eq!("|Block 7 (0b5874...4771)\n| Block number 7 doesn't follow block number 4\n\
+            |Block 7 (0b5874...4771)\n| Signature 0b5874...4771 wasn't made over the header by its signer\n\
+            Checked 6 blocks, 6 batches and 6 transactions: found 2 problems\n", text);
+    }
+
+    #[test]
+    fn json_names_the_problem() {
+        let mut json = example_json();
+        json["data"][0]["header"]["batch_ids"] = serde_json::json!([]);
+        let output: Value = serde_json::from_str(&verify_json(json).to_json()).unwrap();
+        assert_eq!("unlisted_batch", output["violations"][0]["problem"]["type"]);
+        assert!(output["violations"][0]["location"]["batch"].is_null());
+        assert_eq!(6, output["num_blocks"]);
+    }
+}